@@ -0,0 +1,48 @@
+/// The relative tolerance used by default when comparing floating point values
+/// parsed from documents, absorbing the rounding of their textual representation.
+pub const DEFAULT_RELATIVE_TOLERANCE: f64 = 1e-9;
+
+pub trait ApproxEq {
+    /// Returns whether the two values are equal within the provided relative tolerance.
+    ///
+    /// The tolerance is relative to the largest absolute value of the two.
+    ///
+    /// # Arguments
+    /// * `other` - The value to compare with.
+    /// * `relative_tolerance` - The relative tolerance of the comparison.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// assert!(381.0795_f64.approx_eq(&381.07950000001, DEFAULT_RELATIVE_TOLERANCE));
+    /// assert!(!381.0795_f64.approx_eq(&381.0796, DEFAULT_RELATIVE_TOLERANCE));
+    /// assert!(381.0795_f32.approx_eq(&381.0795, 0.0));
+    /// ```
+    fn approx_eq(&self, other: &Self, relative_tolerance: f64) -> bool;
+}
+
+impl ApproxEq for f32 {
+    fn approx_eq(&self, other: &Self, relative_tolerance: f64) -> bool {
+        self == other
+            || (self - other).abs() <= relative_tolerance as f32 * self.abs().max(other.abs())
+    }
+}
+
+impl ApproxEq for f64 {
+    fn approx_eq(&self, other: &Self, relative_tolerance: f64) -> bool {
+        self == other || (self - other).abs() <= relative_tolerance * self.abs().max(other.abs())
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl ApproxEq for rust_decimal::Decimal {
+    fn approx_eq(&self, other: &Self, relative_tolerance: f64) -> bool {
+        // A tolerance that cannot be represented as a decimal, such as a NaN,
+        // falls back to an exact comparison.
+        let relative_tolerance = rust_decimal::Decimal::try_from(relative_tolerance)
+            .unwrap_or(rust_decimal::Decimal::ZERO);
+        self == other || (self - other).abs() <= relative_tolerance * self.abs().max(other.abs())
+    }
+}
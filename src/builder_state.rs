@@ -0,0 +1,21 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The state of a [`MascotGenericFormatBuilder`](crate::prelude::MascotGenericFormatBuilder)
+/// while digesting the lines of an entry.
+pub enum BuilderState {
+    /// No section was opened yet.
+    Empty,
+    /// A section is open, and its `MSLEVEL` is not known yet.
+    MetadataOpen,
+    /// A section with `MSLEVEL=1` is open.
+    FirstLevel,
+    /// A section marked with `SCANS=-1` was closed, and the following
+    /// section of the same entry is expected.
+    AwaitingSecondLevel,
+    /// A section with `MSLEVEL=2` is open.
+    SecondLevel,
+    /// All of the sections are closed, and the entry can be built.
+    Buildable,
+    /// All of the sections are closed, but the entry cannot be built,
+    /// for instance because of a missing required field.
+    Corrupted,
+}
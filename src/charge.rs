@@ -1,7 +1,14 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
 use std::str::FromStr;
 
+/// The largest magnitude of the charges that can be represented by a [`Charge`].
+pub const MAX_CHARGE_MAGNITUDE: u8 = 4;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Charge {
+    Zero,
     One,
     OnePlus,
     Two,
@@ -10,6 +17,10 @@ pub enum Charge {
     ThreePlus,
     Four,
     FourPlus,
+    OneMinus,
+    TwoMinus,
+    ThreeMinus,
+    FourMinus,
 }
 
 impl FromStr for Charge {
@@ -17,6 +28,11 @@ impl FromStr for Charge {
 
     /// Parses a string to a [`Charge`].
     /// 
+    /// The charge value may carry exactly one sign, either leading or
+    /// trailing, so that both `CHARGE=2+` and `CHARGE=+2` are parsed as
+    /// [`Charge::TwoPlus`]. Values with more than one sign are rejected,
+    /// as is a zero charge, which no precursor ion can have.
+    /// 
     /// # Arguments
     /// * `s` - The string to parse.
     /// 
@@ -34,37 +50,80 @@ impl FromStr for Charge {
     /// assert_eq!(Charge::from_str("CHARGE=3+").unwrap(), Charge::ThreePlus);
     /// assert_eq!(Charge::from_str("CHARGE=4").unwrap(), Charge::Four);
     /// assert_eq!(Charge::from_str("CHARGE=4+").unwrap(), Charge::FourPlus);
+    /// assert_eq!(Charge::from_str("CHARGE=1-").unwrap(), Charge::OneMinus);
+    /// assert_eq!(Charge::from_str("CHARGE=4-").unwrap(), Charge::FourMinus);
+    /// assert_eq!(Charge::from_str("CHARGE=+2").unwrap(), Charge::TwoPlus);
+    /// assert_eq!(Charge::from_str("CHARGE=-2").unwrap(), Charge::TwoMinus);
     /// 
     /// assert!(Charge::from_str("CHARGE=5+").is_err());
+    /// assert!(Charge::from_str("CHARGE=0").is_err());
+    /// assert!(Charge::from_str("CHARGE=+0").is_err());
+    /// assert!(Charge::from_str("CHARGE=+").is_err());
+    /// assert!(Charge::from_str("CHARGE=+2-").is_err());
+    /// assert!(Charge::from_str("CHARGE=--2").is_err());
+    /// assert!(Charge::from_str("CHARGE=2+-").is_err());
+    /// assert!(Charge::from_str("CHARGE=2++").is_err());
+    /// assert!(Charge::from_str("2+").is_err());
     /// 
     /// ```
     /// 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "CHARGE=1" => Ok(Self::One),
-            "CHARGE=1+" => Ok(Self::OnePlus),
-            "CHARGE=2" => Ok(Self::Two),
-            "CHARGE=2+" => Ok(Self::TwoPlus),
-            "CHARGE=3" => Ok(Self::Three),
-            "CHARGE=3+" => Ok(Self::ThreePlus),
-            "CHARGE=4" => Ok(Self::Four),
-            "CHARGE=4+" => Ok(Self::FourPlus),
+        let value = s
+            .strip_prefix("CHARGE=")
+            .ok_or_else(|| format!("Could not parse charge: {}", s))?;
+
+        // We split the value into its optional leading sign,
+        // its magnitude and its optional trailing sign.
+        let (leading_sign, value) = match value.strip_prefix(['+', '-']) {
+            Some(stripped) => (value.chars().next(), stripped),
+            None => (None, value),
+        };
+        let (magnitude, trailing_sign) = match value.strip_suffix(['+', '-']) {
+            Some(stripped) => (stripped, value.chars().last()),
+            None => (value, None),
+        };
+
+        let sign = match (leading_sign, trailing_sign) {
+            (Some(_), Some(_)) => {
+                return Err(format!(
+                    concat!(
+                        "Could not parse charge: the charge \"{}\" has both a leading ",
+                        "and a trailing sign, while at most one sign is allowed."
+                    ),
+                    s
+                ))
+            }
+            (Some(sign), None) | (None, Some(sign)) => Some(sign),
+            (None, None) => None,
+        };
+
+        match (magnitude, sign) {
+            ("1", None) => Ok(Self::One),
+            ("1", Some('+')) => Ok(Self::OnePlus),
+            ("1", Some('-')) => Ok(Self::OneMinus),
+            ("2", None) => Ok(Self::Two),
+            ("2", Some('+')) => Ok(Self::TwoPlus),
+            ("2", Some('-')) => Ok(Self::TwoMinus),
+            ("3", None) => Ok(Self::Three),
+            ("3", Some('+')) => Ok(Self::ThreePlus),
+            ("3", Some('-')) => Ok(Self::ThreeMinus),
+            ("4", None) => Ok(Self::Four),
+            ("4", Some('+')) => Ok(Self::FourPlus),
+            ("4", Some('-')) => Ok(Self::FourMinus),
             _ => Err(format!("Could not parse charge: {}", s)),
         }
     }
 }
 
-impl ToString for Charge {
-    /// Converts a [`Charge`] to a string.
-    /// 
-    /// # Arguments
-    /// * `charge` - The [`Charge`] to convert.
+impl Display for Charge {
+    /// Formats a [`Charge`] as its MGF line.
     /// 
     /// # Examples
     /// 
     /// ```
     /// use mascot_rs::prelude::*;
     /// 
+    /// assert_eq!(Charge::Zero.to_string(), "CHARGE=0");
     /// assert_eq!(Charge::One.to_string(), "CHARGE=1");
     /// assert_eq!(Charge::OnePlus.to_string(), "CHARGE=1+");
     /// assert_eq!(Charge::Two.to_string(), "CHARGE=2");
@@ -73,18 +132,350 @@ impl ToString for Charge {
     /// assert_eq!(Charge::ThreePlus.to_string(), "CHARGE=3+");
     /// assert_eq!(Charge::Four.to_string(), "CHARGE=4");
     /// assert_eq!(Charge::FourPlus.to_string(), "CHARGE=4+");
+    /// assert_eq!(Charge::OneMinus.to_string(), "CHARGE=1-");
+    /// assert_eq!(Charge::TwoMinus.to_string(), "CHARGE=2-");
+    /// assert_eq!(Charge::ThreeMinus.to_string(), "CHARGE=3-");
+    /// assert_eq!(Charge::FourMinus.to_string(), "CHARGE=4-");
+    /// ```
+    /// 
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Zero => write!(f, "CHARGE=0"),
+            Self::One => write!(f, "CHARGE=1"),
+            Self::OnePlus => write!(f, "CHARGE=1+"),
+            Self::Two => write!(f, "CHARGE=2"),
+            Self::TwoPlus => write!(f, "CHARGE=2+"),
+            Self::Three => write!(f, "CHARGE=3"),
+            Self::ThreePlus => write!(f, "CHARGE=3+"),
+            Self::Four => write!(f, "CHARGE=4"),
+            Self::FourPlus => write!(f, "CHARGE=4+"),
+            Self::OneMinus => write!(f, "CHARGE=1-"),
+            Self::TwoMinus => write!(f, "CHARGE=2-"),
+            Self::ThreeMinus => write!(f, "CHARGE=3-"),
+            Self::FourMinus => write!(f, "CHARGE=4-"),
+        }
+    }
+}
+impl Charge {
+    /// Returns the absolute value of the charge.
+    /// 
+    /// # Examples
+    /// 
     /// ```
+    /// use mascot_rs::prelude::*;
     /// 
-    fn to_string(&self) -> String {
+    /// assert_eq!(Charge::Zero.magnitude(), 0);
+    /// assert_eq!(Charge::Two.magnitude(), 2);
+    /// assert_eq!(Charge::TwoPlus.magnitude(), 2);
+    /// assert_eq!(Charge::TwoMinus.magnitude(), 2);
+    /// ```
+    pub fn magnitude(&self) -> u8 {
         match self {
-            Self::One => "CHARGE=1".to_string(),
-            Self::OnePlus => "CHARGE=1+".to_string(),
-            Self::Two => "CHARGE=2".to_string(),
-            Self::TwoPlus => "CHARGE=2+".to_string(),
-            Self::Three => "CHARGE=3".to_string(),
-            Self::ThreePlus => "CHARGE=3+".to_string(),
-            Self::Four => "CHARGE=4".to_string(),
-            Self::FourPlus => "CHARGE=4+".to_string(),
+            Self::Zero => 0,
+            Self::One | Self::OnePlus | Self::OneMinus => 1,
+            Self::Two | Self::TwoPlus | Self::TwoMinus => 2,
+            Self::Three | Self::ThreePlus | Self::ThreeMinus => 3,
+            Self::Four | Self::FourPlus | Self::FourMinus => 4,
         }
     }
-}
\ No newline at end of file
+
+    /// Returns the sign of the charge, i.e. `-1`, `0` or `1`.
+    /// 
+    /// Charges without an explicit sign, such as `CHARGE=2`, are
+    /// conventionally positive.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use mascot_rs::prelude::*;
+    /// 
+    /// assert_eq!(Charge::Zero.sign(), 0);
+    /// assert_eq!(Charge::Two.sign(), 1);
+    /// assert_eq!(Charge::TwoPlus.sign(), 1);
+    /// assert_eq!(Charge::TwoMinus.sign(), -1);
+    /// ```
+    pub fn sign(&self) -> i8 {
+        match self {
+            Self::Zero => 0,
+            Self::OneMinus | Self::TwoMinus | Self::ThreeMinus | Self::FourMinus => -1,
+            _ => 1,
+        }
+    }
+
+    /// Returns whether the charge is positive.
+    pub fn is_positive(&self) -> bool {
+        self.sign() > 0
+    }
+
+    /// Returns whether the charge is negative.
+    pub fn is_negative(&self) -> bool {
+        self.sign() < 0
+    }
+
+    /// Returns whether the charge is zero.
+    pub fn is_zero(&self) -> bool {
+        self.sign() == 0
+    }
+
+    /// Returns whether the charge was provided with an explicit sign.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use mascot_rs::prelude::*;
+    /// 
+    /// assert!(!Charge::Zero.has_explicit_sign());
+    /// assert!(!Charge::Two.has_explicit_sign());
+    /// assert!(Charge::TwoPlus.has_explicit_sign());
+    /// assert!(Charge::TwoMinus.has_explicit_sign());
+    /// ```
+    pub fn has_explicit_sign(&self) -> bool {
+        !matches!(
+            self,
+            Self::Zero | Self::One | Self::Two | Self::Three | Self::Four
+        )
+    }
+
+    /// Parses a line listing one or more candidate charges.
+    /// 
+    /// Some documents, such as the ones exported by GNPS, list ambiguous
+    /// charges as `CHARGE=1+ and 2+` or `CHARGE=2+,3+`. The candidates are
+    /// returned in the order they are listed, and a line with a single
+    /// charge returns a single candidate.
+    /// 
+    /// # Arguments
+    /// * `s` - The string to parse.
+    /// 
+    /// # Errors
+    /// * If the string does not start with `CHARGE=`.
+    /// * If any of the candidate charges cannot be parsed.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use mascot_rs::prelude::*;
+    /// 
+    /// assert_eq!(
+    ///     Charge::from_str_multi("CHARGE=1+ and 2+").unwrap(),
+    ///     vec![Charge::OnePlus, Charge::TwoPlus]
+    /// );
+    /// assert_eq!(
+    ///     Charge::from_str_multi("CHARGE=2+,3+").unwrap(),
+    ///     vec![Charge::TwoPlus, Charge::ThreePlus]
+    /// );
+    /// assert_eq!(Charge::from_str_multi("CHARGE=2-").unwrap(), vec![Charge::TwoMinus]);
+    /// 
+    /// assert!(Charge::from_str_multi("CHARGE=1+ and").is_err());
+    /// assert!(Charge::from_str_multi("CHARGE=1+ or 2+").is_err());
+    /// assert!(Charge::from_str_multi("1+ and 2+").is_err());
+    /// ```
+    pub fn from_str_multi(s: &str) -> Result<Vec<Self>, String> {
+        let value = s
+            .strip_prefix("CHARGE=")
+            .ok_or_else(|| format!("Could not parse charges: {}", s))?;
+
+        value
+            .split(" and ")
+            .flat_map(|candidates| candidates.split(','))
+            .map(|candidate| {
+                Self::from_str(&format!("CHARGE={}", candidate.trim()))
+                    .map_err(|_| format!("Could not parse charges: {}", s))
+            })
+            .collect()
+    }
+
+    /// Returns the charge implied by an adduct, such as `[M-H]-` or `[M+2H]2+`.
+    /// 
+    /// The charge is read from the suffix following the closing bracket of
+    /// the adduct, which holds an optional magnitude followed by the sign.
+    /// 
+    /// # Arguments
+    /// * `adduct` - The adduct to parse.
+    /// 
+    /// # Errors
+    /// * If the adduct does not end with a charge suffix following a closing bracket.
+    /// * If the charge of the adduct is zero.
+    /// * If the magnitude of the charge is larger than [`MAX_CHARGE_MAGNITUDE`].
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use mascot_rs::prelude::*;
+    /// 
+    /// assert_eq!(Charge::from_adduct("[M-H]-").unwrap(), Charge::OneMinus);
+    /// assert_eq!(Charge::from_adduct("[M+Na]+").unwrap(), Charge::OnePlus);
+    /// assert_eq!(Charge::from_adduct("[M+2H]2+").unwrap(), Charge::TwoPlus);
+    /// assert_eq!(Charge::from_adduct("[M-2H]2-").unwrap(), Charge::TwoMinus);
+    /// 
+    /// assert!(Charge::from_adduct("[M+H]").is_err());
+    /// assert!(Charge::from_adduct("M+H").is_err());
+    /// assert!(Charge::from_adduct("[M+5H]5+").is_err());
+    /// assert!(Charge::from_adduct("[M]0+").is_err());
+    /// ```
+    pub fn from_adduct(adduct: &str) -> Result<Self, String> {
+        let error = || format!("Could not parse the charge of adduct: {}", adduct);
+        let (_, suffix) = adduct.trim().rsplit_once(']').ok_or_else(error)?;
+        let (magnitude, sign) = if let Some(magnitude) = suffix.strip_suffix('+') {
+            (magnitude, 1)
+        } else if let Some(magnitude) = suffix.strip_suffix('-') {
+            (magnitude, -1)
+        } else {
+            return Err(error());
+        };
+        let magnitude = if magnitude.is_empty() {
+            1
+        } else {
+            magnitude.parse::<i8>().map_err(|_| error())?
+        };
+        // No precursor ion can be neutral.
+        if magnitude == 0 {
+            return Err(error());
+        }
+        Self::try_from(sign * magnitude)
+    }
+
+    /// Converts a signed value to an explicitly signed [`Charge`], rejecting
+    /// the values whose magnitude is larger than the provided maximum.
+    /// 
+    /// The maximum allows callers to enforce a plausible range of charges for
+    /// their data, e.g. only singly charged ions. A maximum larger than
+    /// [`MAX_CHARGE_MAGNITUDE`] is capped, as such charges cannot be represented.
+    /// 
+    /// # Arguments
+    /// * `value` - The signed value of the charge.
+    /// * `max_magnitude` - The largest accepted magnitude of the charge.
+    /// 
+    /// # Errors
+    /// * If `value` is zero, as no precursor ion can be neutral.
+    /// * If the magnitude of `value` is larger than `max_magnitude` or [`MAX_CHARGE_MAGNITUDE`].
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use mascot_rs::prelude::*;
+    /// 
+    /// assert_eq!(Charge::try_from_i8(1, 1).unwrap(), Charge::OnePlus);
+    /// assert_eq!(Charge::try_from_i8(-1, 1).unwrap(), Charge::OneMinus);
+    /// assert!(Charge::try_from_i8(2, 1).is_err());
+    /// assert!(Charge::try_from_i8(-2, 1).is_err());
+    /// 
+    /// assert_eq!(Charge::try_from_i8(4, u8::MAX).unwrap(), Charge::FourPlus);
+    /// assert!(Charge::try_from_i8(5, u8::MAX).is_err());
+    /// assert!(Charge::try_from_i8(i8::MIN, u8::MAX).is_err());
+    /// assert!(Charge::try_from_i8(0, u8::MAX).is_err());
+    /// ```
+    pub fn try_from_i8(value: i8, max_magnitude: u8) -> Result<Self, String> {
+        // As when parsing a `CHARGE=0` line, we reject neutral charges,
+        // so that any converted charge can be written and parsed back.
+        if value == 0 {
+            return Err(
+                "Could not convert 0 to a charge: no precursor ion can be neutral.".to_string(),
+            );
+        }
+        let max_magnitude = max_magnitude.min(MAX_CHARGE_MAGNITUDE);
+        if value.unsigned_abs() > max_magnitude {
+            return Err(format!(
+                concat!(
+                    "Could not convert {} to a charge: the magnitude of the charge ",
+                    "must be at most {}."
+                ),
+                value, max_magnitude
+            ));
+        }
+        Ok(match value {
+            1 => Self::OnePlus,
+            2 => Self::TwoPlus,
+            3 => Self::ThreePlus,
+            4 => Self::FourPlus,
+            -1 => Self::OneMinus,
+            -2 => Self::TwoMinus,
+            -3 => Self::ThreeMinus,
+            _ => Self::FourMinus,
+        })
+    }
+}
+
+impl From<Charge> for i8 {
+    /// Returns the signed value of the charge.
+    /// 
+    /// The conversion is infallible, as the magnitude of any [`Charge`] is
+    /// at most [`MAX_CHARGE_MAGNITUDE`].
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use mascot_rs::prelude::*;
+    /// 
+    /// assert_eq!(i8::from(Charge::Three), 3);
+    /// assert_eq!(i8::from(Charge::ThreePlus), 3);
+    /// assert_eq!(i8::from(Charge::ThreeMinus), -3);
+    /// ```
+    fn from(charge: Charge) -> Self {
+        charge.sign() * charge.magnitude() as i8
+    }
+}
+
+impl TryFrom<i8> for Charge {
+    type Error = String;
+
+    /// Converts a signed value to an explicitly signed [`Charge`].
+    /// 
+    /// Zero and the values whose magnitude is larger than [`MAX_CHARGE_MAGNITUDE`]
+    /// are rejected. Use [`Charge::try_from_i8`] to enforce a narrower range.
+    /// 
+    /// # Arguments
+    /// * `value` - The signed value of the charge.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use mascot_rs::prelude::*;
+    /// 
+    /// assert!(Charge::try_from(0).is_err());
+    /// assert_eq!(Charge::try_from(2).unwrap(), Charge::TwoPlus);
+    /// assert_eq!(Charge::try_from(-2).unwrap(), Charge::TwoMinus);
+    /// assert!(Charge::try_from(5).is_err());
+    /// 
+    /// assert!(Charge::try_from(-2).unwrap() < Charge::try_from(1).unwrap());
+    /// ```
+    fn try_from(value: i8) -> Result<Self, Self::Error> {
+        Self::try_from_i8(value, MAX_CHARGE_MAGNITUDE)
+    }
+}
+
+impl PartialEq<i8> for Charge {
+    fn eq(&self, other: &i8) -> bool {
+        i8::from(*self) == *other
+    }
+}
+
+impl Ord for Charge {
+    /// Compares charges by their signed value.
+    /// 
+    /// Charges with the same value are ordered placing the
+    /// charge without an explicit sign first.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use mascot_rs::prelude::*;
+    /// 
+    /// let mut charges = vec![Charge::TwoPlus, Charge::OneMinus, Charge::Two, Charge::Zero];
+    /// charges.sort();
+    /// 
+    /// assert_eq!(charges, vec![Charge::OneMinus, Charge::Zero, Charge::Two, Charge::TwoPlus]);
+    /// assert!(Charge::FourMinus < Charge::One);
+    /// assert!(Charge::One < Charge::OnePlus);
+    /// ```
+    fn cmp(&self, other: &Self) -> Ordering {
+        i8::from(*self)
+            .cmp(&i8::from(*other))
+            .then_with(|| self.has_explicit_sign().cmp(&other.has_explicit_sign()))
+    }
+}
+
+impl PartialOrd for Charge {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -0,0 +1,34 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// How a field repeated with a different value within an entry is handled.
+pub enum ConflictPolicy {
+    /// The conflicting value raises an error.
+    #[default]
+    Error,
+    /// The value encountered first is kept, and the following ones are ignored.
+    KeepFirst,
+    /// The value encountered last is kept.
+    KeepLast,
+}
+
+impl ConflictPolicy {
+    /// Resolves the conflict between the observed value and a different
+    /// value encountered later, according to the policy.
+    ///
+    /// # Arguments
+    /// * `observed` - The value encountered so far, which is updated in place.
+    /// * `value` - The value encountered later.
+    /// * `error` - Builds the error returned under the [`ConflictPolicy::Error`] policy.
+    pub(crate) fn resolve<T, E>(self, observed: &mut T, value: T, error: E) -> Result<(), String>
+    where
+        E: FnOnce() -> String,
+    {
+        match self {
+            Self::Error => Err(error()),
+            Self::KeepFirst => Ok(()),
+            Self::KeepLast => {
+                *observed = value;
+                Ok(())
+            }
+        }
+    }
+}
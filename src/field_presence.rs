@@ -0,0 +1,83 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// Which of the optional fields of an MGF entry were provided.
+///
+/// The structural and bibliographic fields, such as `SMILES`, `INCHI` and
+/// `PUBMED`, are only collected as extra metadata in lenient mode, and fields
+/// holding a placeholder value such as `N/A` are considered missing.
+pub struct FieldPresence {
+    smiles: bool,
+    inchi: bool,
+    pubmed: bool,
+    filename: bool,
+    ion_mode: bool,
+    merged_scans: bool,
+}
+
+impl FieldPresence {
+    /// Creates a new [`FieldPresence`].
+    ///
+    /// # Arguments
+    /// * `smiles` - Whether the `SMILES` field was provided.
+    /// * `inchi` - Whether the `INCHI` field was provided.
+    /// * `pubmed` - Whether the `PUBMED` field was provided.
+    /// * `filename` - Whether the `FILENAME` field was provided.
+    /// * `ion_mode` - Whether the `IONMODE` field was provided.
+    /// * `merged_scans` - Whether the `MERGED_SCANS` and `MERGED_STATS` fields were provided.
+    pub fn new(
+        smiles: bool,
+        inchi: bool,
+        pubmed: bool,
+        filename: bool,
+        ion_mode: bool,
+        merged_scans: bool,
+    ) -> Self {
+        Self {
+            smiles,
+            inchi,
+            pubmed,
+            filename,
+            ion_mode,
+            merged_scans,
+        }
+    }
+
+    /// Returns whether the `SMILES` field was provided.
+    pub fn has_smiles(&self) -> bool {
+        self.smiles
+    }
+
+    /// Returns whether the `INCHI` field was provided.
+    pub fn has_inchi(&self) -> bool {
+        self.inchi
+    }
+
+    /// Returns whether the `PUBMED` field was provided.
+    pub fn has_pubmed(&self) -> bool {
+        self.pubmed
+    }
+
+    /// Returns whether the `FILENAME` field was provided.
+    pub fn has_filename(&self) -> bool {
+        self.filename
+    }
+
+    /// Returns whether the `IONMODE` field was provided.
+    pub fn has_ion_mode(&self) -> bool {
+        self.ion_mode
+    }
+
+    /// Returns whether the `MERGED_SCANS` and `MERGED_STATS` fields were provided.
+    pub fn has_merged_scans(&self) -> bool {
+        self.merged_scans
+    }
+
+    /// Returns whether a structure was provided, either as a `SMILES` or as an `INCHI`.
+    pub fn has_structure(&self) -> bool {
+        self.smiles || self.inchi
+    }
+
+    /// Returns whether a bibliographic reference was provided, i.e. a `PUBMED` identifier.
+    pub fn has_reference(&self) -> bool {
+        self.pubmed
+    }
+}
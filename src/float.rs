@@ -0,0 +1,78 @@
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::prelude::*;
+
+/// Trait for the floating point types supporting the numerical
+/// routines of the crate, such as binning and similarity scores.
+pub trait Float:
+    Copy
+    + PartialOrd
+    + Zero
+    + NaN
+    + StrictlyPositive
+    + Add<Self, Output = Self>
+    + Sub<Self, Output = Self>
+    + Mul<Self, Output = Self>
+    + Div<Self, Output = Self>
+{
+    /// Returns the value truncated to an unsigned integer.
+    ///
+    /// Negative values are saturated to zero.
+    fn to_usize(self) -> usize;
+
+    /// Returns the square root of the value.
+    fn sqrt(self) -> Self;
+
+    /// Returns the absolute value of the value.
+    fn abs(self) -> Self;
+
+    /// Returns the value raised to the provided floating point power.
+    fn powf(self, exponent: Self) -> Self;
+
+    /// Returns the value closest to the provided `f64`.
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Float for f32 {
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+
+    fn sqrt(self) -> Self {
+        f32::sqrt(self)
+    }
+
+    fn abs(self) -> Self {
+        f32::abs(self)
+    }
+
+    fn powf(self, exponent: Self) -> Self {
+        f32::powf(self, exponent)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl Float for f64 {
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+
+    fn powf(self, exponent: Self) -> Self {
+        f64::powf(self, exponent)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
@@ -1,6 +1,25 @@
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The fragmentation level of a spectrum, as provided by the `MSLEVEL` field.
+///
+/// # Examples
+///
+/// The levels can be used as the keys of hashed collections:
+///
+/// ```
+/// use mascot_rs::prelude::*;
+/// use std::collections::HashSet;
+///
+/// let mut levels = HashSet::new();
+/// assert!(levels.insert(FragmentationSpectraLevel::One));
+/// assert!(levels.insert(FragmentationSpectraLevel::Two));
+/// assert!(!levels.insert(FragmentationSpectraLevel::One));
+///
+/// assert_eq!(levels.len(), 2);
+/// assert!(levels.contains(&FragmentationSpectraLevel::Two));
+/// ```
 pub enum FragmentationSpectraLevel {
     One,
     Two,
@@ -30,6 +49,10 @@ impl FromStr for FragmentationSpectraLevel {
 
     /// Parses a string to a [`FragmentationSpectraLevel`].
     ///
+    /// Whitespace within the key, as in `MS LEVEL=2`, and around the value,
+    /// as in `MSLEVEL= 2`, is ignored, and the value may be prefixed by `MS`,
+    /// as in `MSLEVEL=MS2`.
+    ///
     /// # Arguments
     /// * `s` - The string to parse.
     ///
@@ -42,17 +65,98 @@ impl FromStr for FragmentationSpectraLevel {
     /// assert_eq!(FragmentationSpectraLevel::from_str("MSLEVEL=1").unwrap(), FragmentationSpectraLevel::One);
     /// assert_eq!(FragmentationSpectraLevel::from_str("MSLEVEL=2").unwrap(), FragmentationSpectraLevel::Two);
     ///
+    /// assert_eq!(FragmentationSpectraLevel::from_str("MSLEVEL= 2").unwrap(), FragmentationSpectraLevel::Two);
+    /// assert_eq!(FragmentationSpectraLevel::from_str("MS LEVEL=2").unwrap(), FragmentationSpectraLevel::Two);
+    /// assert_eq!(FragmentationSpectraLevel::from_str("MSLEVEL=MS2").unwrap(), FragmentationSpectraLevel::Two);
+    /// assert_eq!(FragmentationSpectraLevel::from_str("MSLEVEL=ms1").unwrap(), FragmentationSpectraLevel::One);
+    ///
     /// assert!(FragmentationSpectraLevel::from_str("MSLEVEL=3").is_err());
+    /// assert!(FragmentationSpectraLevel::from_str("MSLEVEL=MSMS").is_err());
+    /// assert!(FragmentationSpectraLevel::from_str("LEVEL=2").is_err());
     ///
     /// ```
     ///
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "MSLEVEL=1" => Ok(Self::One),
-            "MSLEVEL=2" => Ok(Self::Two),
+        let error = || format!("Could not parse fragmentation spectra level: {}", s);
+        let value = Self::strip_key(s).ok_or_else(error)?.trim();
+        let value = match value.get(..2) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("MS") => &value[2..],
+            _ => value,
+        };
+        let level = value.trim().parse::<u8>().map_err(|_| error())?;
+        Self::try_from(level).map_err(|_| error())
+    }
+}
+
+impl FragmentationSpectraLevel {
+    /// Returns the value of the provided `MSLEVEL` line, or `None` when the
+    /// line has a different key.
+    ///
+    /// The key is compared ignoring its case and whitespace, so that lines
+    /// such as `MS LEVEL=2` are recognized.
+    ///
+    /// # Arguments
+    /// * `line` - The line to strip.
+    pub(crate) fn strip_key(line: &str) -> Option<&str> {
+        let (key, value) = line.split_once('=')?;
+        let key = key
+            .chars()
+            .filter(|character| !character.is_whitespace())
+            .collect::<String>();
+        key.eq_ignore_ascii_case("MSLEVEL").then_some(value)
+    }
+
+    /// Returns the numeric value of the level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// assert_eq!(FragmentationSpectraLevel::One.as_u8(), 1);
+    /// assert_eq!(FragmentationSpectraLevel::Two.as_u8(), 2);
+    /// ```
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+        }
+    }
+}
+
+impl From<FragmentationSpectraLevel> for u8 {
+    fn from(level: FragmentationSpectraLevel) -> Self {
+        level.as_u8()
+    }
+}
+
+impl TryFrom<u8> for FragmentationSpectraLevel {
+    type Error = String;
+
+    /// Converts a numeric value to a [`FragmentationSpectraLevel`].
+    ///
+    /// # Arguments
+    /// * `value` - The numeric value of the level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// assert_eq!(FragmentationSpectraLevel::try_from(1).unwrap(), FragmentationSpectraLevel::One);
+    /// assert_eq!(FragmentationSpectraLevel::try_from(2).unwrap(), FragmentationSpectraLevel::Two);
+    /// assert_eq!(u8::from(FragmentationSpectraLevel::Two), 2);
+    ///
+    /// assert!(FragmentationSpectraLevel::try_from(0).is_err());
+    /// assert!(FragmentationSpectraLevel::try_from(3).is_err());
+    /// ```
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::One),
+            2 => Ok(Self::Two),
             _ => Err(format!(
-                "Could not parse fragmentation spectra level: {}",
-                s
+                "Could not convert {} to a fragmentation spectra level.",
+                value
             )),
         }
     }
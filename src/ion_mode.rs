@@ -0,0 +1,111 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The polarity of the ionization used to acquire a spectrum.
+pub enum IonMode {
+    Positive,
+    Negative,
+}
+
+impl FromStr for IonMode {
+    type Err = String;
+
+    /// Parses a string to an [`IonMode`].
+    ///
+    /// The value is matched case-insensitively, as documents variously
+    /// use `Positive`, `positive` and `POSITIVE`.
+    ///
+    /// # Arguments
+    /// * `s` - The string to parse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    /// use std::str::FromStr;
+    ///
+    /// assert_eq!(IonMode::from_str("IONMODE=Positive").unwrap(), IonMode::Positive);
+    /// assert_eq!(IonMode::from_str("IONMODE=negative").unwrap(), IonMode::Negative);
+    ///
+    /// assert!(IonMode::from_str("IONMODE=Neutral").is_err());
+    /// assert!(IonMode::from_str("Positive").is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("IONMODE=").map(str::to_lowercase).as_deref() {
+            Some("positive") => Ok(Self::Positive),
+            Some("negative") => Ok(Self::Negative),
+            _ => Err(format!("Could not parse ion mode: {}", s)),
+        }
+    }
+}
+
+impl Display for IonMode {
+    /// Formats an [`IonMode`] as its MGF line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// assert_eq!(IonMode::Positive.to_string(), "IONMODE=Positive");
+    /// assert_eq!(IonMode::Negative.to_string(), "IONMODE=Negative");
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Positive => write!(f, "IONMODE=Positive"),
+            Self::Negative => write!(f, "IONMODE=Negative"),
+        }
+    }
+}
+
+impl IonMode {
+    /// Returns the opposite polarity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// assert_eq!(IonMode::Positive.opposite(), IonMode::Negative);
+    /// assert_eq!(IonMode::Negative.opposite(), IonMode::Positive);
+    /// ```
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::Positive => Self::Negative,
+            Self::Negative => Self::Positive,
+        }
+    }
+
+    /// Returns the ion mode implied by the provided charge, if any.
+    ///
+    /// Only charges with an explicit sign imply a polarity, as a charge
+    /// such as `CHARGE=1` does not specify it.
+    ///
+    /// # Arguments
+    /// * `charge` - The charge to derive the ion mode from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// assert_eq!(IonMode::from_charge(Charge::TwoPlus), Some(IonMode::Positive));
+    /// assert_eq!(IonMode::from_charge(Charge::OneMinus), Some(IonMode::Negative));
+    /// assert_eq!(IonMode::from_charge(Charge::One), None);
+    /// assert_eq!(IonMode::from_charge(Charge::Zero), None);
+    /// ```
+    pub fn from_charge(charge: Charge) -> Option<Self> {
+        if !charge.has_explicit_sign() {
+            return None;
+        }
+        if charge.is_negative() {
+            Some(Self::Negative)
+        } else {
+            Some(Self::Positive)
+        }
+    }
+}
@@ -0,0 +1,49 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// Which of the fragmentation levels of an MGF entry are present.
+///
+/// Most documents either hold only the second fragmentation level of each
+/// entry, or both the first and the second ones, so entries deviating from
+/// the structure of the rest of a document usually deserve a closer look.
+pub struct LevelProfile {
+    first_level: bool,
+    second_level: bool,
+}
+
+impl LevelProfile {
+    /// Creates a new [`LevelProfile`].
+    ///
+    /// # Arguments
+    /// * `first_level` - Whether the first fragmentation level is present.
+    /// * `second_level` - Whether the second fragmentation level is present.
+    pub fn new(first_level: bool, second_level: bool) -> Self {
+        Self {
+            first_level,
+            second_level,
+        }
+    }
+
+    /// Returns whether the first fragmentation level is present.
+    pub fn has_first_level(&self) -> bool {
+        self.first_level
+    }
+
+    /// Returns whether the second fragmentation level is present.
+    pub fn has_second_level(&self) -> bool {
+        self.second_level
+    }
+
+    /// Returns whether both the first and the second fragmentation levels are present.
+    pub fn has_both_levels(&self) -> bool {
+        self.first_level && self.second_level
+    }
+
+    /// Returns whether only the first fragmentation level is present.
+    pub fn is_first_level_only(&self) -> bool {
+        self.first_level && !self.second_level
+    }
+
+    /// Returns whether only the second fragmentation level is present.
+    pub fn is_second_level_only(&self) -> bool {
+        !self.first_level && self.second_level
+    }
+}
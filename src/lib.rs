@@ -1,5 +1,6 @@
 #![doc = include_str!("../README.md")]
 pub mod charge;
+pub mod ion_mode;
 pub mod fragmentation_spectra_level;
 pub mod mascot_generic_format;
 pub mod mascot_generic_format_builder;
@@ -13,21 +14,54 @@ pub mod line_parser;
 pub mod strictly_positive;
 pub mod zero;
 pub mod nan;
+pub mod float;
+pub mod parsable_float;
+pub mod approx_eq;
+pub mod parse_options;
+pub mod msp_builder;
+pub mod precursor_index;
+pub mod peak;
+pub mod field_presence;
+pub mod builder_state;
+pub mod parse_report;
+pub mod mgf_stats;
+pub mod level_profile;
+pub mod conflict_policy;
 
 pub mod prelude {
     pub use crate::charge::Charge;
+    pub use crate::charge::MAX_CHARGE_MAGNITUDE;
+    pub use crate::ion_mode::IonMode;
     pub use crate::fragmentation_spectra_level::FragmentationSpectraLevel;
     pub use crate::mascot_generic_format::MascotGenericFormat;
     pub use crate::mascot_generic_format::MGFVec;
+    pub use crate::mascot_generic_format::DEFAULT_MGF_PRECISION;
+    pub use crate::mascot_generic_format::DroppedEntries;
+    pub use crate::mascot_generic_format::FileErrors;
     pub use crate::mascot_generic_format_builder::MascotGenericFormatBuilder;
     pub use crate::mascot_generic_format_metadata::MascotGenericFormatMetadata;
     pub use crate::merge_scans_metadata::MergeScansMetadata;
     pub use crate::merge_scans_metadata_builder::MergeScansMetadataBuilder;
     pub use crate::mascot_generic_format_data::MascotGenericFormatData;
+    pub use crate::mascot_generic_format_data::ISOTOPE_SPACING;
     pub use crate::mascot_generic_format_data_builder::MascotGenericFormatDataBuilder;
     pub use crate::mascot_generic_format_metadata_builder::MascotGenericFormatMetadataBuilder;
     pub use crate::line_parser::LineParser;
     pub use crate::strictly_positive::StrictlyPositive;
     pub use crate::zero::Zero;
     pub use crate::nan::NaN;
+    pub use crate::float::Float;
+    pub use crate::parsable_float::ParsableFloat;
+    pub use crate::approx_eq::ApproxEq;
+    pub use crate::approx_eq::DEFAULT_RELATIVE_TOLERANCE;
+    pub use crate::parse_options::ParseOptions;
+    pub use crate::msp_builder::MSPBuilder;
+    pub use crate::precursor_index::PrecursorIndex;
+    pub use crate::peak::Peak;
+    pub use crate::field_presence::FieldPresence;
+    pub use crate::builder_state::BuilderState;
+    pub use crate::parse_report::ParseReport;
+    pub use crate::mgf_stats::MgfStats;
+    pub use crate::level_profile::LevelProfile;
+    pub use crate::conflict_policy::ConflictPolicy;
 }
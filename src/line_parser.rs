@@ -1,5 +1,6 @@
-pub trait LineParser {
+use std::borrow::Cow;
 
+pub trait LineParser {
     /// Returns `true` if the line can be parsed by the data structure.
     fn can_parse_line(line: &str) -> bool;
 
@@ -8,4 +9,171 @@ pub trait LineParser {
 
     /// Returns whether the data structure can be built.
     fn can_build(&self) -> bool;
+
+    /// Parses the lines in order and updates the data structure, stopping
+    /// at the first line that cannot be parsed.
+    ///
+    /// # Arguments
+    /// * `lines` - The lines to parse.
+    ///
+    /// # Errors
+    /// * If any of the lines cannot be parsed, in which case the following lines are not digested.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::default();
+    ///
+    /// mascot_generic_format_builder.digest_lines([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// assert!(mascot_generic_format_builder.can_build());
+    /// ```
+    fn digest_lines<T, S>(&mut self, lines: T) -> Result<(), String>
+    where
+        Self: Sized,
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for line in lines {
+            self.digest_line(line.as_ref())?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the line with its `KEY=VALUE` key normalized.
+///
+/// The key, i.e. the portion preceding the first `=`, is trimmed and
+/// uppercased, and the whitespace preceding the value is removed, so that
+/// lines such as `Pepmass=381.0795` or `CHARGE = 1+` are digested as
+/// `PEPMASS=381.0795` and `CHARGE=1+`. The value is otherwise left untouched,
+/// and lines without a `=` are returned as they are.
+///
+/// # Arguments
+/// * `line` - The line to normalize.
+pub(crate) fn normalize_key(line: &str) -> Cow<'_, str> {
+    match line.split_once('=') {
+        Some((key, value)) => {
+            let normalized_key = key.trim().to_uppercase();
+            let normalized_value = value.trim_start();
+            if normalized_key == key && normalized_value == value {
+                Cow::Borrowed(line)
+            } else {
+                Cow::Owned(format!("{}={}", normalized_key, normalized_value))
+            }
+        }
+        None => Cow::Borrowed(line),
+    }
+}
+
+/// Returns whether the line is a comment, i.e. it starts with `#` or `;`.
+///
+/// # Arguments
+/// * `line` - The line to check.
+pub(crate) fn is_comment(line: &str) -> bool {
+    line.starts_with('#') || line.starts_with(';')
+}
+
+/// Returns whether the line is the provided section delimiter, such as
+/// `BEGIN IONS` or `END IONS`.
+///
+/// Whitespace around the delimiter and a trailing comment, as in
+/// `END IONS ; end of entry`, are ignored, while lines where the delimiter is
+/// followed by anything else, such as `END IONSX`, are not delimiters.
+///
+/// # Arguments
+/// * `line` - The line to check.
+/// * `delimiter` - The delimiter to match.
+pub(crate) fn is_section_delimiter(line: &str, delimiter: &str) -> bool {
+    line.trim_start()
+        .strip_prefix(delimiter)
+        .is_some_and(|rest| {
+            let rest = rest.trim_start();
+            rest.is_empty() || is_comment(rest)
+        })
+}
+
+/// Returns whether the value is a placeholder for a missing value, such as
+/// the `N/A` or `*..*` written by some exporters, or an empty value.
+///
+/// # Arguments
+/// * `value` - The value to check.
+pub(crate) fn is_sentinel_value(value: &str) -> bool {
+    let value = value.trim();
+    value.is_empty() || value.eq_ignore_ascii_case("N/A") || value == "*..*"
+}
+
+/// Returns the value with its decimal comma replaced by a dot, so that values
+/// exported with a European locale, such as `381,0795`, can be parsed.
+///
+/// Only values with a single comma and no dot are converted, so that lists of
+/// comma-separated values are left untouched.
+///
+/// # Arguments
+/// * `value` - The value to normalize.
+pub(crate) fn normalize_decimal_comma(value: &str) -> Cow<'_, str> {
+    if value.matches(',').count() == 1 && !value.contains('.') {
+        Cow::Owned(value.replace(',', "."))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Returns whether the value looks like a SMILES string.
+///
+/// This is only a cheap check of the characters used by the string, and of
+/// the balance of its branches and bracket atoms, meant to reject values that
+/// are clearly not SMILES, such as free text, rather than to validate the
+/// chemistry of the structure.
+///
+/// # Arguments
+/// * `value` - The value to check.
+pub(crate) fn is_plausible_smiles(value: &str) -> bool {
+    let mut characters = value.chars().peekable();
+    let mut branch_depth = 0_usize;
+    if value.is_empty() {
+        return false;
+    }
+    while let Some(character) = characters.next() {
+        match character {
+            // Bracket atoms, such as `[NH4+]` or `[C@@H]`, hold an element,
+            // its hydrogens, charge, chirality and atom class.
+            '[' => loop {
+                match characters.next() {
+                    Some(']') => break,
+                    Some(character)
+                        if character.is_ascii_alphanumeric() || "+-@:*".contains(character) => {}
+                    _ => return false,
+                }
+            },
+            '(' => branch_depth += 1,
+            ')' => {
+                if branch_depth == 0 {
+                    return false;
+                }
+                branch_depth -= 1;
+            }
+            'B' => {
+                characters.next_if_eq(&'r');
+            }
+            'C' => {
+                characters.next_if_eq(&'l');
+            }
+            'N' | 'O' | 'P' | 'S' | 'F' | 'I' | 'b' | 'c' | 'n' | 'o' | 'p' | 's' | '*' => {}
+            '0'..='9' | '%' | '-' | '=' | '#' | '$' | ':' | '/' | '\\' | '.' => {}
+            _ => return false,
+        }
+    }
+    branch_depth == 0
 }
@@ -1,16 +1,97 @@
+use crate::line_parser::{is_section_delimiter, is_sentinel_value, normalize_key};
 use crate::prelude::*;
-use std::collections::HashSet;
-use std::fmt::Debug;
-use std::hash::Hash;
-use std::ops::{Add, Index, IndexMut, Sub};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::ops::{Add, Div, Index, IndexMut, Sub};
 use std::str::FromStr;
 
-#[derive(Debug, Clone)]
+/// The default number of decimal places used when writing the mass-charge
+/// ratios and the intensities of MGF documents.
+pub const DEFAULT_MGF_PRECISION: usize = 4;
+
+/// The entries dropped while parsing a document, as pairs of the feature ID
+/// of each entry, when known, and of the reason why it was dropped.
+pub type DroppedEntries<I> = Vec<(Option<I>, String)>;
+
+/// The files that could not be parsed, as pairs of the path of each file
+/// and of the error raised while parsing it.
+pub type FileErrors = Vec<(String, String)>;
+
+/// Returns the provided line of a document, ignoring the UTF-8 byte order
+/// mark that documents exported by some Windows tools start with, which
+/// would otherwise hide the first `BEGIN IONS`.
+fn strip_byte_order_mark(line_number: usize, line: &str) -> &str {
+    if line_number == 0 {
+        line.strip_prefix('\u{FEFF}').unwrap_or(line)
+    } else {
+        line
+    }
+}
+
+/// Returns the document held by the provided bytes, ignoring a leading
+/// UTF-8 byte order mark.
+fn decode_document(bytes: &[u8]) -> Result<&str, String> {
+    let document = std::str::from_utf8(bytes)
+        .map_err(|e| format!("The document is not valid UTF-8: {}", e))?;
+    Ok(strip_byte_order_mark(0, document))
+}
+
+/// Returns a copy of the provided report, with the warnings prefixed with
+/// the path of the file they were raised in.
+fn locate_report(path: &str, report: &ParseReport) -> ParseReport {
+    let mut located_report = ParseReport::new();
+    for warning in report.warnings() {
+        located_report.push_warning(format!("{}: {}", path, warning));
+    }
+    located_report
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A single entry of an MGF document.
+///
+/// Two entries are equal when their metadata and their peaks are equal,
+/// with floating point values compared exactly and optional fields equal
+/// only when both are absent or both hold equal values. Hashing only uses
+/// the feature ID, so that entries can be deduplicated with a `HashSet`
+/// whenever the floating point type implements `Eq`.
+///
+/// # Examples
+///
+/// ```
+/// use mascot_rs::prelude::*;
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::{Hash, Hasher};
+///
+/// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+/// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+///
+/// let mascot_generic_format = mascot_generic_formats[0].clone();
+///
+/// assert_eq!(mascot_generic_format, mascot_generic_formats[0]);
+/// assert_ne!(mascot_generic_format, mascot_generic_formats[1]);
+///
+/// let hash = |mascot_generic_format: &MascotGenericFormat<usize, f64>| {
+///     let mut hasher = DefaultHasher::new();
+///     mascot_generic_format.hash(&mut hasher);
+///     hasher.finish()
+/// };
+///
+/// assert_eq!(hash(&mascot_generic_format), hash(&mascot_generic_formats[0]));
+/// ```
 pub struct MascotGenericFormat<I, F> {
     metadata: MascotGenericFormatMetadata<I, F>,
     data: Vec<MascotGenericFormatData<F>>,
 }
 
+impl<I: Hash, F> Hash for MascotGenericFormat<I, F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.metadata.hash(state);
+    }
+}
+
 impl<
         I: Copy + Zero + PartialEq + Debug + Add<Output = I> + Eq,
         F: Copy
@@ -19,13 +100,98 @@ impl<
             + PartialOrd
             + Debug
             + Add<F, Output = F>
-            + Sub<F, Output = F>,
+            + Sub<F, Output = F>
+            + ApproxEq,
     > MascotGenericFormat<I, F>
 {
+    /// Creates a new [`MascotGenericFormat`], comparing the parent ion mass
+    /// with the first fragmentation level within the default tolerance.
+    ///
+    /// # Arguments
+    /// * `metadata` - The metadata of the entry.
+    /// * `data` - The data of the fragmentation levels of the entry.
+    ///
+    /// # Errors
+    /// * If no fragmentation level is provided.
+    /// * If a fragmentation level is provided more than once.
+    /// * If the parent ion mass differs from the minimum mass-charge ratio of the first fragmentation level.
     pub fn new(
         metadata: MascotGenericFormatMetadata<I, F>,
         data: Vec<MascotGenericFormatData<F>>,
     ) -> Result<Self, String> {
+        Self::new_with_precursor_tolerance(metadata, data, Some(DEFAULT_RELATIVE_TOLERANCE))
+    }
+
+    /// Creates a new [`MascotGenericFormat`], comparing the parent ion mass
+    /// with the first fragmentation level within the provided tolerance.
+    ///
+    /// Deconvoluted documents may report a parent ion mass that is not among
+    /// the measured peaks of the first fragmentation level: in such cases, the
+    /// check may be relaxed with a larger tolerance, or skipped altogether by
+    /// providing `None`.
+    ///
+    /// # Arguments
+    /// * `metadata` - The metadata of the entry.
+    /// * `data` - The data of the fragmentation levels of the entry.
+    /// * `precursor_tolerance` - The relative tolerance used to compare the parent ion mass with the minimum mass-charge ratio of the first fragmentation level, or `None` to skip the check.
+    ///
+    /// # Errors
+    /// * If no fragmentation level is provided.
+    /// * If a fragmentation level is provided more than once.
+    /// * If the parent ion mass differs from the minimum mass-charge ratio of the first fragmentation level, when a tolerance is provided.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let metadata = MascotGenericFormatMetadata::<usize, f64>::new(
+    ///     1,
+    ///     381.0800,
+    ///     37.083,
+    ///     Charge::One,
+    ///     None,
+    ///     None,
+    /// )
+    /// .unwrap();
+    /// let data = vec![MascotGenericFormatData::new(
+    ///     FragmentationSpectraLevel::One,
+    ///     vec![381.0795, 420.5],
+    ///     vec![2.4E5, 3.3E5],
+    /// )
+    /// .unwrap()];
+    ///
+    /// assert!(MascotGenericFormat::new(metadata.clone(), data.clone()).is_err());
+    /// assert!(
+    ///     MascotGenericFormat::new_with_precursor_tolerance(metadata.clone(), data.clone(), Some(1e-5))
+    ///         .is_ok()
+    /// );
+    /// assert!(MascotGenericFormat::new_with_precursor_tolerance(metadata, data, None).is_ok());
+    /// ```
+    pub fn new_with_precursor_tolerance(
+        metadata: MascotGenericFormatMetadata<I, F>,
+        data: Vec<MascotGenericFormatData<F>>,
+        precursor_tolerance: Option<f64>,
+    ) -> Result<Self, String> {
+        if data.is_empty() {
+            return Err(
+                "Could not create MascotGenericFormat: at least one fragmentation level is required."
+                    .to_string(),
+            );
+        }
+
+        for (index, level_data) in data.iter().enumerate() {
+            if data[..index]
+                .iter()
+                .any(|other| other.level() == level_data.level())
+            {
+                return Err(format!(
+                    "Could not create MascotGenericFormat: the fragmentation level {:?} is provided more than once.",
+                    level_data.level()
+                ));
+            }
+        }
+
         // We need to check that, if the data provided is compatible with
         // the metadata provided. Specifically, if the minimum MSLEVEL
         // of the data is equal to one, then the PEPMASS must be equal to
@@ -33,8 +199,17 @@ impl<
         // first level.
         let mgf = Self { metadata, data };
 
+        let Some(precursor_tolerance) = precursor_tolerance else {
+            return Ok(mgf);
+        };
+
         if let Ok(first_mgf) = mgf.get_first_fragmentation_level() {
-            if mgf.parent_ion_mass() != first_mgf.min_mass_divided_by_charge_ratio() {
+            // The two values are compared within a small relative tolerance, as the
+            // documents may write them with a different number of decimals.
+            if !mgf.precursor_mz().approx_eq(
+                &first_mgf.min_mass_divided_by_charge_ratio(),
+                precursor_tolerance,
+            ) {
                 return Err(format!(
                     concat!(
                         "When the MGF contains data relative to fragmentation level one, ",
@@ -44,7 +219,7 @@ impl<
                         "of {:?}, while the minimum mass-charge ratio was {:?}. This may be a data bug ",
                         "derived from how the file was created."
                     ),
-                    mgf.parent_ion_mass(),
+                    mgf.precursor_mz(),
                     first_mgf.min_mass_divided_by_charge_ratio()
                 ));
             }
@@ -53,69 +228,504 @@ impl<
         Ok(mgf)
     }
 
+    /// Returns a copy of the MGF object holding only the requested fragmentation level.
+    ///
+    /// The copy is created through [`MascotGenericFormat::new`], so that it
+    /// satisfies the same invariants as the parsed objects.
+    ///
+    /// # Arguments
+    /// * `level` - The fragmentation level to keep.
+    ///
+    /// # Errors
+    /// * If the requested fragmentation level is not available.
+    /// * If the copy does not satisfy the invariants of [`MascotGenericFormat::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "SPECTYPE=CORRELATED MS",
+    ///     "MSLEVEL=1",
+    ///     "SCANS=-1",
+    ///     "381.0795 7.8E8",
+    ///     "END IONS",
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "SCANS=1",
+    ///     "60.5425 2.4E5",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// let second_level = mascot_generic_formats[0]
+    ///     .clone_with_level(FragmentationSpectraLevel::Two)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(second_level.levels().len(), 1);
+    /// assert!(second_level.get_first_fragmentation_level().is_err());
+    /// assert_eq!(
+    ///     second_level.get_second_fragmentation_level(),
+    ///     mascot_generic_formats[0].get_second_fragmentation_level()
+    /// );
+    /// assert_eq!(second_level.feature_id(), 1);
+    ///
+    /// let copy = second_level.clone_with_level(FragmentationSpectraLevel::Two).unwrap();
+    /// assert_eq!(copy, second_level);
+    /// assert!(second_level.clone_with_level(FragmentationSpectraLevel::One).is_err());
+    /// ```
+    pub fn clone_with_level(&self, level: FragmentationSpectraLevel) -> Result<Self, String> {
+        self.clone_with_level_and_tolerance(level, Some(DEFAULT_RELATIVE_TOLERANCE))
+    }
+
+    /// Returns a copy of the MGF object holding only the requested fragmentation
+    /// level, comparing the parent ion mass with the first fragmentation level
+    /// within the provided tolerance.
+    ///
+    /// The copy is created through [`MascotGenericFormat::new_with_precursor_tolerance`],
+    /// as for entries parsed with [`ParseOptions::with_precursor_tolerance`].
+    ///
+    /// # Arguments
+    /// * `level` - The fragmentation level to keep.
+    /// * `precursor_tolerance` - The relative tolerance used to compare the parent ion mass with the minimum mass-charge ratio of the first fragmentation level, or `None` to skip the check.
+    ///
+    /// # Errors
+    /// * If the requested fragmentation level is not available.
+    /// * If the copy does not satisfy the invariants of [`MascotGenericFormat::new_with_precursor_tolerance`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let metadata = MascotGenericFormatMetadata::<usize, f64>::new(
+    ///     1,
+    ///     381.0800,
+    ///     37.083,
+    ///     Charge::One,
+    ///     None,
+    ///     None,
+    /// )
+    /// .unwrap();
+    /// let data = vec![MascotGenericFormatData::new(
+    ///     FragmentationSpectraLevel::One,
+    ///     vec![381.0795, 420.5],
+    ///     vec![2.4E5, 3.3E5],
+    /// )
+    /// .unwrap()];
+    /// let mascot_generic_format =
+    ///     MascotGenericFormat::new_with_precursor_tolerance(metadata, data, None).unwrap();
+    ///
+    /// assert!(mascot_generic_format
+    ///     .clone_with_level(FragmentationSpectraLevel::One)
+    ///     .is_err());
+    /// assert!(mascot_generic_format
+    ///     .clone_with_level_and_tolerance(FragmentationSpectraLevel::One, Some(1e-5))
+    ///     .is_ok());
+    /// assert!(mascot_generic_format
+    ///     .clone_with_level_and_tolerance(FragmentationSpectraLevel::One, None)
+    ///     .is_ok());
+    /// ```
+    pub fn clone_with_level_and_tolerance(
+        &self,
+        level: FragmentationSpectraLevel,
+        precursor_tolerance: Option<f64>,
+    ) -> Result<Self, String> {
+        let data = self
+            .data
+            .iter()
+            .find(|data| data.level() == level)
+            .ok_or_else(|| {
+                format!(
+                    "Could not clone the MGF object with the fragmentation level {:?}, as it is not available.",
+                    level
+                )
+            })?;
+        Self::new_with_precursor_tolerance(
+            self.metadata.clone(),
+            vec![data.clone()],
+            precursor_tolerance,
+        )
+    }
+}
+
+impl<
+        I: Copy + Zero + PartialEq + Debug + Add<Output = I> + Eq,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>,
+    > MascotGenericFormat<I, F>
+{
     /// Returns the feature ID of the metadata.
     pub fn feature_id(&self) -> I {
         self.metadata.feature_id()
     }
 
+    /// Returns the precursor mass divided by charge ratio of the metadata.
+    ///
+    /// The `PEPMASS` field of MGF documents stores the m/z of the precursor
+    /// ion, and not its neutral mass, which would additionally require to
+    /// correct for the charge and the adducts of the ion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=2+",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// // The value is the m/z of the doubly charged ion, as written in the document,
+    /// // and not the neutral mass of the molecule, which would be about twice as large.
+    /// assert_eq!(mascot_generic_formats[0].precursor_mz(), 381.0795);
+    /// assert_eq!(mascot_generic_formats[0].charge(), Charge::TwoPlus);
+    /// ```
+    pub fn precursor_mz(&self) -> F {
+        self.metadata.parent_ion_mass()
+    }
+
     /// Returns the parent ion mass of the metadata.
+    ///
+    /// Despite its name, the value is the m/z of the precursor ion.
+    #[deprecated(note = "The value is a mass divided by charge ratio: use `precursor_mz` instead.")]
     pub fn parent_ion_mass(&self) -> F {
-        self.metadata.parent_ion_mass()
+        self.precursor_mz()
     }
 
-    /// Returns the retention time of the metadata.
+    /// Returns the retention time of the metadata, in seconds.
     pub fn retention_time(&self) -> F {
         self.metadata.retention_time()
     }
 
+    /// Returns the retention time of the metadata, in minutes.
+    pub fn retention_time_minutes(&self) -> F
+    where
+        F: Div<F, Output = F> + From<u8>,
+    {
+        self.metadata.retention_time_minutes()
+    }
+
+    /// Sets the retention time of the metadata, in seconds.
+    ///
+    /// # Arguments
+    /// * `retention_time` - The new retention time.
+    ///
+    /// # Errors
+    /// * If `retention_time` is not strictly positive, in which case the MGF object is left untouched.
+    pub fn set_retention_time(&mut self, retention_time: F) -> Result<(), String> {
+        self.metadata.set_retention_time(retention_time)
+    }
+
+    /// Maps the retention time of the metadata through the provided function.
+    ///
+    /// # Arguments
+    /// * `map` - The function to apply to the retention time.
+    ///
+    /// # Errors
+    /// * If the mapped retention time is not strictly positive, in which case the MGF object is left untouched.
+    pub fn map_retention_time<G: Fn(F) -> F>(&mut self, map: G) -> Result<(), String> {
+        self.metadata.map_retention_time(map)
+    }
+
     /// Returns the charge of the metadata.
     pub fn charge(&self) -> Charge {
         self.metadata.charge()
     }
 
+    /// Returns the alternative candidate charges listed after the charge, if any.
+    pub fn alternative_charges(&self) -> &[Charge] {
+        self.metadata.alternative_charges()
+    }
+
+    /// Returns the scan number provided by the `SCANS` field, if it differs
+    /// from the feature ID.
+    pub fn scan_number(&self) -> Option<I>
+    where
+        I: Copy,
+    {
+        self.metadata.scan_number()
+    }
+
+    /// Returns the ion mode explicitly provided by the `IONMODE` field, if any.
+    pub fn ion_mode(&self) -> Option<IonMode> {
+        self.metadata.ion_mode()
+    }
+
+    /// Returns the ion mode, inferring it from the charge when the
+    /// `IONMODE` field is absent.
+    pub fn effective_ion_mode(&self) -> Option<IonMode> {
+        self.metadata.effective_ion_mode()
+    }
+
+    /// Returns whether the ion mode provided by the `IONMODE` field agrees
+    /// with the sign of the charge, when both are known.
+    pub fn ion_mode_consistent(&self) -> Option<bool> {
+        self.metadata.ion_mode_consistent()
+    }
+
     /// Returns the filename of the metadata.
     pub fn filename(&self) -> Option<&str> {
         self.metadata.filename()
     }
 
+    /// Returns the extra metadata collected while parsing in lenient mode.
+    pub fn extra(&self) -> &BTreeMap<String, String> {
+        self.metadata.extra()
+    }
+
+    /// Returns which of the optional fields of the metadata were provided.
+    pub fn field_presence(&self) -> FieldPresence {
+        self.metadata.field_presence()
+    }
+
+    /// Returns the keys of the optional fields that were present in the document
+    /// with a placeholder value, such as `N/A`, `*..*` or an empty value.
+    pub fn sentinel_fields(&self) -> &BTreeSet<String> {
+        self.metadata.sentinel_fields()
+    }
+
+    /// Returns whether any optional field was present in the document with a
+    /// placeholder value, as opposed to being omitted altogether.
+    pub fn had_sentinel_fields(&self) -> bool {
+        self.metadata.had_sentinel_fields()
+    }
+
+    /// Returns the data of all of the fragmentation levels.
+    pub fn data(&self) -> &[MascotGenericFormatData<F>] {
+        &self.data
+    }
+
+    /// Returns the metadata and the data of the MGF object.
+    ///
+    /// The parts can be recombined, after any change, with [`MascotGenericFormat::new`],
+    /// which validates them again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let mascot_generic_format = mascot_generic_formats[0].clone();
+    /// let number_of_peaks = mascot_generic_format.num_peaks(FragmentationSpectraLevel::Two);
+    /// assert!(number_of_peaks.is_some());
+    /// assert_eq!(mascot_generic_format.num_peaks(FragmentationSpectraLevel::One), None);
+    ///
+    /// let (metadata, data) = mascot_generic_format.into_parts();
+    ///
+    /// assert_eq!(data.len(), 1);
+    /// assert_eq!(Some(data[0].mass_divided_by_charge_ratios().len()), number_of_peaks);
+    ///
+    /// let mascot_generic_format = MascotGenericFormat::new(metadata, data).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format, mascot_generic_formats[0]);
+    /// ```
+    pub fn into_parts(
+        self,
+    ) -> (
+        MascotGenericFormatMetadata<I, F>,
+        Vec<MascotGenericFormatData<F>>,
+    ) {
+        (self.metadata, self.data)
+    }
+
+    /// Returns the number of peaks of the requested fragmentation level, if available.
+    ///
+    /// # Arguments
+    /// * `level` - The fragmentation level whose peaks should be counted.
+    pub fn num_peaks(&self, level: FragmentationSpectraLevel) -> Option<usize> {
+        self.get_level(level)
+            .map(|data| data.mass_divided_by_charge_ratios().len())
+    }
+
+    /// Returns an iterator over the data of all of the fragmentation levels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let mascot_generic_format = mascot_generic_formats
+    ///     .iter()
+    ///     .find(|mascot_generic_format| mascot_generic_format.has_second_level())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     mascot_generic_format.levels().map(|data| data.level()).collect::<Vec<_>>(),
+    ///     vec![FragmentationSpectraLevel::One, FragmentationSpectraLevel::Two]
+    /// );
+    ///
+    /// for data in mascot_generic_format.levels() {
+    ///     assert_eq!(mascot_generic_format.get_level(data.level()), Some(data));
+    /// }
+    /// ```
+    pub fn levels(&self) -> std::slice::Iter<'_, MascotGenericFormatData<F>> {
+        self.data.iter()
+    }
+
+    /// Returns a reference to the data of the requested fragmentation level, if available.
+    ///
+    /// # Arguments
+    /// * `level` - The fragmentation level to retrieve.
+    pub fn get_level(
+        &self,
+        level: FragmentationSpectraLevel,
+    ) -> Option<&MascotGenericFormatData<F>> {
+        self.levels().find(|data| data.level() == level)
+    }
+
+    /// Returns a view of the mass-charge ratios of the requested fragmentation level, if available.
+    ///
+    /// The view borrows the peaks of the MGF object, without copying them.
+    ///
+    /// # Arguments
+    /// * `level` - The fragmentation level to retrieve.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "119.0857 3.3E5",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// let mz_array = mascot_generic_formats[0].mz_array(FragmentationSpectraLevel::Two).unwrap();
+    /// let intensity_array = mascot_generic_formats[0]
+    ///     .intensity_array(FragmentationSpectraLevel::Two)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(mz_array.to_vec(), vec![60.5425, 119.0857]);
+    /// assert_eq!(intensity_array.to_vec(), vec![2.4E5, 3.3E5]);
+    /// assert!(mascot_generic_formats[0].mz_array(FragmentationSpectraLevel::One).is_none());
+    /// ```
+    #[cfg(feature = "ndarray")]
+    pub fn mz_array(&self, level: FragmentationSpectraLevel) -> Option<ndarray::ArrayView1<'_, F>> {
+        self.get_level(level)
+            .map(|data| ndarray::ArrayView1::from(data.mass_divided_by_charge_ratios()))
+    }
+
+    /// Returns a view of the intensities of the requested fragmentation level, if available.
+    ///
+    /// The view borrows the peaks of the MGF object, without copying them.
+    ///
+    /// # Arguments
+    /// * `level` - The fragmentation level to retrieve.
+    #[cfg(feature = "ndarray")]
+    pub fn intensity_array(
+        &self,
+        level: FragmentationSpectraLevel,
+    ) -> Option<ndarray::ArrayView1<'_, F>> {
+        self.get_level(level)
+            .map(|data| ndarray::ArrayView1::from(data.fragment_intensities()))
+    }
+
+    /// Returns the peaks of the requested fragmentation level as a `(n, 2)`
+    /// array, whose columns are the mass-charge ratios and the intensities.
+    ///
+    /// As the peaks are stored column-wise, the array is an owned copy.
+    ///
+    /// # Arguments
+    /// * `level` - The fragmentation level to retrieve.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "119.0857 3.3E5",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// let peaks = mascot_generic_formats[0].peaks_array2(FragmentationSpectraLevel::Two).unwrap();
+    ///
+    /// assert_eq!(peaks.shape(), &[2, 2]);
+    /// assert_eq!(peaks[[1, 0]], 119.0857);
+    /// assert_eq!(peaks[[1, 1]], 3.3E5);
+    /// ```
+    #[cfg(feature = "ndarray")]
+    pub fn peaks_array2(&self, level: FragmentationSpectraLevel) -> Option<ndarray::Array2<F>> {
+        self.get_level(level).map(|data| {
+            ndarray::Array2::from_shape_fn((data.len(), 2), |(row, column)| {
+                if column == 0 {
+                    data.mass_divided_by_charge_ratios()[row]
+                } else {
+                    data.fragment_intensities()[row]
+                }
+            })
+        })
+    }
+
     /// Returns a reference to the first fragmentation level, if available.
     pub fn get_first_fragmentation_level(&self) -> Result<&MascotGenericFormatData<F>, String> {
-        if let Some(mgf) = self
-            .data
-            .iter()
-            .find(|mgf| mgf.level() == FragmentationSpectraLevel::One)
-        {
-            Ok(mgf)
-        } else {
-            Err(concat!(
-                "There is no first fragmentation level available for the ",
-                "corrent mascot fragmentation object."
-            )
-            .to_string())
-        }
+        self.get_level(FragmentationSpectraLevel::One)
+            .ok_or_else(|| {
+                concat!(
+                    "There is no first fragmentation level available for the ",
+                    "corrent mascot fragmentation object."
+                )
+                .to_string()
+            })
     }
 
     /// Returns a reference to the second fragmentation level, if available.
     pub fn get_second_fragmentation_level(&self) -> Result<&MascotGenericFormatData<F>, String> {
-        if let Some(mgf) = self
-            .data
-            .iter()
-            .find(|mgf| mgf.level() == FragmentationSpectraLevel::Two)
-        {
-            Ok(mgf)
-        } else {
-            Err(concat!(
-                "There is no second fragmentation level available for the ",
-                "corrent mascot fragmentation object."
-            )
-            .to_string())
-        }
+        self.get_level(FragmentationSpectraLevel::Two)
+            .ok_or_else(|| {
+                concat!(
+                    "There is no second fragmentation level available for the ",
+                    "corrent mascot fragmentation object."
+                )
+                .to_string()
+            })
     }
 
     /// Returns iterator over the mass over charge ratios of the first fragmentation level.
     pub fn first_fragmentation_level_mass_divided_by_charge_ratios_iter(
         &self,
-    ) -> Result<std::slice::Iter<F>, String> {
+    ) -> Result<std::slice::Iter<'_, F>, String> {
         Ok(self
             .get_first_fragmentation_level()?
             .mass_divided_by_charge_ratios_iter())
@@ -124,7 +734,7 @@ impl<
     /// Returns iterator over the mass over charge ratios of the second fragmentation level.
     pub fn second_fragmentation_level_mass_divided_by_charge_ratios_iter(
         &self,
-    ) -> Result<std::slice::Iter<F>, String> {
+    ) -> Result<std::slice::Iter<'_, F>, String> {
         Ok(self
             .get_second_fragmentation_level()?
             .mass_divided_by_charge_ratios_iter())
@@ -133,7 +743,7 @@ impl<
     /// Returns iterator over the intensities of the first fragmentation level.
     pub fn first_fragmentation_level_intensities_iter(
         &self,
-    ) -> Result<std::slice::Iter<F>, String> {
+    ) -> Result<std::slice::Iter<'_, F>, String> {
         Ok(self
             .get_first_fragmentation_level()?
             .fragment_intensities_iter())
@@ -142,7 +752,7 @@ impl<
     /// Returns iterator over the intensities of the second fragmentation level.
     pub fn second_fragmentation_level_intensities_iter(
         &self,
-    ) -> Result<std::slice::Iter<F>, String> {
+    ) -> Result<std::slice::Iter<'_, F>, String> {
         Ok(self
             .get_second_fragmentation_level()?
             .fragment_intensities_iter())
@@ -158,23 +768,608 @@ impl<
         self.data.iter().map(|d| d.level()).max().unwrap()
     }
 
+    /// Returns whether the current MGF has first level fragmentation data.
+    pub fn has_first_level(&self) -> bool {
+        self.min_fragmentation_level() == FragmentationSpectraLevel::One
+    }
+
     /// Returns whether the current MGF has second level fragmentation data.
     pub fn has_second_level(&self) -> bool {
         self.max_fragmentation_level() == FragmentationSpectraLevel::Two
     }
 
-    /// Returns indices associated to matching mass-charge ratios of the second level.
+    /// Returns which of the fragmentation levels are present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// for mascot_generic_format in mascot_generic_formats.iter() {
+    ///     let level_profile = mascot_generic_format.level_profile();
+    ///     assert_eq!(level_profile.has_first_level(), mascot_generic_format.has_first_level());
+    ///     assert_eq!(level_profile.has_second_level(), mascot_generic_format.has_second_level());
+    /// }
+    /// ```
+    pub fn level_profile(&self) -> LevelProfile {
+        LevelProfile::new(self.has_first_level(), self.has_second_level())
+    }
+
+    /// Returns whether the precursor appears among the peaks of the second
+    /// fragmentation level, which hints at an incomplete fragmentation.
+    ///
+    /// Returns `None` when there is no second fragmentation level. As the
+    /// parent ion mass is a required field, the precursor is always known.
     ///
     /// # Arguments
-    /// * `other` - The other [`MascotGenericFormat`] object.
-    /// * `tolerance` - The tolerance to use when matching mass-charge ratios.
-    /// * `shift` - The shift to apply to the mass-charge ratios of the other
+    /// * `tolerance` - The absolute tolerance on the mass-charge ratios.
     ///
-    /// # Safety
-    /// This function is unsafe because it does not check that the
-    /// mass-charge ratios are sorted in ascending order. The results
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let document = |peaks: [&'static str; 2]| {
+    ///     MGFVec::<usize, f64>::try_from_iter([
+    ///         "BEGIN IONS", "FEATURE_ID=1", "PEPMASS=381.0795", "CHARGE=1",
+    ///         "RTINSECONDS=37.083", "MSLEVEL=2", peaks[0], peaks[1], "END IONS",
+    ///     ]).unwrap()
+    /// };
+    ///
+    /// let fragmented = document(["60.5425 2.4E5", "119.0857 3.3E5"]);
+    /// assert_eq!(fragmented[0].precursor_in_ms2(0.01), Some(false));
+    ///
+    /// let incomplete = document(["119.0857 3.3E5", "381.0801 1.2E5"]);
+    /// assert_eq!(incomplete[0].precursor_in_ms2(0.01), Some(true));
+    /// assert_eq!(incomplete[0].precursor_in_ms2(0.0001), Some(false));
+    ///
+    /// let first_level_only = MGFVec::<usize, f64>::try_from_iter([
+    ///     "BEGIN IONS", "FEATURE_ID=1", "PEPMASS=381.0795", "CHARGE=1",
+    ///     "RTINSECONDS=37.083", "MSLEVEL=1", "SCANS=1", "381.0795 7.8E8", "END IONS",
+    /// ]).unwrap();
+    /// assert_eq!(first_level_only[0].precursor_in_ms2(0.01), None);
+    /// ```
+    pub fn precursor_in_ms2(&self, tolerance: F) -> Option<bool> {
+        self.get_level(FragmentationSpectraLevel::Two)
+            .map(|data| data.contains_mz_within(self.precursor_mz(), tolerance))
+    }
+
+    /// Checks that the fragments of the second fragmentation level do not
+    /// exceed the parent ion mass by more than the provided tolerance.
+    ///
+    /// This check is not performed while parsing, and is meant as an opt-in
+    /// sanity check for documents without a first fragmentation level, whose
+    /// parent ion mass is not otherwise validated against the peaks.
+    /// Spectra without a second fragmentation level trivially pass the check.
+    ///
+    /// # Arguments
+    /// * `tolerance` - The tolerance by which a fragment may exceed the parent ion mass.
+    ///
+    /// # Errors
+    /// * If the maximum mass divided by charge ratio of the second fragmentation
+    ///   level is larger than the parent ion mass plus the tolerance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::default();
+    ///
+    /// for line in [
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "381.0801 3.3E5",
+    ///     "END IONS",
+    /// ] {
+    ///     mascot_generic_format_builder.digest_line(line).unwrap();
+    /// }
+    ///
+    /// let mascot_generic_format = mascot_generic_format_builder.build().unwrap();
+    ///
+    /// assert!(mascot_generic_format.validate_precursor_within_range(0.01).is_ok());
+    /// assert!(mascot_generic_format.validate_precursor_within_range(0.0001).is_err());
+    /// ```
+    pub fn validate_precursor_within_range(&self, tolerance: F) -> Result<(), String> {
+        let Ok(second_level) = self.get_second_fragmentation_level() else {
+            return Ok(());
+        };
+        let max_mass_divided_by_charge_ratio = second_level.max_mass_divided_by_charge_ratio();
+        if max_mass_divided_by_charge_ratio > self.precursor_mz() + tolerance {
+            return Err(format!(
+                concat!(
+                    "The maximum mass divided by charge ratio of the second fragmentation ",
+                    "level of the feature {:?} is {:?}, which exceeds the parent ion mass ",
+                    "{:?} by more than the tolerance {:?}."
+                ),
+                self.feature_id(),
+                max_mass_divided_by_charge_ratio,
+                self.precursor_mz(),
+                tolerance
+            ));
+        }
+        Ok(())
+    }
+
+    /// Writes the peaks of the requested fragmentation level as CSV.
+    ///
+    /// The output has a `mz,intensity` header followed by one row per peak.
+    ///
+    /// # Arguments
+    /// * `writer` - The writer to write the CSV to.
+    /// * `level` - The fragmentation level whose peaks should be written.
+    ///
+    /// # Errors
+    /// * If the requested fragmentation level is not available.
+    /// * If the writer fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    /// let mascot_generic_format = &mascot_generic_formats[0];
+    ///
+    /// let mut csv = Vec::new();
+    /// mascot_generic_format.peaks_to_csv(&mut csv, FragmentationSpectraLevel::Two).unwrap();
+    /// let csv = String::from_utf8(csv).unwrap();
+    ///
+    /// let mut lines = csv.lines();
+    /// assert_eq!(lines.next(), Some("mz,intensity"));
+    /// assert_eq!(
+    ///     lines.count(),
+    ///     mascot_generic_format.get_second_fragmentation_level().unwrap().mass_divided_by_charge_ratios().len()
+    /// );
+    ///
+    /// assert!(mascot_generic_format.peaks_to_csv(Vec::new(), FragmentationSpectraLevel::One).is_err());
+    /// ```
+    pub fn peaks_to_csv<W: Write>(
+        &self,
+        mut writer: W,
+        level: FragmentationSpectraLevel,
+    ) -> std::io::Result<()>
+    where
+        F: Display,
+    {
+        let data = self
+            .data
+            .iter()
+            .find(|data| data.level() == level)
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!(
+                        "The fragmentation level {:?} is not available for feature {:?}.",
+                        level,
+                        self.feature_id()
+                    ),
+                )
+            })?;
+
+        writeln!(writer, "mz,intensity")?;
+        for (mass_divided_by_charge_ratio, fragment_intensity) in data
+            .mass_divided_by_charge_ratios_iter()
+            .zip(data.fragment_intensities_iter())
+        {
+            writeln!(
+                writer,
+                "{},{}",
+                mass_divided_by_charge_ratio, fragment_intensity
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Maps the fragment intensities of all the levels through the provided function.
+    ///
+    /// The MGF object is left untouched when any of the mapped intensities
+    /// is NaN, zero or negative.
+    ///
+    /// # Arguments
+    /// * `map` - The function to apply to each intensity.
+    ///
+    /// # Errors
+    /// * If any of the mapped intensities is NaN, zero or negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let mut mascot_generic_format = mascot_generic_formats[0].clone();
+    /// mascot_generic_format.map_intensities(|intensity| intensity * 2.0).unwrap();
+    ///
+    /// for (level, original_level) in mascot_generic_format.levels().zip(mascot_generic_formats[0].levels()) {
+    ///     for (intensity, original_intensity) in level
+    ///         .fragment_intensities_iter()
+    ///         .zip(original_level.fragment_intensities_iter())
+    ///     {
+    ///         assert_eq!(*intensity, original_intensity * 2.0);
+    ///     }
+    /// }
+    ///
+    /// assert!(mascot_generic_format.map_intensities(|_| f64::NAN).is_err());
+    /// ```
+    pub fn map_intensities<G: Fn(F) -> F>(&mut self, map: G) -> Result<(), String>
+    where
+        F: NaN,
+    {
+        let mut data = self.data.clone();
+        for data in data.iter_mut() {
+            data.map_intensities(&map)?;
+        }
+        self.data = data;
+        Ok(())
+    }
+
+    /// Returns the MSP (NIST) representation of the MGF object.
+    ///
+    /// The highest available fragmentation level is written as the
+    /// spectrum. The `NAME` extra field, such as the compound name read from
+    /// an MSP library, is used as the name of the entry, falling back to the
+    /// feature ID when the entry has none.
+    /// The retention time is written in seconds, as stored in the metadata.
+    /// The `PRECURSORTYPE` line is written only when the adduct of the entry
+    /// is known, i.e. when the `ADDUCT` field was retained while parsing.
+    /// The entry is terminated by a blank line, so that several entries
+    /// can be concatenated into a library.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1+",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "119.0857 3.3E5",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// assert_eq!(
+    ///     mascot_generic_formats[0].to_msp_string(),
+    ///     concat!(
+    ///         "NAME: 1\n",
+    ///         "PRECURSORMZ: 381.0795\n",
+    ///         "CHARGE: 1+\n",
+    ///         "RETENTIONTIME: 37.083\n",
+    ///         "Num Peaks: 2\n",
+    ///         "60.5425 240000\n",
+    ///         "119.0857 330000\n",
+    ///         "\n",
+    ///     )
+    /// );
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter_with_options(
+    ///     [
+    ///         "BEGIN IONS",
+    ///         "FEATURE_ID=1",
+    ///         "PEPMASS=381.0795",
+    ///         "ADDUCT=[M+H]+",
+    ///         "RTINSECONDS=37.083",
+    ///         "MSLEVEL=2",
+    ///         "60.5425 2.4E5",
+    ///         "119.0857 3.3E5",
+    ///         "END IONS",
+    ///     ],
+    ///     ParseOptions::default().with_infer_charge_from_adduct(true),
+    /// ).unwrap();
+    ///
+    /// assert!(mascot_generic_formats[0]
+    ///     .to_msp_string()
+    ///     .starts_with("NAME: 1\nPRECURSORMZ: 381.0795\nPRECURSORTYPE: [M+H]+\nCHARGE: 1+\n"));
+    /// ```
+    pub fn to_msp_string(&self) -> String
+    where
+        I: Display,
+        F: Display,
+    {
+        let level = self.max_fragmentation_level();
+        let data = self.data.iter().find(|data| data.level() == level).unwrap();
+        let charge = self.charge().to_string();
+
+        let mut msp = match self.extra().get("NAME") {
+            Some(name) => format!("NAME: {}\n", name),
+            None => format!("NAME: {}\n", self.feature_id()),
+        };
+        msp.push_str(&format!("PRECURSORMZ: {}\n", self.precursor_mz()));
+        if let Some(adduct) = self.extra().get("ADDUCT") {
+            msp.push_str(&format!("PRECURSORTYPE: {}\n", adduct));
+        }
+        msp.push_str(&format!(
+            "CHARGE: {}\nRETENTIONTIME: {}\nNum Peaks: {}\n",
+            charge.strip_prefix("CHARGE=").unwrap_or(&charge),
+            self.retention_time(),
+            data.mass_divided_by_charge_ratios().len()
+        ));
+        for (mass_divided_by_charge_ratio, fragment_intensity) in data
+            .mass_divided_by_charge_ratios_iter()
+            .zip(data.fragment_intensities_iter())
+        {
+            msp.push_str(&format!(
+                "{} {}\n",
+                mass_divided_by_charge_ratio, fragment_intensity
+            ));
+        }
+        msp.push('\n');
+
+        msp
+    }
+
+    /// Writes the MGF representation of the MGF object.
+    ///
+    /// Each fragmentation level is written as its own `BEGIN IONS` section,
+    /// repeating the metadata, as done by the tools exporting MGF documents.
+    /// All sections but the last one are marked with `SCANS=-1`, so that they
+    /// are read back as parts of the same entry, while the last one carries
+    /// the feature ID as scans and the merged scans metadata.
+    /// Each section is followed by a blank line.
+    ///
+    /// When the entry was parsed with [`ParseOptions::with_preserve_field_order`],
+    /// and it still has as many fragmentation levels as recorded sections,
+    /// the `KEY=VALUE` lines of each section are instead written in the order
+    /// they appeared in the document, followed by the ones that were not in it.
+    /// The values always come from the current metadata and are written with
+    /// the requested precision, so that unedited entries round-trip exactly
+    /// when the document was written with the same precision.
+    ///
+    /// The extra fields are written back as they are. As the strict parse
+    /// options reject the fields they do not recognize, entries carrying extra
+    /// fields must be read back with parse options retaining them, i.e. the
+    /// ones they were read with, such as [`ParseOptions::lenient`]. This is
+    /// also the case of the entries read from MSP libraries.
+    ///
+    /// # Arguments
+    /// * `writer` - The writer to write the MGF representation to.
+    /// * `precision` - The number of decimal places of the parent ion mass, the retention time, the mass-charge ratios and the intensities.
+    ///
+    /// # Errors
+    /// * If the writer fails.
+    pub fn write_to<W: Write>(&self, mut writer: W, precision: usize) -> std::io::Result<()>
+    where
+        I: Display + From<usize>,
+        F: Display,
+    {
+        let raw_fields = self.metadata.raw_fields();
+        let replays_raw_fields = !raw_fields.is_empty() && raw_fields.len() == self.data.len();
+
+        for (index, data) in self.data.iter().enumerate() {
+            writeln!(writer, "BEGIN IONS")?;
+            // The metadata lines of the section, in the fixed order, along
+            // with the key each of them is recorded under.
+            let mut lines: Vec<(String, String)> = Vec::new();
+            lines.push((
+                "FEATURE_ID".to_string(),
+                format!("FEATURE_ID={}", self.feature_id()),
+            ));
+            lines.push((
+                "PEPMASS".to_string(),
+                format!("PEPMASS={:.*}", precision, self.precursor_mz()),
+            ));
+            let mut charge = self.charge().to_string();
+            for alternative_charge in self.alternative_charges() {
+                let alternative_charge = alternative_charge.to_string();
+                charge.push_str(" and ");
+                charge.push_str(
+                    alternative_charge
+                        .strip_prefix("CHARGE=")
+                        .unwrap_or(&alternative_charge),
+                );
+            }
+            lines.push(("CHARGE".to_string(), charge));
+            if let Some(ion_mode) = self.ion_mode() {
+                lines.push(("IONMODE".to_string(), ion_mode.to_string()));
+            }
+            lines.push((
+                "RTINSECONDS".to_string(),
+                format!("RTINSECONDS={:.*}", precision, self.retention_time()),
+            ));
+            if let Some(spec_type) = data.spec_type() {
+                lines.push(("SPECTYPE".to_string(), format!("SPECTYPE={}", spec_type)));
+            } else if data.level() == FragmentationSpectraLevel::One {
+                lines.push(("SPECTYPE".to_string(), "SPECTYPE=CORRELATED MS".to_string()));
+            }
+            lines.push((
+                "MSLEVEL".to_string(),
+                format!("MSLEVEL={}", data.level().as_u8()),
+            ));
+            if let Some(filename) = self.filename() {
+                lines.push(("FILENAME".to_string(), format!("FILENAME={}", filename)));
+            }
+            for (key, value) in self.extra() {
+                lines.push((key.to_string(), format!("{}={}", key, value)));
+            }
+            let is_last_section = index + 1 == self.data.len();
+            if is_last_section {
+                lines.push((
+                    "SCANS".to_string(),
+                    format!("SCANS={}", self.scan_number().unwrap_or(self.feature_id())),
+                ));
+            } else {
+                lines.push(("SCANS".to_string(), "SCANS=-1".to_string()));
+            }
+            if let Some(merge_scans_metadata) = self
+                .metadata
+                .merged_scans_metadata()
+                .filter(|_| is_last_section)
+            {
+                lines.push((
+                    "MERGED_SCANS".to_string(),
+                    format!(
+                        "MERGED_SCANS={}",
+                        merge_scans_metadata
+                            .scans_iter()
+                            .map(|scan| scan.to_string())
+                            .collect::<Vec<String>>()
+                            .join(",")
+                    ),
+                ));
+                lines.push((
+                    "MERGED_STATS".to_string(),
+                    format!(
+                        concat!(
+                            "MERGED_STATS={} / {} ({} removed due to low quality, ",
+                            "{} removed due to low cosine)."
+                        ),
+                        merge_scans_metadata.num_merged(),
+                        merge_scans_metadata.total_scans(),
+                        merge_scans_metadata.removed_due_to_low_quality(),
+                        merge_scans_metadata.removed_due_to_low_cosine()
+                    ),
+                ));
+            }
+            if replays_raw_fields {
+                // The lines follow the order of the keys in the document, while
+                // their values come from the current metadata, so that edits are
+                // kept. The lines whose key was not in the document follow them.
+                let position = |key: &str| {
+                    raw_fields[index]
+                        .iter()
+                        .position(|(raw_key, _)| {
+                            let raw_key = raw_key.trim();
+                            raw_key.eq_ignore_ascii_case(key)
+                                || raw_key.eq_ignore_ascii_case("RTINMINUTES")
+                                    && key == "RTINSECONDS"
+                        })
+                        .unwrap_or(usize::MAX)
+                };
+                lines.sort_by_key(|(key, _)| position(key));
+            }
+            for (_, line) in &lines {
+                writeln!(writer, "{}", line)?;
+            }
+            for (mass_divided_by_charge_ratio, fragment_intensity) in data
+                .mass_divided_by_charge_ratios_iter()
+                .zip(data.fragment_intensities_iter())
+            {
+                writeln!(
+                    writer,
+                    "{:.*} {:.*}",
+                    precision, mass_divided_by_charge_ratio, precision, fragment_intensity
+                )?;
+            }
+            writeln!(writer, "END IONS")?;
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the MGF representation of the MGF object.
+    ///
+    /// # Arguments
+    /// * `precision` - The number of decimal places of the parent ion mass, the retention time, the mass-charge ratios and the intensities.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1+",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "119.0857 3.3E5",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// assert_eq!(
+    ///     mascot_generic_formats[0].to_mgf_string(2),
+    ///     concat!(
+    ///         "BEGIN IONS\n",
+    ///         "FEATURE_ID=1\n",
+    ///         "PEPMASS=381.08\n",
+    ///         "CHARGE=1+\n",
+    ///         "RTINSECONDS=37.08\n",
+    ///         "MSLEVEL=2\n",
+    ///         "SCANS=1\n",
+    ///         "60.54 240000.00\n",
+    ///         "119.09 330000.00\n",
+    ///         "END IONS\n",
+    ///         "\n",
+    ///     )
+    /// );
+    /// ```
+    pub fn to_mgf_string(&self, precision: usize) -> String
+    where
+        I: Display + From<usize>,
+        F: Display,
+    {
+        let mut mgf = Vec::new();
+        self.write_to(&mut mgf, precision).unwrap();
+        String::from_utf8(mgf).unwrap()
+    }
+
+    /// Returns indices associated to matching mass-charge ratios of the second level.
+    ///
+    /// # Arguments
+    /// * `other` - The other [`MascotGenericFormat`] object.
+    /// * `tolerance` - The tolerance to use when matching mass-charge ratios.
+    /// * `shift` - The shift to apply to the mass-charge ratios of the other
+    ///
+    /// # Safety
+    /// This function is unsafe because it does not check that the
+    /// mass-charge ratios are sorted in ascending order. The results
     /// when the requirement is not met are undefined. Also, it does not
     /// check whether the MGF files have a second level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=200.0",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "50.0 1.0",
+    ///     "100.0 2.0",
+    ///     "150.0 3.0",
+    ///     "END IONS",
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=2",
+    ///     "PEPMASS=210.0",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "20.0 1.0",
+    ///     "30.0 1.0",
+    ///     "100.0 2.0",
+    ///     "160.0 3.0",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// let (first, second) = (&mascot_generic_formats[0], &mascot_generic_formats[1]);
+    ///
+    /// assert_eq!(first.find_sorted_matches(second, 0.01, 0.0).unwrap(), vec![(1, 2)]);
+    /// assert_eq!(first.find_sorted_matches(second, 0.01, -10.0).unwrap(), vec![(2, 3)]);
+    /// ```
     pub fn find_sorted_matches(
         &self,
         other: &MascotGenericFormat<I, F>,
@@ -191,148 +1386,2934 @@ impl<
         {
             let low_bound = first_mz - tolerance;
             let high_bound = first_mz + tolerance;
+            let start = lowest_index;
 
             for (j, shifted_second_mz) in other
                 .second_fragmentation_level_mass_divided_by_charge_ratios_iter()?
-                .skip(lowest_index)
+                .skip(start)
                 .copied()
                 .map(|second_mz| second_mz + shift)
                 .enumerate()
             {
+                // The enumeration starts after the skipped values, so we
+                // convert the index back to a position in the whole spectrum.
+                let j = start + j;
                 if shifted_second_mz > high_bound {
                     break;
                 }
                 if shifted_second_mz < low_bound {
-                    lowest_index = j;
+                    // As the values are sorted, this peak cannot match any of
+                    // the following peaks of the current spectrum either.
+                    lowest_index = j + 1;
                     continue;
                 }
                 matches.push((i, j));
             }
         }
 
-        Ok(matches)
+        Ok(matches)
+    }
+
+    /// Returns the number of peaks of the second level of this MGF object
+    /// matching a peak of the second level of the other MGF object, without
+    /// computing a similarity score.
+    ///
+    /// The matches are found as in [`MascotGenericFormat::find_sorted_matches`],
+    /// and each peak of either spectrum is counted at most once, greedily
+    /// pairing each peak with the first peak of the other spectrum that was
+    /// not already paired.
+    ///
+    /// # Arguments
+    /// * `other` - The other [`MascotGenericFormat`] object.
+    /// * `tolerance` - The tolerance to use when matching mass-charge ratios.
+    /// * `shift` - The shift to apply to the mass-charge ratios of the other spectrum.
+    ///
+    /// # Errors
+    /// * If either of the MGF objects does not have a second level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=200.0",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "50.0 1.0",
+    ///     "100.0 2.0",
+    ///     "100.005 2.0",
+    ///     "150.0 3.0",
+    ///     "END IONS",
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=2",
+    ///     "PEPMASS=210.0",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "20.0 1.0",
+    ///     "50.002 1.0",
+    ///     "100.002 2.0",
+    ///     "160.0 3.0",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// let (first, second) = (&mascot_generic_formats[0], &mascot_generic_formats[1]);
+    ///
+    /// // Both 100.0 and 100.005 are within the tolerance of 100.002, which is
+    /// // only counted once, so the matches are 50.0 and one of the two.
+    /// assert_eq!(first.find_sorted_matches(second, 0.01, 0.0).unwrap().len(), 3);
+    /// assert_eq!(first.num_matching_peaks(second, 0.01, 0.0).unwrap(), 2);
+    /// assert_eq!(first.num_matching_peaks(second, 0.01, -10.0).unwrap(), 1);
+    /// assert_eq!(first.num_matching_peaks(second, 0.001, 0.0).unwrap(), 0);
+    /// ```
+    pub fn num_matching_peaks(
+        &self,
+        other: &Self,
+        tolerance: F,
+        shift: F,
+    ) -> Result<usize, String> {
+        let mut other_used = vec![false; other.get_second_fragmentation_level()?.len()];
+        let mut last_matched = None;
+        let mut number_of_matches = 0;
+
+        // The matches are sorted by the index of the peaks of this spectrum,
+        // so that each of them is paired with the first available peak.
+        for (i, j) in self.find_sorted_matches(other, tolerance, shift)? {
+            if last_matched == Some(i) || other_used[j] {
+                continue;
+            }
+            other_used[j] = true;
+            last_matched = Some(i);
+            number_of_matches += 1;
+        }
+
+        Ok(number_of_matches)
+    }
+
+    /// Returns the estimated purity of the precursor, i.e. the fraction of
+    /// the intensity of the first fragmentation level within the isolation
+    /// window that belongs to the precursor peak.
+    ///
+    /// The isolation window is centered on the parent ion mass, and the
+    /// precursor peak is the peak of the window closest to the parent ion mass.
+    /// A purity close to one indicates that the second fragmentation level
+    /// is unlikely to be contaminated by co-isolated ions.
+    ///
+    /// Returns `None` when there is no first fragmentation level, when no
+    /// peak of the first fragmentation level lies within the isolation window,
+    /// or when all of the peaks within the window have a zero intensity, as
+    /// allowed by [`ParseOptions::with_allow_zero_values`].
+    ///
+    /// # Arguments
+    /// * `isolation_width` - The total width of the isolation window.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let document = |first_level_peaks: &[&str]| {
+    ///     let mut lines = vec![
+    ///         "BEGIN IONS", "FEATURE_ID=1", "PEPMASS=381.0795", "CHARGE=1",
+    ///         "RTINSECONDS=37.083", "MSLEVEL=1", "SCANS=-1",
+    ///     ];
+    ///     lines.extend_from_slice(first_level_peaks);
+    ///     lines.extend_from_slice(&[
+    ///         "END IONS", "BEGIN IONS", "FEATURE_ID=1", "PEPMASS=381.0795", "CHARGE=1",
+    ///         "RTINSECONDS=37.083", "MSLEVEL=2", "SCANS=1", "60.5425 2.4E5", "END IONS",
+    ///     ]);
+    ///     MGFVec::<usize, f64>::try_from_iter(lines).unwrap()
+    /// };
+    ///
+    /// let clean = document(&["381.0795 8.0E8", "390.2 1.0E8"]);
+    /// assert_eq!(clean[0].precursor_purity(2.0), Some(1.0));
+    ///
+    /// let contaminated = document(&["381.0795 6.0E8", "381.5 2.0E8", "390.2 1.0E8"]);
+    /// assert_eq!(contaminated[0].precursor_purity(2.0), Some(0.75));
+    ///
+    /// let without_first_level = MGFVec::<usize, f64>::try_from_iter([
+    ///     "BEGIN IONS", "FEATURE_ID=1", "PEPMASS=381.0795", "CHARGE=1",
+    ///     "RTINSECONDS=37.083", "MSLEVEL=2", "60.5425 2.4E5", "END IONS",
+    /// ]).unwrap();
+    /// assert_eq!(without_first_level[0].precursor_purity(2.0), None);
+    ///
+    /// let zero_intensities = MGFVec::<usize, f64>::try_from_iter_with_options(
+    ///     [
+    ///         "BEGIN IONS", "FEATURE_ID=1", "PEPMASS=381.0795", "CHARGE=1",
+    ///         "RTINSECONDS=37.083", "MSLEVEL=1", "SCANS=-1", "381.0795 0.0", "390.2 1.0E8",
+    ///         "END IONS", "BEGIN IONS", "FEATURE_ID=1", "PEPMASS=381.0795", "CHARGE=1",
+    ///         "RTINSECONDS=37.083", "MSLEVEL=2", "SCANS=1", "60.5425 2.4E5", "END IONS",
+    ///     ],
+    ///     ParseOptions::default().with_allow_zero_values(true),
+    /// ).unwrap();
+    /// assert_eq!(zero_intensities[0].precursor_purity(2.0), None);
+    /// ```
+    pub fn precursor_purity(&self, isolation_width: F) -> Option<F>
+    where
+        F: Float + From<u8>,
+    {
+        let first_level = self.get_level(FragmentationSpectraLevel::One)?;
+        let precursor_mz = self.precursor_mz();
+        let half_width = isolation_width / F::from(2);
+
+        let mut total_intensity = F::ZERO;
+        // The peak closest to the parent ion mass, as its distance and intensity.
+        let mut precursor_peak: Option<(F, F)> = None;
+        for (&mass_divided_by_charge_ratio, &fragment_intensity) in first_level
+            .mass_divided_by_charge_ratios_iter()
+            .zip(first_level.fragment_intensities_iter())
+        {
+            let distance = (mass_divided_by_charge_ratio - precursor_mz).abs();
+            if distance > half_width {
+                continue;
+            }
+            total_intensity = total_intensity + fragment_intensity;
+            if precursor_peak.map_or(true, |(closest_distance, _)| distance < closest_distance) {
+                precursor_peak = Some((distance, fragment_intensity));
+            }
+        }
+
+        if total_intensity == F::ZERO {
+            return None;
+        }
+
+        precursor_peak.map(|(_, precursor_intensity)| precursor_intensity / total_intensity)
+    }
+
+    /// Returns the modified cosine similarity of the second levels of the two
+    /// MGF objects, together with the number of matched peaks.
+    ///
+    /// Two peaks match when their mass-charge ratios are within the tolerance
+    /// either directly or after shifting the peaks of `other` by the difference
+    /// between the two parent ion masses. Each peak is matched at most once,
+    /// greedily picking first the matches with the largest product of intensities.
+    /// The score is the sum of the products of the intensities of the matched
+    /// peaks, normalized by the norms of the two intensity vectors.
+    ///
+    /// # Arguments
+    /// * `other` - The other [`MascotGenericFormat`] object.
+    /// * `tolerance` - The tolerance to use when matching mass-charge ratios.
+    ///
+    /// # Errors
+    /// * If either of the MGF objects does not have a second level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=200.0",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "50.0 1.0",
+    ///     "100.0 2.0",
+    ///     "150.0 3.0",
+    ///     "END IONS",
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=2",
+    ///     "PEPMASS=210.0",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "50.0 1.0",
+    ///     "100.0 2.0",
+    ///     "160.0 3.0",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// let (score, matches) = mascot_generic_formats[0]
+    ///     .modified_cosine(&mascot_generic_formats[1], 0.01)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(matches, 3);
+    /// assert!((score - 1.0).abs() < 1e-12);
+    /// ```
+    pub fn modified_cosine(&self, other: &Self, tolerance: F) -> Result<(F, usize), String>
+    where
+        F: Float,
+    {
+        let intensities = self
+            .get_second_fragmentation_level()?
+            .fragment_intensities();
+        let other_intensities = other
+            .get_second_fragmentation_level()?
+            .fragment_intensities();
+        let shift = self.precursor_mz() - other.precursor_mz();
+
+        // We collect both the direct and the shifted matches.
+        let matches = self
+            .find_sorted_matches(other, tolerance, F::ZERO)?
+            .into_iter()
+            .chain(self.find_sorted_matches(other, tolerance, shift)?)
+            .collect::<Vec<(usize, usize)>>();
+
+        Ok(greedy_cosine(intensities, other_intensities, matches))
+    }
+
+    /// Returns the weighted cosine similarity of the second levels of the two MGF objects.
+    ///
+    /// Each peak is weighted by `mz^mz_power * intensity^intensity_power`, and
+    /// two peaks match when their mass-charge ratios are within the tolerance.
+    /// Each peak is matched at most once, greedily picking first the matches
+    /// with the largest product of weights. The score is the sum of the products
+    /// of the weights of the matched peaks, normalized by the norms of the two
+    /// weight vectors. A `mz_power` of zero and an `intensity_power` of one half
+    /// reproduce the common square root intensity weighting.
+    ///
+    /// # Arguments
+    /// * `other` - The other [`MascotGenericFormat`] object.
+    /// * `tolerance` - The tolerance to use when matching mass-charge ratios.
+    /// * `mz_power` - The exponent of the mass-charge ratios in the weights.
+    /// * `intensity_power` - The exponent of the intensities in the weights.
+    ///
+    /// # Errors
+    /// * If either of the MGF objects does not have a second level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=400.0",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "100.0 4.0",
+    ///     "200.0 9.0",
+    ///     "END IONS",
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=2",
+    ///     "PEPMASS=400.0",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "100.0 1.0",
+    ///     "200.0 16.0",
+    ///     "300.0 4.0",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// let (first, second) = (&mascot_generic_formats[0], &mascot_generic_formats[1]);
+    ///
+    /// // With square root intensity weighting, the weights are [2, 3] and [1, 4, 2].
+    /// let score = first.weighted_cosine(second, 0.01, 0.0, 0.5).unwrap();
+    /// let expected = (2.0 * 1.0 + 3.0 * 4.0) / ((2.0f64 * 2.0 + 3.0 * 3.0).sqrt() * (1.0f64 + 16.0 + 4.0).sqrt());
+    /// assert!((score - expected).abs() < 1e-12);
+    ///
+    /// // With linear weighting, the weights are [400, 1800] and [100, 3200, 1200].
+    /// let score = first.weighted_cosine(second, 0.01, 1.0, 1.0).unwrap();
+    /// let expected = (400.0 * 100.0 + 1800.0 * 3200.0)
+    ///     / ((400.0f64 * 400.0 + 1800.0 * 1800.0).sqrt()
+    ///         * (100.0f64 * 100.0 + 3200.0 * 3200.0 + 1200.0 * 1200.0).sqrt());
+    /// assert!((score - expected).abs() < 1e-12);
+    ///
+    /// // Extreme exponents may yield NaN weights, which are never matched.
+    /// assert!(first.weighted_cosine(second, 0.01, f64::NEG_INFINITY, f64::INFINITY).is_ok());
+    /// ```
+    pub fn weighted_cosine(
+        &self,
+        other: &Self,
+        tolerance: F,
+        mz_power: F,
+        intensity_power: F,
+    ) -> Result<F, String>
+    where
+        F: Float,
+    {
+        let weights = |data: &MascotGenericFormatData<F>| {
+            data.mass_divided_by_charge_ratios_iter()
+                .zip(data.fragment_intensities_iter())
+                .map(|(&mass_divided_by_charge_ratio, &fragment_intensity)| {
+                    mass_divided_by_charge_ratio.powf(mz_power)
+                        * fragment_intensity.powf(intensity_power)
+                })
+                .collect::<Vec<F>>()
+        };
+        let self_weights = weights(self.get_second_fragmentation_level()?);
+        let other_weights = weights(other.get_second_fragmentation_level()?);
+        let matches = self.find_sorted_matches(other, tolerance, F::ZERO)?;
+
+        Ok(greedy_cosine(&self_weights, &other_weights, matches).0)
+    }
+
+    /// Returns the reverse cosine similarity of the second levels of the query,
+    /// i.e. this MGF object, and of the provided library spectrum.
+    ///
+    /// The peaks are matched as in [`weighted_cosine`](Self::weighted_cosine)
+    /// with the intensities as weights, but the norm of the library spectrum
+    /// only includes its matched peaks, so that the library peaks which are not
+    /// explained by the query do not lower the score. Unlike the symmetric
+    /// cosine, the score therefore depends on which spectrum is the library.
+    /// When no peak matches, the score is zero.
+    ///
+    /// # Arguments
+    /// * `library` - The library [`MascotGenericFormat`] object.
+    /// * `tolerance` - The tolerance to use when matching mass-charge ratios.
+    ///
+    /// # Errors
+    /// * If either of the MGF objects does not have a second level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=400.0",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "100.0 1.0",
+    ///     "200.0 1.0",
+    ///     "END IONS",
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=2",
+    ///     "PEPMASS=400.0",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "100.0 1.0",
+    ///     "200.0 1.0",
+    ///     "300.0 10.0",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// let (query, library) = (&mascot_generic_formats[0], &mascot_generic_formats[1]);
+    ///
+    /// // The unmatched library peak at 300.0 lowers the symmetric score only.
+    /// let symmetric = query.weighted_cosine(library, 0.01, 0.0, 1.0).unwrap();
+    /// assert!((symmetric - 2.0 / (2.0f64.sqrt() * 102.0f64.sqrt())).abs() < 1e-12);
+    /// assert!((query.reverse_cosine(library, 0.01).unwrap() - 1.0).abs() < 1e-12);
+    ///
+    /// // With the roles swapped, the unmatched query peak lowers the score.
+    /// let swapped = library.reverse_cosine(query, 0.01).unwrap();
+    /// assert!((swapped - symmetric).abs() < 1e-12);
+    /// ```
+    pub fn reverse_cosine(&self, library: &Self, tolerance: F) -> Result<F, String>
+    where
+        F: Float,
+    {
+        let intensities = self
+            .get_second_fragmentation_level()?
+            .fragment_intensities();
+        let library_intensities = library
+            .get_second_fragmentation_level()?
+            .fragment_intensities();
+        let matches = greedy_matches(
+            intensities,
+            library_intensities,
+            self.find_sorted_matches(library, tolerance, F::ZERO)?,
+        );
+        if matches.is_empty() {
+            return Ok(F::ZERO);
+        }
+
+        let score = matches.iter().fold(F::ZERO, |score, &(i, j)| {
+            score + intensities[i] * library_intensities[j]
+        });
+        let library_norm = norm(matches.iter().map(|&(_, j)| library_intensities[j]));
+
+        Ok(score / (norm(intensities.iter().copied()) * library_norm))
+    }
+
+    /// Returns whether the two MGF objects are equal within the provided tolerances.
+    ///
+    /// The metadata is compared as described in
+    /// [`MascotGenericFormatMetadata::approx_eq`], and the spectra of each
+    /// level as described in [`MascotGenericFormatData::approx_eq`]: in
+    /// particular, spectra with a different number of peaks are never equal.
+    ///
+    /// # Arguments
+    /// * `other` - The other MGF object.
+    /// * `mz_tolerance` - The absolute tolerance on the mass divided by charge ratios.
+    /// * `relative_tolerance` - The relative tolerance on the intensities and the retention time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let mut scaled = mascot_generic_formats[0].clone();
+    /// scaled.map_intensities(|intensity| intensity * (1.0 + 1e-7)).unwrap();
+    ///
+    /// assert_ne!(scaled, mascot_generic_formats[0]);
+    /// assert!(scaled.approx_eq(&mascot_generic_formats[0], 1e-4, 1e-6));
+    /// assert!(!scaled.approx_eq(&mascot_generic_formats[0], 1e-4, 1e-8));
+    /// assert!(!mascot_generic_formats[1].approx_eq(&mascot_generic_formats[0], 1e-4, 1e-6));
+    /// ```
+    pub fn approx_eq(&self, other: &Self, mz_tolerance: F, relative_tolerance: F) -> bool
+    where
+        F: Float,
+    {
+        self.metadata
+            .approx_eq(&other.metadata, mz_tolerance, relative_tolerance)
+            && self.data.len() == other.data.len()
+            && self
+                .data
+                .iter()
+                .zip(other.data.iter())
+                .all(|(data, other_data)| {
+                    data.approx_eq(other_data, mz_tolerance, relative_tolerance)
+                })
+    }
+}
+
+/// Returns the matches of the two weight vectors selected so that each peak
+/// is matched at most once, greedily picking first the matches with the
+/// largest product of weights. The matches whose product of weights is NaN,
+/// such as the product of a zero and an infinite weight, are ignored.
+///
+/// # Arguments
+/// * `weights` - The weights of the peaks of the first spectrum.
+/// * `other_weights` - The weights of the peaks of the second spectrum.
+/// * `matches` - The candidate matches, as pairs of peak indices.
+fn greedy_matches<F: Float>(
+    weights: &[F],
+    other_weights: &[F],
+    matches: Vec<(usize, usize)>,
+) -> Vec<(usize, usize)> {
+    let mut candidates = matches
+        .into_iter()
+        .map(|(i, j)| (weights[i] * other_weights[j], i, j))
+        .filter(|(weight, _, _)| !weight.is_nan())
+        .collect::<Vec<(F, usize, usize)>>();
+    candidates.sort_by(|(left, _, _), (right, _, _)| {
+        right.partial_cmp(left).unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let mut used = vec![false; weights.len()];
+    let mut other_used = vec![false; other_weights.len()];
+    let mut selected = Vec::new();
+    for (_, i, j) in candidates {
+        if used[i] || other_used[j] {
+            continue;
+        }
+        used[i] = true;
+        other_used[j] = true;
+        selected.push((i, j));
+    }
+    selected
+}
+
+/// Returns the Euclidean norm of the weights.
+///
+/// # Arguments
+/// * `weights` - The weights of the peaks.
+fn norm<F: Float>(weights: impl IntoIterator<Item = F>) -> F {
+    weights
+        .into_iter()
+        .fold(F::ZERO, |norm, weight| norm + weight * weight)
+        .sqrt()
+}
+
+/// Returns the cosine similarity of the two weight vectors restricted to the
+/// provided matches, together with the number of matches used.
+///
+/// Each peak is matched at most once, greedily picking first the matches
+/// with the largest product of weights.
+///
+/// # Arguments
+/// * `weights` - The weights of the peaks of the first spectrum.
+/// * `other_weights` - The weights of the peaks of the second spectrum.
+/// * `matches` - The candidate matches, as pairs of peak indices.
+fn greedy_cosine<F: Float>(
+    weights: &[F],
+    other_weights: &[F],
+    matches: Vec<(usize, usize)>,
+) -> (F, usize) {
+    let selected = greedy_matches(weights, other_weights, matches);
+    let score = selected.iter().fold(F::ZERO, |score, &(i, j)| {
+        score + weights[i] * other_weights[j]
+    });
+
+    (
+        score / (norm(weights.iter().copied()) * norm(other_weights.iter().copied())),
+        selected.len(),
+    )
+}
+
+#[repr(transparent)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MGFVec<I, F> {
+    mascot_generic_formats: Vec<MascotGenericFormat<I, F>>,
+}
+
+impl<I, F> MGFVec<I, F> {
+    pub fn new() -> Self {
+        Self {
+            mascot_generic_formats: Vec::new(),
+        }
+    }
+
+    /// Creates a new empty vector of MGF objects with room for at least
+    /// `capacity` objects without reallocating.
+    ///
+    /// # Arguments
+    /// * `capacity` - The number of MGF objects to preallocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::with_capacity(10);
+    ///
+    /// assert!(mascot_generic_formats.is_empty());
+    /// assert!(mascot_generic_formats.capacity() >= 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            mascot_generic_formats: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Creates a new vector wrapping the provided MGF objects, without copying them.
+    ///
+    /// As for [`MGFVec::append`], no check is made on the feature IDs.
+    ///
+    /// # Arguments
+    /// * `mascot_generic_formats` - The MGF objects to wrap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> =
+    ///     MGFVec::from_path("tests/data/20220513_PMA_DBGI_01_04_003.mgf").unwrap();
+    ///
+    /// let wrapped = MGFVec::from_vec(mascot_generic_formats.clone().into_vec());
+    ///
+    /// assert_eq!(wrapped.as_slice(), mascot_generic_formats.as_slice());
+    /// ```
+    pub fn from_vec(mascot_generic_formats: Vec<MascotGenericFormat<I, F>>) -> Self {
+        Self {
+            mascot_generic_formats,
+        }
+    }
+
+    /// Returns the number of MGF objects the vector can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.mascot_generic_formats.capacity()
+    }
+
+    /// Create a new vector of MGF objects from the file at the provided path.
+    ///
+    /// # Arguments
+    /// * `path` - The path to the file to read.
+    ///
+    /// # Returns
+    /// A new vector of MGF objects.
+    ///
+    /// # Errors
+    /// * If the file at the provided path cannot be read.
+    /// * If the file at the provided path cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// An example of a document that contains only the first level of
+    /// fragmentation spectra:
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats.len(), 74, concat!(
+    ///     "The number of MascotGenericFormat objects in the vector should be 74, ",
+    ///     "but it is {}."
+    /// ), mascot_generic_formats.len());
+    /// ```
+    ///
+    /// An example of another type of documents that contains both the first and
+    /// second level of fragmentation spectra:
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats.len(), 139);
+    ///
+    /// ```
+    ///
+    ///
+    pub fn from_path(path: &str) -> Result<Self, String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        Self::from_path_with_options(path, ParseOptions::default())
+    }
+
+    /// Create a new vector of MGF objects from the file at the provided path,
+    /// using the provided [`ParseOptions`].
+    ///
+    /// # Arguments
+    /// * `path` - The path to the file to read.
+    /// * `options` - The options to use while parsing.
+    ///
+    /// # Errors
+    /// * If the file at the provided path cannot be read.
+    /// * If the file at the provided path cannot be parsed.
+    pub fn from_path_with_options(path: &str, options: ParseOptions) -> Result<Self, String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        let file = std::fs::read(path).map_err(|e| e.to_string())?;
+        Self::try_from_bytes_with_options(&file, options)
+    }
+
+    /// Create a new vector of MGF objects from a document held in memory.
+    ///
+    /// The bytes must be valid UTF-8, and a leading byte order mark is ignored.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes of the document.
+    ///
+    /// # Errors
+    /// * If the bytes are not valid UTF-8.
+    /// * If the document cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let document = b"\xEF\xBB\xBFBEGIN IONS\nFEATURE_ID=1\nPEPMASS=381.0795\nCHARGE=1\nRTINSECONDS=37.083\nMSLEVEL=2\n60.5425 2.4E5\nEND IONS\n";
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_bytes(document).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats.len(), 1);
+    /// assert!(MGFVec::<usize, f64>::try_from_bytes(b"BEGIN IONS\n\xFF\n").is_err());
+    /// ```
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self, String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        Self::try_from_bytes_with_options(bytes, ParseOptions::default())
+    }
+
+    /// Create a new vector of MGF objects from a document held in memory,
+    /// using the provided [`ParseOptions`].
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes of the document.
+    /// * `options` - The options to use while parsing.
+    ///
+    /// # Errors
+    /// * If the bytes are not valid UTF-8.
+    /// * If the document cannot be parsed.
+    pub fn try_from_bytes_with_options(bytes: &[u8], options: ParseOptions) -> Result<Self, String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        Self::try_from_iter_with_options(decode_document(bytes)?.lines(), options)
+    }
+
+    /// Create a new vector of MGF objects from the gzip-compressed file at the provided path.
+    ///
+    /// The file is decompressed and parsed line by line, without loading
+    /// the whole decompressed document into memory.
+    ///
+    /// # Arguments
+    /// * `path` - The path to the gzip-compressed file to read.
+    ///
+    /// # Errors
+    /// * If the file at the provided path cannot be opened or decompressed.
+    /// * If the decompressed document cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf.gz";
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_gz_path(path).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats.len(), 74);
+    /// ```
+    #[cfg(feature = "flate2")]
+    pub fn try_from_gz_path(path: &str) -> Result<Self, String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        Self::try_from_gz_path_with_options(path, ParseOptions::default())
+    }
+
+    /// Create a new vector of MGF objects from the gzip-compressed file at the
+    /// provided path, using the provided [`ParseOptions`].
+    ///
+    /// # Arguments
+    /// * `path` - The path to the gzip-compressed file to read.
+    /// * `options` - The options to use while parsing.
+    ///
+    /// # Errors
+    /// * If the file at the provided path cannot be opened or decompressed.
+    /// * If the decompressed document cannot be parsed.
+    #[cfg(feature = "flate2")]
+    pub fn try_from_gz_path_with_options(path: &str, options: ParseOptions) -> Result<Self, String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        use std::io::BufRead;
+
+        let file = std::fs::File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+        let reader = std::io::BufReader::new(flate2::read::GzDecoder::new(file));
+
+        // We stop at the first line that cannot be read, and report
+        // the underlying IO error once the parsing is over.
+        let mut io_error = None;
+        let result = Self::try_from_iter_with_options(
+            reader
+                .lines()
+                .map_while(|line| line.map_err(|e| io_error = Some(e)).ok()),
+            options,
+        );
+
+        if let Some(io_error) = io_error {
+            return Err(format!("{}: {}", path, io_error));
+        }
+
+        result
+    }
+
+    /// Create a new vector of MGF objects from the file at the provided path,
+    /// decompressing it first when the path ends with `.gz`.
+    ///
+    /// # Arguments
+    /// * `path` - The path to the file to read.
+    ///
+    /// # Errors
+    /// * If the file at the provided path cannot be read.
+    /// * If the file at the provided path cannot be parsed.
+    /// * If the file is gzip-compressed and the `flate2` feature is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path_auto(path).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats.len(), 74);
+    /// ```
+    pub fn from_path_auto(path: &str) -> Result<Self, String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        Self::from_path_auto_with_options(path, ParseOptions::default())
+    }
+
+    /// Create a new vector of MGF objects from the file at the provided path,
+    /// decompressing it first when the path ends with `.gz`, using the provided
+    /// [`ParseOptions`].
+    ///
+    /// # Arguments
+    /// * `path` - The path to the file to read.
+    /// * `options` - The options to use while parsing.
+    ///
+    /// # Errors
+    /// * If the file at the provided path cannot be read.
+    /// * If the file at the provided path cannot be parsed.
+    /// * If the file is gzip-compressed and the `flate2` feature is disabled.
+    pub fn from_path_auto_with_options(path: &str, options: ParseOptions) -> Result<Self, String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        if !path.ends_with(".gz") {
+            return Self::from_path_with_options(path, options);
+        }
+
+        #[cfg(feature = "flate2")]
+        {
+            Self::try_from_gz_path_with_options(path, options)
+        }
+
+        #[cfg(not(feature = "flate2"))]
+        {
+            let _ = options;
+            Err(format!(
+                concat!(
+                    "The file at path \"{}\" appears to be gzip-compressed, ",
+                    "but the crate was compiled without the `flate2` feature."
+                ),
+                path
+            ))
+        }
+    }
+
+    /// Create a new vector of MGF objects from the file at the provided path,
+    /// returning the warnings raised while parsing it.
+    ///
+    /// Both the error and the warnings are prefixed with the path of the
+    /// file, so that they can be traced back to it when several files
+    /// are parsed.
+    ///
+    /// # Arguments
+    /// * `path` - The path to the file to read.
+    /// * `options` - The options to use while parsing.
+    ///
+    /// # Errors
+    /// * If the file at the provided path cannot be read.
+    /// * If the file at the provided path cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    ///
+    /// let (mascot_generic_formats, report): (MGFVec<usize, f64>, ParseReport) =
+    ///     MGFVec::try_from_path_with_report(path, ParseOptions::default()).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats.len(), 74);
+    /// assert!(!report.has_warnings());
+    ///
+    /// let error = MGFVec::<usize, f64>::try_from_path_with_report(
+    ///     "tests/batch_data/broken.mgf",
+    ///     ParseOptions::default(),
+    /// )
+    /// .unwrap_err();
+    ///
+    /// assert!(error.starts_with("tests/batch_data/broken.mgf: Error at line"));
+    /// ```
+    pub fn try_from_path_with_report(
+        path: &str,
+        options: ParseOptions,
+    ) -> Result<(Self, ParseReport), String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        let file = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::try_from_located_bytes_with_report(path, &file, options)
+    }
+
+    /// Parses the document held by the bytes read from the provided path,
+    /// prefixing the error and the warnings with the path.
+    fn try_from_located_bytes_with_report(
+        path: &str,
+        bytes: &[u8],
+        options: ParseOptions,
+    ) -> Result<(Self, ParseReport), String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        let document = decode_document(bytes).map_err(|error| format!("{}: {}", path, error))?;
+        let (mascot_generic_formats, report) =
+            Self::try_from_iter_with_report(document.lines(), options)
+                .map_err(|error| format!("{}: {}", path, error))?;
+
+        Ok((mascot_generic_formats, locate_report(path, &report)))
+    }
+
+    /// Create a new vector of MGF objects from the file at the provided path,
+    /// decompressing it first when the path ends with `.gz`, and returning
+    /// the warnings raised while parsing it, prefixed with the path.
+    fn try_from_path_auto_with_report(
+        path: &str,
+        options: ParseOptions,
+    ) -> Result<(Self, ParseReport), String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        if !path.ends_with(".gz") {
+            return Self::try_from_path_with_report(path, options);
+        }
+
+        #[cfg(feature = "flate2")]
+        {
+            use std::io::BufRead;
+
+            let file = std::fs::File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+            let reader = std::io::BufReader::new(flate2::read::GzDecoder::new(file));
+
+            // As for MGFVec::try_from_gz_path_with_options, we stop at the first
+            // line that cannot be read, and report the underlying IO error.
+            let mut io_error = None;
+            let result = Self::try_from_iter_with_report(
+                reader
+                    .lines()
+                    .map_while(|line| line.map_err(|e| io_error = Some(e)).ok()),
+                options,
+            );
+
+            if let Some(io_error) = io_error {
+                return Err(format!("{}: {}", path, io_error));
+            }
+
+            let (mascot_generic_formats, report) =
+                result.map_err(|error| format!("{}: {}", path, error))?;
+
+            Ok((mascot_generic_formats, locate_report(path, &report)))
+        }
+
+        // Without the `flate2` feature, this reports that the file cannot be decompressed.
+        #[cfg(not(feature = "flate2"))]
+        {
+            Self::from_path_auto_with_options(path, options)
+                .map(|mascot_generic_formats| (mascot_generic_formats, ParseReport::new()))
+        }
+    }
+
+    /// Create a new vector of MGF objects from all the `.mgf` and `.mgf.gz`
+    /// files in the provided directory, returning the errors of the files
+    /// that could not be parsed alongside the entries of the others.
+    ///
+    /// The files are parsed in order of path, and their entries are
+    /// concatenated as with [`MGFVec::append`], so that no check is made on
+    /// the feature IDs across files. Subdirectories are not visited.
+    ///
+    /// # Arguments
+    /// * `dir` - The path to the directory to read.
+    ///
+    /// # Errors
+    /// * If the directory cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let (mascot_generic_formats, errors): (MGFVec<usize, f64>, Vec<(String, String)>) =
+    ///     MGFVec::try_from_dir("tests/batch_data").unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats.len(), 2);
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors[0].0, "tests/batch_data/broken.mgf");
+    /// ```
+    pub fn try_from_dir(dir: &str) -> Result<(Self, FileErrors), String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        Self::try_from_dir_with_options(dir, ParseOptions::default())
+    }
+
+    /// Create a new vector of MGF objects from all the `.mgf` and `.mgf.gz`
+    /// files in the provided directory, using the provided [`ParseOptions`].
+    ///
+    /// # Arguments
+    /// * `dir` - The path to the directory to read.
+    /// * `options` - The options to use while parsing.
+    ///
+    /// # Errors
+    /// * If the directory cannot be read.
+    pub fn try_from_dir_with_options(
+        dir: &str,
+        options: ParseOptions,
+    ) -> Result<(Self, FileErrors), String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        Self::try_from_dir_with_report(dir, options)
+            .map(|(mascot_generic_formats, errors, _)| (mascot_generic_formats, errors))
+    }
+
+    /// Create a new vector of MGF objects from all the `.mgf` and `.mgf.gz`
+    /// files in the provided directory, using the provided [`ParseOptions`]
+    /// and returning the warnings raised while parsing the files.
+    ///
+    /// As with [`MGFVec::try_from_path_with_report`], each warning is
+    /// prefixed with the path of the file it was raised in.
+    ///
+    /// # Arguments
+    /// * `dir` - The path to the directory to read.
+    /// * `options` - The options to use while parsing.
+    ///
+    /// # Errors
+    /// * If the directory cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let (mascot_generic_formats, errors, report): (
+    ///     MGFVec<usize, f64>,
+    ///     Vec<(String, String)>,
+    ///     ParseReport,
+    /// ) = MGFVec::try_from_dir_with_report("tests/batch_data", ParseOptions::default()).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats.len(), 2);
+    /// assert_eq!(errors.len(), 1);
+    /// assert!(!report.has_warnings());
+    /// ```
+    pub fn try_from_dir_with_report(
+        dir: &str,
+        options: ParseOptions,
+    ) -> Result<(Self, FileErrors, ParseReport), String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(dir).map_err(|e| format!("{}: {}", dir, e))? {
+            let path = entry.map_err(|e| format!("{}: {}", dir, e))?.path();
+            if !path.is_file() {
+                continue;
+            }
+            let path = path.to_string_lossy().into_owned();
+            if path.ends_with(".mgf") || path.ends_with(".mgf.gz") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut mascot_generic_formats = Self::new();
+        let mut errors = Vec::new();
+        let mut report = ParseReport::new();
+        for path in paths {
+            match Self::try_from_path_auto_with_report(&path, options) {
+                Ok((mut parsed, parsed_report)) => {
+                    mascot_generic_formats.append(&mut parsed);
+                    for warning in parsed_report.warnings() {
+                        report.push_warning(warning.clone());
+                    }
+                }
+                Err(error) => errors.push((path, error)),
+            }
+        }
+
+        Ok((mascot_generic_formats, errors, report))
+    }
+
+    /// Writes the MGF objects to a binary cache at the provided path, so that
+    /// they can be loaded with [`MGFVec::load_cache`] without parsing the
+    /// document again.
+    ///
+    /// # Arguments
+    /// * `path` - The path of the cache to write.
+    ///
+    /// # Errors
+    /// * If the file at the provided path cannot be created or written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let cache_path = std::env::temp_dir().join("mascot_rs_save_cache_doctest.bin");
+    /// let cache_path = cache_path.to_str().unwrap();
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    /// mascot_generic_formats.save_cache(cache_path).unwrap();
+    ///
+    /// let cached: MGFVec<usize, f64> = MGFVec::load_cache(cache_path).unwrap();
+    ///
+    /// assert_eq!(cached.as_slice(), mascot_generic_formats.as_slice());
+    ///
+    /// std::fs::remove_file(cache_path).unwrap();
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn save_cache(&self, path: &str) -> Result<(), String>
+    where
+        I: serde::Serialize,
+        F: serde::Serialize,
+    {
+        let file = std::fs::File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+        let mut writer = std::io::BufWriter::new(file);
+        bincode::serialize_into(&mut writer, self).map_err(|e| format!("{}: {}", path, e))?;
+        writer.flush().map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// Loads the MGF objects from a binary cache written by [`MGFVec::save_cache`].
+    ///
+    /// # Arguments
+    /// * `path` - The path of the cache to read.
+    ///
+    /// # Errors
+    /// * If the file at the provided path cannot be opened.
+    /// * If the file is not a cache of MGF objects with the same feature ID and float types.
+    #[cfg(feature = "serde")]
+    pub fn load_cache(path: &str) -> Result<Self, String>
+    where
+        I: serde::de::DeserializeOwned,
+        F: serde::de::DeserializeOwned,
+    {
+        let file = std::fs::File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+        bincode::deserialize_from(std::io::BufReader::new(file))
+            .map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// Create a new vector of MGF objects from the file at the provided path,
+    /// going through a binary cache.
+    ///
+    /// The cache is loaded when it is at least as recent as the document.
+    /// Otherwise, including when the cache is missing or cannot be loaded,
+    /// the document is parsed with [`MGFVec::from_path_auto`] and the cache
+    /// is written again.
+    ///
+    /// # Arguments
+    /// * `mgf_path` - The path to the document to read.
+    /// * `cache_path` - The path of the cache to read or write.
+    ///
+    /// # Errors
+    /// * If the document has to be parsed and it cannot be read or parsed.
+    /// * If the cache has to be written and it cannot be written.
+    #[cfg(feature = "serde")]
+    pub fn try_from_path_cached(mgf_path: &str, cache_path: &str) -> Result<Self, String>
+    where
+        I: Copy
+            + From<usize>
+            + FromStr
+            + Add<Output = I>
+            + Eq
+            + Debug
+            + Zero
+            + Hash
+            + serde::Serialize
+            + serde::de::DeserializeOwned,
+        F: ParsableFloat + serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let modified =
+            |path: &str| std::fs::metadata(path).and_then(|metadata| metadata.modified());
+        let mgf_modified = modified(mgf_path).map_err(|e| format!("{}: {}", mgf_path, e))?;
+
+        if modified(cache_path).is_ok_and(|cache_modified| cache_modified >= mgf_modified) {
+            if let Ok(mascot_generic_formats) = Self::load_cache(cache_path) {
+                return Ok(mascot_generic_formats);
+            }
+        }
+
+        let mascot_generic_formats = Self::from_path_auto(mgf_path)?;
+        mascot_generic_formats.save_cache(cache_path)?;
+        Ok(mascot_generic_formats)
+    }
+
+    pub fn try_from_iter<T, S>(iter: T) -> Result<Self, String>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        Self::try_from_iter_with_options(iter, ParseOptions::default())
+    }
+
+    /// Create a new vector of MGF objects from the provided lines, using the
+    /// provided [`ParseOptions`].
+    ///
+    /// # Arguments
+    /// * `iter` - The lines to parse.
+    /// * `options` - The options to use while parsing.
+    ///
+    /// # Errors
+    /// * If any of the lines cannot be parsed.
+    /// * If the feature IDs of the parsed entries are not unique.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let document = [
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "SOURCE_INSTRUMENT=LC-ESI-qTof",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "END IONS",
+    /// ];
+    ///
+    /// assert!(MGFVec::<usize, f64>::try_from_iter(document).is_err());
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter_with_options(
+    ///     document,
+    ///     ParseOptions::lenient()
+    /// ).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats.len(), 1);
+    /// assert_eq!(
+    ///     mascot_generic_formats[0].extra().get("SOURCE_INSTRUMENT").map(String::as_str),
+    ///     Some("LC-ESI-qTof")
+    /// );
+    /// ```
+    pub fn try_from_iter_with_options<T, S>(iter: T, options: ParseOptions) -> Result<Self, String>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        Self::try_from_iter_with_report(iter, options)
+            .map(|(mascot_generic_formats, _)| mascot_generic_formats)
+    }
+
+    /// Create a new vector of MGF objects from the provided lines, using the
+    /// provided [`ParseOptions`] and returning the warnings raised while parsing.
+    ///
+    /// # Arguments
+    /// * `iter` - The lines to parse.
+    /// * `options` - The options to use while parsing.
+    ///
+    /// # Errors
+    /// * If any of the lines cannot be parsed.
+    /// * If the feature IDs of the parsed entries are not unique.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let document = [
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "119.0857 3.3E5",
+    ///     "60.5425 2.4E5",
+    ///     "END IONS",
+    /// ];
+    ///
+    /// assert!(MGFVec::<usize, f64>::try_from_iter(document).is_err());
+    ///
+    /// let (mascot_generic_formats, report): (MGFVec<usize, f64>, ParseReport) =
+    ///     MGFVec::try_from_iter_with_report(document, ParseOptions::lenient()).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats.len(), 1);
+    /// assert_eq!(report.warnings().len(), 1);
+    /// assert!(report.warnings()[0].starts_with("Warning at line 9 (feature ID Some(1))"));
+    /// ```
+    pub fn try_from_iter_with_report<T, S>(
+        iter: T,
+        options: ParseOptions,
+    ) -> Result<(Self, ParseReport), String>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        let mut mascot_generic_formats = MGFVec::new();
+        let mut report = ParseReport::new();
+        let mut mascot_generic_format_builder = MascotGenericFormatBuilder::with_options(options);
+
+        for (line_number, line) in iter.into_iter().enumerate() {
+            let line = strip_byte_order_mark(line_number, line.as_ref());
+            let located_error = |error: String, feature_id: Option<I>| match feature_id {
+                Some(feature_id) => format!(
+                    "Error at line {} (feature ID {:?}): {}",
+                    line_number + 1,
+                    feature_id,
+                    error
+                ),
+                None => format!("Error at line {}: {}", line_number + 1, error),
+            };
+            let located_warning = |warning: String, feature_id: Option<I>| {
+                format!(
+                    "Warning at line {} (feature ID {:?}): {}",
+                    line_number + 1,
+                    feature_id,
+                    warning
+                )
+            };
+            let feature_id = mascot_generic_format_builder.feature_id();
+            mascot_generic_format_builder
+                .digest_line(line)
+                .map_err(|error| located_error(error, feature_id))?;
+            if mascot_generic_format_builder.can_build() {
+                let feature_id = mascot_generic_format_builder.feature_id();
+                for warning in mascot_generic_format_builder.warnings() {
+                    report.push_warning(located_warning(warning, feature_id));
+                }
+                mascot_generic_formats.push(
+                    mascot_generic_format_builder
+                        .build_and_reset()
+                        .map_err(|error| located_error(error, feature_id))?,
+                );
+            }
+        }
+
+        mascot_generic_formats.check_unique_feature_ids()?;
+
+        Ok((mascot_generic_formats, report))
+    }
+
+    /// Create a new vector of MGF objects from the file at the provided path,
+    /// dropping the entries that cannot be parsed or built and returning the
+    /// reason why each of them was dropped.
+    ///
+    /// See [`MGFVec::try_from_iter_collecting_errors`] for how the entries
+    /// are recovered.
+    ///
+    /// # Arguments
+    /// * `path` - The path to the file to read.
+    ///
+    /// # Errors
+    /// * If the file at the provided path cannot be read.
+    /// * If the feature IDs of the built entries are not unique.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let (mascot_generic_formats, errors): (MGFVec<usize, f64>, DroppedEntries<usize>) =
+    ///     MGFVec::try_from_path_collecting_errors("tests/dropped_entries_data/partially_broken.mgf")
+    ///         .unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats.len(), 1);
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors[0].0, Some(2));
+    /// ```
+    pub fn try_from_path_collecting_errors(path: &str) -> Result<(Self, DroppedEntries<I>), String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        let file = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+        let document = decode_document(&file).map_err(|error| format!("{}: {}", path, error))?;
+        Self::try_from_iter_collecting_errors(document.lines(), ParseOptions::default())
+    }
+
+    /// Create a new vector of MGF objects from the provided lines, dropping
+    /// the entries that cannot be parsed or built and returning the reason
+    /// why each of them was dropped.
+    ///
+    /// When a line of an entry cannot be parsed, the remaining lines of its
+    /// section are skipped, and the later sections carrying the same feature
+    /// ID are dropped and reported as well. Entries whose sections are all closed but that cannot be
+    /// built, for instance because of a missing required field, and an entry
+    /// left incomplete at the end of the document are also dropped. Each
+    /// dropped entry is reported with its feature ID, when known, and the error
+    /// located by its line number.
+    ///
+    /// # Arguments
+    /// * `iter` - The lines to parse.
+    /// * `options` - The options to use while parsing.
+    ///
+    /// # Errors
+    /// * If the feature IDs of the built entries are not unique.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let document = [
+    ///     "BEGIN IONS", "FEATURE_ID=1", "PEPMASS=381.0795", "CHARGE=1",
+    ///     "RTINSECONDS=37.083", "MSLEVEL=2", "60.5425 -2.4E5", "END IONS",
+    ///     "BEGIN IONS", "FEATURE_ID=2", "PEPMASS=245.1282", "CHARGE=1",
+    ///     "MSLEVEL=2", "70.0651 1.2E5", "END IONS",
+    ///     "BEGIN IONS", "FEATURE_ID=3", "PEPMASS=512.2034", "CHARGE=1",
+    ///     "RTINSECONDS=61.504", "MSLEVEL=2", "98.0964 5.6E5", "END IONS",
+    /// ];
+    ///
+    /// let (mascot_generic_formats, errors): (MGFVec<usize, f64>, DroppedEntries<usize>) =
+    ///     MGFVec::try_from_iter_collecting_errors(document, ParseOptions::default()).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats.len(), 1);
+    /// assert_eq!(mascot_generic_formats[0].feature_id(), 3);
+    /// assert_eq!(errors.len(), 2);
+    /// assert_eq!(errors[0].0, Some(1));
+    /// assert!(errors[0].1.starts_with("Error at line 7"));
+    /// assert_eq!(errors[1].0, Some(2));
+    /// assert!(errors[1].1.contains("retention_time is missing"));
+    /// ```
+    pub fn try_from_iter_collecting_errors<T, S>(
+        iter: T,
+        options: ParseOptions,
+    ) -> Result<(Self, DroppedEntries<I>), String>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        let mut mascot_generic_formats = MGFVec::new();
+        let mut errors = Vec::new();
+        let mut dropped_feature_ids = HashSet::new();
+        let mut mascot_generic_format_builder = MascotGenericFormatBuilder::with_options(options);
+        // Whether the remaining lines of the section of a dropped entry are being skipped.
+        let mut skipping = false;
+        let mut last_line_number = 0;
+
+        for (line_number, line) in iter.into_iter().enumerate() {
+            last_line_number = line_number + 1;
+            let line = strip_byte_order_mark(line_number, line.as_ref());
+            let located_error =
+                |error: String| format!("Error at line {}: {}", line_number + 1, error);
+
+            if skipping {
+                if is_section_delimiter(line, "END IONS") {
+                    skipping = false;
+                    continue;
+                }
+                if !is_section_delimiter(line, "BEGIN IONS") {
+                    continue;
+                }
+                skipping = false;
+            }
+
+            let feature_id = mascot_generic_format_builder.feature_id();
+            if let Err(error) = mascot_generic_format_builder.digest_line(line) {
+                let feature_id = mascot_generic_format_builder.feature_id().or(feature_id);
+                errors.push((feature_id, located_error(error)));
+                dropped_feature_ids.extend(feature_id);
+                skipping = mascot_generic_format_builder.is_section_open();
+                mascot_generic_format_builder.reset();
+                continue;
+            }
+
+            let feature_id = mascot_generic_format_builder.feature_id();
+            match mascot_generic_format_builder.state() {
+                BuilderState::Buildable => {
+                    match mascot_generic_format_builder.build_and_reset() {
+                        // The later sections of a dropped entry are dropped as well.
+                        Ok(_)
+                            if feature_id.is_some_and(|feature_id| {
+                                dropped_feature_ids.contains(&feature_id)
+                            }) =>
+                        {
+                            errors.push((
+                                feature_id,
+                                located_error(
+                                    concat!(
+                                        "The entry shares its feature ID with an entry ",
+                                        "dropped earlier, and it is dropped with it."
+                                    )
+                                    .to_string(),
+                                ),
+                            ));
+                        }
+                        Ok(mascot_generic_format) => {
+                            mascot_generic_formats.push(mascot_generic_format)
+                        }
+                        Err(error) => {
+                            errors.push((feature_id, located_error(error)));
+                            dropped_feature_ids.extend(feature_id);
+                        }
+                    }
+                }
+                BuilderState::Corrupted => {
+                    // Building the entry also opens the section of the next
+                    // entry, when its `BEGIN IONS` line closed this one.
+                    let error = mascot_generic_format_builder
+                        .build_and_reset()
+                        .err()
+                        .unwrap_or_else(|| "The entry cannot be built.".to_string());
+                    errors.push((feature_id, located_error(error)));
+                    dropped_feature_ids.extend(feature_id);
+                }
+                _ => {}
+            }
+        }
+
+        if mascot_generic_format_builder.state() != BuilderState::Empty {
+            errors.push((
+                mascot_generic_format_builder.feature_id(),
+                format!(
+                    "Error at line {}: the entry is incomplete at the end of the document.",
+                    last_line_number
+                ),
+            ));
+        }
+
+        mascot_generic_formats.check_unique_feature_ids()?;
+
+        Ok((mascot_generic_formats, errors))
+    }
+
+    /// Create a new vector of MGF objects from the provided lines, accepting
+    /// feature IDs that are not numeric, such as `FEATURE_ID=f_001`.
+    ///
+    /// The feature ID type `I` cannot be a `String`: feature IDs are copied out
+    /// of the MGF objects by value, which requires `Copy`, and they share their
+    /// type with the scan numbers of the [`MergeScansMetadata`], which are summed
+    /// and counted through the `Add`, `Zero` and `From<usize>` bounds. Instead,
+    /// each distinct feature ID is replaced by its position in the returned
+    /// vector of feature IDs, in order of first appearance, so that the original
+    /// feature ID of an MGF object is `feature_ids[mascot_generic_format.feature_id()]`.
+    ///
+    /// # Arguments
+    /// * `iter` - The lines to parse.
+    ///
+    /// # Errors
+    /// * If any of the lines cannot be parsed.
+    /// * If the feature IDs of the parsed entries are not unique.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let document = [
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=f_001",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "SCANS=f_001",
+    ///     "60.5425 2.4E5",
+    ///     "END IONS",
+    /// ];
+    ///
+    /// assert!(MGFVec::<usize, f64>::try_from_iter(document).is_err());
+    ///
+    /// let (mascot_generic_formats, feature_ids): (MGFVec<usize, f64>, Vec<String>) =
+    ///     MGFVec::try_from_iter_with_string_feature_ids(document).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats.len(), 1);
+    /// assert_eq!(feature_ids[mascot_generic_formats[0].feature_id()], "f_001");
+    /// ```
+    pub fn try_from_iter_with_string_feature_ids<T, S>(
+        iter: T,
+    ) -> Result<(Self, Vec<String>), String>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        Self::try_from_iter_with_string_feature_ids_and_options(iter, ParseOptions::default())
+    }
+
+    /// Create a new vector of MGF objects from the provided lines, accepting
+    /// feature IDs that are not numeric and using the provided [`ParseOptions`].
+    ///
+    /// See [`try_from_iter_with_string_feature_ids`](Self::try_from_iter_with_string_feature_ids)
+    /// for how the feature IDs are mapped.
+    ///
+    /// # Arguments
+    /// * `iter` - The lines to parse.
+    /// * `options` - The options to use while parsing.
+    ///
+    /// # Errors
+    /// * If any of the lines cannot be parsed.
+    /// * If the feature IDs of the parsed entries are not unique.
+    pub fn try_from_iter_with_string_feature_ids_and_options<T, S>(
+        iter: T,
+        options: ParseOptions,
+    ) -> Result<(Self, Vec<String>), String>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        let mut feature_ids: Vec<String> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+
+        let lines = iter.into_iter().map(|line| {
+            let line = line.as_ref();
+            let normalized_line = normalize_key(line);
+            // The feature ID is written both in the `FEATURE_ID` line and in
+            // the `SCANS` line of the last section of an entry.
+            let (key, feature_id) = match normalized_line
+                .strip_prefix("FEATURE_ID=")
+                .map(|feature_id| ("FEATURE_ID", feature_id))
+                .or_else(|| {
+                    normalized_line
+                        .strip_prefix("SCANS=")
+                        .map(|feature_id| ("SCANS", feature_id))
+                }) {
+                Some((key, feature_id)) if feature_id != "-1" && !is_sentinel_value(feature_id) => {
+                    (key, feature_id)
+                }
+                _ => return line.to_string(),
+            };
+            let position = *positions.entry(feature_id.to_string()).or_insert_with(|| {
+                feature_ids.push(feature_id.to_string());
+                feature_ids.len() - 1
+            });
+            format!("{}={}", key, position)
+        });
+
+        let mascot_generic_formats = Self::try_from_iter_with_options(lines, options)?;
+
+        Ok((mascot_generic_formats, feature_ids))
+    }
+
+    /// Create a new vector of MGF objects from the MSP (NIST) file at the provided path.
+    ///
+    /// # Arguments
+    /// * `path` - The path to the file to read.
+    ///
+    /// # Errors
+    /// * If the file at the provided path cannot be read.
+    /// * If the file at the provided path cannot be parsed.
+    pub fn try_from_msp_path(path: &str) -> Result<Self, String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        Self::try_from_msp_path_with_options(path, ParseOptions::default())
+    }
+
+    /// Create a new vector of MGF objects from the MSP (NIST) file at the
+    /// provided path, using the provided [`ParseOptions`].
+    ///
+    /// As for the MGF documents, a leading UTF-8 byte order mark is ignored,
+    /// and the errors are prefixed with the path of the file.
+    ///
+    /// # Arguments
+    /// * `path` - The path to the file to read.
+    /// * `options` - The options to use while parsing.
+    ///
+    /// # Errors
+    /// * If the file at the provided path cannot be read.
+    /// * If the file at the provided path is not valid UTF-8.
+    /// * If the file at the provided path cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// assert!(MGFVec::<usize, f64>::try_from_msp_path("tests/data/missing.msp")
+    ///     .unwrap_err()
+    ///     .starts_with("tests/data/missing.msp: "));
+    /// ```
+    pub fn try_from_msp_path_with_options(path: &str, options: ParseOptions) -> Result<Self, String>
+    where
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        let file = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+        let document = decode_document(&file).map_err(|error| format!("{}: {}", path, error))?;
+        Self::try_from_msp_iter_with_options(document.lines(), options)
+            .map_err(|error| format!("{}: {}", path, error))
+    }
+
+    /// Create a new vector of MGF objects from the provided MSP (NIST) lines.
+    ///
+    /// Entries whose `NAME` is not a valid feature ID, and that do not
+    /// provide a `FEATURE_ID` field, are assigned their 1-based position
+    /// in the document as feature ID. Such names, usually compound names,
+    /// are kept as the `NAME` extra field, and written back by
+    /// [`MascotGenericFormat::to_msp_string`].
+    ///
+    /// # Arguments
+    /// * `iter` - The lines to parse.
+    ///
+    /// # Errors
+    /// * If any of the lines cannot be parsed.
+    /// * If the number of peaks of an entry does not match its `Num Peaks` field.
+    /// * If the feature IDs of the parsed entries are not unique.
+    ///
+    /// # Examples
+    ///
+    /// The MSP export of a document can be parsed back:
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let mut msp = Vec::new();
+    /// mascot_generic_formats.write_msp(&mut msp).unwrap();
+    /// let msp = String::from_utf8(msp).unwrap();
+    ///
+    /// let parsed: MGFVec<usize, f64> = MGFVec::try_from_msp_iter(msp.lines()).unwrap();
+    ///
+    /// assert_eq!(parsed.len(), mascot_generic_formats.len());
+    /// for (left, right) in parsed.iter().zip(mascot_generic_formats.iter()) {
+    ///     assert_eq!(left.feature_id(), right.feature_id());
+    ///     assert_eq!(left.precursor_mz(), right.precursor_mz());
+    ///     assert_eq!(left.charge(), right.charge());
+    ///     assert_eq!(
+    ///         left.get_second_fragmentation_level().unwrap().mass_divided_by_charge_ratios(),
+    ///         right.get_second_fragmentation_level().unwrap().mass_divided_by_charge_ratios(),
+    ///     );
+    /// }
+    /// ```
+    ///
+    /// A mismatch with the declared number of peaks is an error:
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let msp = [
+    ///     "NAME: Some compound",
+    ///     "PRECURSORMZ: 381.0795",
+    ///     "CHARGE: 1",
+    ///     "RETENTIONTIME: 37.083",
+    ///     "Num Peaks: 3",
+    ///     "60.5425\t240000",
+    ///     "119.0857\t330000",
+    ///     "",
+    /// ];
+    ///
+    /// assert!(MGFVec::<usize, f64>::try_from_msp_iter(msp).is_err());
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_msp_iter(
+    ///     msp.iter().map(|line| line.replace("Num Peaks: 3", "Num Peaks: 2"))
+    /// ).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats[0].feature_id(), 1);
+    /// ```
+    ///
+    /// The standard NIST fields are accepted by the strict parse options,
+    /// and the retention time and the charge may be omitted when the parse
+    /// options allow zero values:
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let msp = [
+    ///     "Name: Some compound",
+    ///     "Synon: Another name",
+    ///     "Synon: Yet another name",
+    ///     "Precursor_type: [M+H]+",
+    ///     "Formula: C6H12O6",
+    ///     "InChIKey: WQZGKKKJIJFFOK-GASJEMHNSA-N",
+    ///     "Ion_mode: P",
+    ///     "PrecursorMZ: 381.0795",
+    ///     "Num Peaks: 2",
+    ///     "60.5425 240000",
+    ///     "119.0857 330000",
+    ///     "",
+    /// ];
+    ///
+    /// assert!(MGFVec::<usize, f64>::try_from_msp_iter(msp)
+    ///     .unwrap_err()
+    ///     .contains("retention_time is missing"));
+    ///
+    /// let options = ParseOptions::default().with_allow_zero_values(true);
+    /// let mascot_generic_formats: MGFVec<usize, f64> =
+    ///     MGFVec::try_from_msp_iter_with_options(msp, options).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats[0].retention_time(), 0.0);
+    /// assert_eq!(mascot_generic_formats[0].charge(), Charge::One);
+    /// assert_eq!(mascot_generic_formats[0].ion_mode(), Some(IonMode::Positive));
+    /// assert_eq!(mascot_generic_formats[0].extra()["ADDUCT"], "[M+H]+");
+    /// assert_eq!(mascot_generic_formats[0].extra()["FORMULA"], "C6H12O6");
+    /// assert_eq!(mascot_generic_formats[0].extra()["SYNON"], "Another name; Yet another name");
+    /// assert_eq!(mascot_generic_formats[0].extra()["NAME"], "Some compound");
+    /// assert!(mascot_generic_formats[0]
+    ///     .to_msp_string()
+    ///     .starts_with("NAME: Some compound\n"));
+    ///
+    /// // The charge is implied by the adduct when inferring it is enabled.
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_msp_iter_with_options(
+    ///     msp.iter().map(|line| line.replace("[M+H]+", "[M+2H]2+")),
+    ///     options.with_infer_charge_from_adduct(true),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats[0].charge(), Charge::TwoPlus);
+    ///
+    /// // Unknown fields are still rejected, as are empty spectra.
+    /// assert!(MGFVec::<usize, f64>::try_from_msp_iter_with_options(
+    ///     msp.iter().map(|line| line.replace("Formula", "Garbage")),
+    ///     options,
+    /// ).is_err());
+    /// assert!(MGFVec::<usize, f64>::try_from_msp_iter(
+    ///     ["Name: Some compound", "PrecursorMZ: 381.0795", "Num Peaks: 0", ""]
+    /// ).unwrap_err().contains("declares an empty spectrum"));
+    /// ```
+    pub fn try_from_msp_iter<T, S>(iter: T) -> Result<Self, String>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        Self::try_from_msp_iter_with_options(iter, ParseOptions::default())
+    }
+
+    /// Create a new vector of MGF objects from the provided MSP (NIST) lines,
+    /// using the provided [`ParseOptions`].
+    ///
+    /// # Arguments
+    /// * `iter` - The lines to parse.
+    /// * `options` - The options to use while parsing.
+    ///
+    /// # Errors
+    /// * If any of the lines cannot be parsed.
+    /// * If the number of peaks of an entry does not match its `Num Peaks` field.
+    /// * If the feature IDs of the parsed entries are not unique.
+    pub fn try_from_msp_iter_with_options<T, S>(
+        iter: T,
+        options: ParseOptions,
+    ) -> Result<Self, String>
+    where
+        T: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: ParsableFloat,
+    {
+        let mut mascot_generic_formats = MGFVec::new();
+        let mut msp_builder = MSPBuilder::with_options(options);
+        let mut last_line_number = 0;
+
+        for (line_number, line) in iter.into_iter().enumerate() {
+            // As for MGF documents, we ignore a leading UTF-8 byte order mark.
+            let line = strip_byte_order_mark(line_number, line.as_ref()).trim();
+            last_line_number = line_number + 1;
+            // Entries are separated by blank lines, which must
+            // never appear in the middle of an entry.
+            if line.is_empty() {
+                if !msp_builder.is_empty() {
+                    return Err(format!(
+                        "Error at line {}: {}",
+                        last_line_number,
+                        msp_builder.incomplete_entry_error().unwrap_or_default()
+                    ));
+                }
+                continue;
+            }
+            msp_builder
+                .digest_line(line)
+                .map_err(|error| format!("Error at line {}: {}", last_line_number, error))?;
+            if msp_builder.has_all_peaks() && msp_builder.feature_id().is_none() {
+                msp_builder.set_default_feature_id(mascot_generic_formats.len() + 1)?;
+            }
+            if msp_builder.can_build() {
+                mascot_generic_formats.push(
+                    msp_builder.build_and_reset().map_err(|error| {
+                        format!("Error at line {}: {}", last_line_number, error)
+                    })?,
+                );
+            }
+        }
+
+        if !msp_builder.is_empty() {
+            return Err(format!(
+                "Error at line {}: the document ended with an incomplete entry. {}",
+                last_line_number,
+                msp_builder.incomplete_entry_error().unwrap_or_default()
+            ));
+        }
+
+        mascot_generic_formats.check_unique_feature_ids()?;
+
+        Ok(mascot_generic_formats)
+    }
+
+    /// Checks that the feature IDs of the MGF objects are unique.
+    fn check_unique_feature_ids(&self) -> Result<(), String>
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>,
+    {
+        let number_of_unique_feature_ids = self
+            .iter()
+            .map(|mgf| mgf.feature_id())
+            .collect::<HashSet<I>>()
+            .len();
+        if number_of_unique_feature_ids != self.len() {
+            return Err(format!(
+                concat!(
+                    "We have identified {} duplicated feature ids in the MGF document provided. ",
+                    "Specifically, there were {} entries, but only {} unique feature IDs."
+                ),
+                self.len() - number_of_unique_feature_ids,
+                self.len(),
+                number_of_unique_feature_ids
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Writes the peaks of all the MGF objects as a long-format CSV table.
+    ///
+    /// The output has a `feature_id,mslevel,mz,intensity` header followed by
+    /// one row per peak, for every fragmentation level of every entry.
+    ///
+    /// # Arguments
+    /// * `writer` - The writer to write the CSV to.
+    ///
+    /// # Errors
+    /// * If the writer fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let mut csv = Vec::new();
+    /// mascot_generic_formats.peaks_to_csv(&mut csv).unwrap();
+    /// let csv = String::from_utf8(csv).unwrap();
+    ///
+    /// let number_of_peaks: usize = mascot_generic_formats
+    ///     .iter()
+    ///     .map(|mgf| {
+    ///         mgf.first_fragmentation_level_mass_divided_by_charge_ratios_iter().map_or(0, |iter| iter.count())
+    ///             + mgf.second_fragmentation_level_mass_divided_by_charge_ratios_iter().map_or(0, |iter| iter.count())
+    ///     })
+    ///     .sum();
+    ///
+    /// assert_eq!(csv.lines().next(), Some("feature_id,mslevel,mz,intensity"));
+    /// assert_eq!(csv.lines().count(), number_of_peaks + 1);
+    /// assert!(csv.lines().nth(1).unwrap().starts_with("1,1,"));
+    /// ```
+    pub fn peaks_to_csv<W: Write>(&self, mut writer: W) -> std::io::Result<()>
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero + Display,
+        F: Copy + StrictlyPositive + PartialOrd + Display,
+    {
+        writeln!(writer, "feature_id,mslevel,mz,intensity")?;
+        for mascot_generic_format in self.iter() {
+            for data in mascot_generic_format.data.iter() {
+                let level = data.level().as_u8();
+                for (mass_divided_by_charge_ratio, fragment_intensity) in data
+                    .mass_divided_by_charge_ratios_iter()
+                    .zip(data.fragment_intensities_iter())
+                {
+                    writeln!(
+                        writer,
+                        "{},{},{},{}",
+                        mascot_generic_format.metadata.feature_id(),
+                        level,
+                        mass_divided_by_charge_ratio,
+                        fragment_intensity
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns an iterator over the feature ID, precursor m/z and charge of each MGF object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let (feature_id, precursor_mz, charge) = mascot_generic_formats.summary_iter().next().unwrap();
+    ///
+    /// assert_eq!(feature_id, mascot_generic_formats[0].feature_id());
+    /// assert_eq!(precursor_mz, mascot_generic_formats[0].precursor_mz());
+    /// assert_eq!(charge, mascot_generic_formats[0].charge());
+    /// assert_eq!(mascot_generic_formats.summary_iter().count(), mascot_generic_formats.len());
+    /// ```
+    pub fn summary_iter(&self) -> impl Iterator<Item = (I, F, Charge)> + '_
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>,
+    {
+        self.iter().map(|mascot_generic_format| {
+            (
+                mascot_generic_format.feature_id(),
+                mascot_generic_format.precursor_mz(),
+                mascot_generic_format.charge(),
+            )
+        })
+    }
+
+    /// Writes the feature ID, precursor m/z and charge of all the MGF objects as a CSV table.
+    ///
+    /// The output has a `feature_id,precursor_mz,charge` header followed by
+    /// one row per entry, with the charge written as in its `CHARGE` line.
+    ///
+    /// # Arguments
+    /// * `writer` - The writer to write the CSV to.
+    ///
+    /// # Errors
+    /// * If the writer fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let mut csv = Vec::new();
+    /// mascot_generic_formats.to_summary_csv(&mut csv).unwrap();
+    /// let csv = String::from_utf8(csv).unwrap();
+    ///
+    /// assert_eq!(csv.lines().next(), Some("feature_id,precursor_mz,charge"));
+    /// assert_eq!(csv.lines().count(), mascot_generic_formats.len() + 1);
+    /// ```
+    pub fn to_summary_csv<W: Write>(&self, mut writer: W) -> std::io::Result<()>
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero + Display,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>
+            + Display,
+    {
+        writeln!(writer, "feature_id,precursor_mz,charge")?;
+        for (feature_id, precursor_mz, charge) in self.summary_iter() {
+            let charge = charge.to_string();
+            writeln!(
+                writer,
+                "{},{},{}",
+                feature_id,
+                precursor_mz,
+                charge.strip_prefix("CHARGE=").unwrap_or(&charge)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of MGF objects with each charge.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1+",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "END IONS",
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=2",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1+",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "END IONS",
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=3",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=2-",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "END IONS",
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=4",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "IONMODE=Negative",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// let charge_histogram = mascot_generic_formats.charge_histogram();
+    ///
+    /// assert_eq!(
+    ///     charge_histogram.into_iter().collect::<Vec<_>>(),
+    ///     vec![(Charge::TwoMinus, 1), (Charge::One, 1), (Charge::OnePlus, 2)]
+    /// );
+    ///
+    /// let ion_mode_counts = mascot_generic_formats.ion_mode_counts();
+    ///
+    /// assert_eq!(
+    ///     ion_mode_counts.into_iter().collect::<Vec<_>>(),
+    ///     vec![(Some(IonMode::Positive), 2), (Some(IonMode::Negative), 2)]
+    /// );
+    /// ```
+    pub fn charge_histogram(&self) -> BTreeMap<Charge, usize>
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero,
+        F: Copy + StrictlyPositive,
+    {
+        let mut charge_histogram = BTreeMap::new();
+        for mascot_generic_format in self.iter() {
+            *charge_histogram
+                .entry(mascot_generic_format.metadata.charge())
+                .or_insert(0) += 1;
+        }
+        charge_histogram
+    }
+
+    /// Returns the number of MGF objects with each effective ion mode.
+    ///
+    /// The ion mode of each MGF object is the one provided by its `IONMODE`
+    /// field or, when missing, the one implied by an explicitly signed charge.
+    /// The MGF objects whose ion mode is unknown are counted under `None`.
+    pub fn ion_mode_counts(&self) -> BTreeMap<Option<IonMode>, usize>
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero,
+        F: Copy + StrictlyPositive,
+    {
+        let mut ion_mode_counts = BTreeMap::new();
+        for mascot_generic_format in self.iter() {
+            *ion_mode_counts
+                .entry(mascot_generic_format.metadata.effective_ion_mode())
+                .or_insert(0) += 1;
+        }
+        ion_mode_counts
+    }
+
+    /// Returns the minimum and maximum parent ion masses of the MGF objects,
+    /// or `None` when the vector is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS", "FEATURE_ID=1", "PEPMASS=381.0795", "CHARGE=1",
+    ///     "RTINSECONDS=37.083", "MSLEVEL=2", "60.5425 2.4E5", "END IONS",
+    ///     "BEGIN IONS", "FEATURE_ID=2", "PEPMASS=245.1282", "CHARGE=1",
+    ///     "RTINSECONDS=52.117", "MSLEVEL=2", "70.0651 1.2E5", "END IONS",
+    ///     "BEGIN IONS", "FEATURE_ID=3", "PEPMASS=512.2034", "CHARGE=1",
+    ///     "RTINSECONDS=61.504", "MSLEVEL=2", "98.0964 5.6E5", "END IONS",
+    /// ]).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_formats.precursor_mz_range(), Some((245.1282, 512.2034)));
+    /// assert_eq!(MGFVec::<usize, f64>::new().precursor_mz_range(), None);
+    /// ```
+    pub fn precursor_mz_range(&self) -> Option<(F, F)>
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>,
+    {
+        self.iter()
+            .map(|mascot_generic_format| mascot_generic_format.precursor_mz())
+            .fold(None, |range, precursor_mz| match range {
+                None => Some((precursor_mz, precursor_mz)),
+                Some((min, max)) => Some((
+                    if precursor_mz < min {
+                        precursor_mz
+                    } else {
+                        min
+                    },
+                    if precursor_mz > max {
+                        precursor_mz
+                    } else {
+                        max
+                    },
+                )),
+            })
+    }
+
+    /// Returns the summary statistics of the MGF objects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let statistics = mascot_generic_formats.statistics();
+    ///
+    /// assert_eq!(statistics.number_of_entries(), mascot_generic_formats.len());
+    /// assert_eq!(
+    ///     statistics.charge_histogram(),
+    ///     &mascot_generic_formats.charge_histogram()
+    /// );
+    /// ```
+    pub fn statistics(&self) -> MgfStats
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>
+            + Into<f64>,
+    {
+        MgfStats::new(self.iter())
+    }
+
+    /// Writes all the MGF objects as an MSP (NIST) library.
+    ///
+    /// # Arguments
+    /// * `writer` - The writer to write the library to.
+    ///
+    /// # Errors
+    /// * If the writer fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let mut msp = Vec::new();
+    /// mascot_generic_formats.write_msp(&mut msp).unwrap();
+    /// let msp = String::from_utf8(msp).unwrap();
+    ///
+    /// assert_eq!(
+    ///     msp.lines().filter(|line| line.starts_with("NAME: ")).count(),
+    ///     mascot_generic_formats.len()
+    /// );
+    /// ```
+    pub fn write_msp<W: Write>(&self, mut writer: W) -> std::io::Result<()>
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero + Display,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>
+            + Display,
+    {
+        for mascot_generic_format in self.iter() {
+            writer.write_all(mascot_generic_format.to_msp_string().as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes all the MGF objects as an MGF document.
+    ///
+    /// # Arguments
+    /// * `writer` - The writer to write the MGF document to.
+    /// * `precision` - The number of decimal places of the parent ion masses, the retention times, the mass-charge ratios and the intensities.
+    ///
+    /// # Errors
+    /// * If the writer fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let mut mgf = Vec::new();
+    /// mascot_generic_formats.write_to(&mut mgf, DEFAULT_MGF_PRECISION).unwrap();
+    /// let mgf = String::from_utf8(mgf).unwrap();
+    ///
+    /// let reloaded: MGFVec<usize, f64> = MGFVec::try_from_iter(mgf.lines()).unwrap();
+    ///
+    /// assert_eq!(reloaded.len(), mascot_generic_formats.len());
+    /// ```
+    pub fn write_to<W: Write>(&self, mut writer: W, precision: usize) -> std::io::Result<()>
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero + Display + From<usize>,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>
+            + Display,
+    {
+        for mascot_generic_format in self.iter() {
+            mascot_generic_format.write_to(&mut writer, precision)?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes the MGF objects whose feature ID was already encountered.
+    ///
+    /// The first occurrence of each feature ID is kept, and the relative
+    /// order of the kept MGF objects is preserved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let mut duplicated = MGFVec::new();
+    /// duplicated.push(mascot_generic_formats[0].clone());
+    /// duplicated.push(mascot_generic_formats[1].clone());
+    /// duplicated.push(mascot_generic_formats[0].clone());
+    ///
+    /// duplicated.dedup_by_feature_id();
+    ///
+    /// assert_eq!(duplicated.len(), 2);
+    /// assert_eq!(duplicated[0], mascot_generic_formats[0]);
+    /// assert_eq!(duplicated[1], mascot_generic_formats[1]);
+    /// ```
+    pub fn dedup_by_feature_id(&mut self)
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>,
+    {
+        let mut feature_ids = HashSet::new();
+        self.mascot_generic_formats
+            .retain(|mascot_generic_format| feature_ids.insert(mascot_generic_format.feature_id()));
+    }
+
+    /// Merges the MGF objects sharing the same feature ID.
+    ///
+    /// The merged MGF object takes the position and the metadata of the first
+    /// occurrence of its feature ID, while the peaks of the duplicates are merged
+    /// level by level into its own with [`MascotGenericFormatData::merge`].
+    /// The relative order of the first occurrences is preserved.
+    ///
+    /// # Arguments
+    /// * `precursor_tolerance` - The relative tolerance used to compare the parent ion mass with the minimum mass-charge ratio of the merged first fragmentation level, or `None` to skip the check.
+    ///
+    /// # Errors
+    /// * If a merged MGF object is not valid, for instance because the merged
+    ///   first fragmentation level no longer matches the parent ion mass.
+    ///   The vector is left unchanged in such cases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let mut duplicated = MGFVec::new();
+    /// duplicated.push(mascot_generic_formats[0].clone());
+    /// duplicated.push(mascot_generic_formats[1].clone());
+    /// duplicated.push(mascot_generic_formats[0].clone());
+    ///
+    /// duplicated
+    ///     .dedup_merge_by_feature_id(Some(DEFAULT_RELATIVE_TOLERANCE))
+    ///     .unwrap();
+    ///
+    /// let number_of_peaks = mascot_generic_formats[0]
+    ///     .num_peaks(FragmentationSpectraLevel::Two)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(duplicated.len(), 2);
+    /// assert_eq!(duplicated[0].feature_id(), mascot_generic_formats[0].feature_id());
+    /// assert_eq!(
+    ///     duplicated[0].num_peaks(FragmentationSpectraLevel::Two),
+    ///     Some(2 * number_of_peaks)
+    /// );
+    /// assert!(duplicated[0].get_second_fragmentation_level().unwrap().is_sorted_by_mz());
+    /// assert_eq!(duplicated[1], mascot_generic_formats[1]);
+    /// ```
+    pub fn dedup_merge_by_feature_id(
+        &mut self,
+        precursor_tolerance: Option<f64>,
+    ) -> Result<(), String>
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>
+            + ApproxEq,
+    {
+        // The position of the first occurrence of each feature ID.
+        let mut positions: HashMap<I, usize> = HashMap::new();
+        // The merged MGF objects, by the position of their first occurrence.
+        // They are all built before the vector is modified, so that it is
+        // left unchanged when any of the merges fails.
+        let mut merged: HashMap<usize, MascotGenericFormat<I, F>> = HashMap::new();
+
+        for (index, mascot_generic_format) in self.mascot_generic_formats.iter().enumerate() {
+            let Some(&position) = positions.get(&mascot_generic_format.feature_id()) else {
+                positions.insert(mascot_generic_format.feature_id(), index);
+                continue;
+            };
+            let (metadata, mut data) = merged
+                .remove(&position)
+                .unwrap_or_else(|| self.mascot_generic_formats[position].clone())
+                .into_parts();
+            for other in mascot_generic_format.data() {
+                match data.iter().position(|data| data.level() == other.level()) {
+                    Some(index) => {
+                        let current = data.swap_remove(index);
+                        data.push(current.merge(other.clone())?);
+                    }
+                    None => data.push(other.clone()),
+                }
+            }
+            data.sort_by_key(|data| data.level());
+            merged.insert(
+                position,
+                MascotGenericFormat::new_with_precursor_tolerance(
+                    metadata,
+                    data,
+                    precursor_tolerance,
+                )?,
+            );
+        }
+
+        if merged.is_empty() {
+            return Ok(());
+        }
+
+        self.mascot_generic_formats = std::mem::take(&mut self.mascot_generic_formats)
+            .into_iter()
+            .enumerate()
+            .filter(|(index, mascot_generic_format)| {
+                positions[&mascot_generic_format.feature_id()] == *index
+            })
+            .map(|(index, mascot_generic_format)| {
+                merged.remove(&index).unwrap_or(mascot_generic_format)
+            })
+            .collect();
+
+        Ok(())
     }
-}
-
-#[repr(transparent)]
-#[derive(Debug, Clone)]
-pub struct MGFVec<I, F> {
-    mascot_generic_formats: Vec<MascotGenericFormat<I, F>>,
-}
 
-impl<I, F> MGFVec<I, F> {
-    pub fn new() -> Self {
-        Self {
-            mascot_generic_formats: Vec::new(),
-        }
+    /// Returns the position of the first MGF object with the provided feature ID.
+    ///
+    /// # Arguments
+    /// * `feature_id` - The feature ID to look for.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let feature_id = mascot_generic_formats[3].feature_id();
+    ///
+    /// assert_eq!(mascot_generic_formats.position_by_feature_id(feature_id), Some(3));
+    /// assert_eq!(mascot_generic_formats.position_by_feature_id(usize::MAX), None);
+    /// ```
+    pub fn position_by_feature_id(&self, feature_id: I) -> Option<usize>
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>,
+    {
+        self.iter()
+            .position(|mascot_generic_format| mascot_generic_format.feature_id() == feature_id)
     }
 
-    /// Create a new vector of MGF objects from the file at the provided path.
+    /// Returns the first MGF object with the provided feature ID.
+    ///
+    /// The lookup is a linear scan: when many lookups are needed, consider
+    /// building an index with [`build_feature_index`](Self::build_feature_index).
     ///
     /// # Arguments
-    /// * `path` - The path to the file to read.
+    /// * `feature_id` - The feature ID to look for.
     ///
-    /// # Returns
-    /// A new vector of MGF objects.
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let feature_id = mascot_generic_formats[3].feature_id();
+    ///
+    /// assert_eq!(
+    ///     mascot_generic_formats.get_by_feature_id(feature_id),
+    ///     Some(&mascot_generic_formats[3])
+    /// );
+    /// assert!(mascot_generic_formats.get_by_feature_id(usize::MAX).is_none());
+    /// ```
+    pub fn get_by_feature_id(&self, feature_id: I) -> Option<&MascotGenericFormat<I, F>>
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>,
+    {
+        self.position_by_feature_id(feature_id)
+            .map(|position| &self.mascot_generic_formats[position])
+    }
+
+    /// Returns a map from the feature IDs to the positions of the MGF objects.
     ///
     /// # Errors
-    /// * If the file at the provided path cannot be read.
-    /// * If the file at the provided path cannot be parsed.
+    /// * If two MGF objects share the same feature ID, in which case
+    ///   [`dedup_by_feature_id`](Self::dedup_by_feature_id) or
+    ///   [`dedup_merge_by_feature_id`](Self::dedup_merge_by_feature_id) may be used first.
     ///
     /// # Examples
     ///
-    /// An example of a document that contains only the first level of
-    /// fragmentation spectra:
-    ///
     /// ```
     /// use mascot_rs::prelude::*;
     ///
     /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
-    ///
     /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
     ///
-    /// assert_eq!(mascot_generic_formats.len(), 74, concat!(
-    ///     "The number of MascotGenericFormat objects in the vector should be 74, ",
-    ///     "but it is {}."
-    /// ), mascot_generic_formats.len());
+    /// let index = mascot_generic_formats.build_feature_index().unwrap();
+    ///
+    /// assert_eq!(index.len(), mascot_generic_formats.len());
+    /// for (position, mascot_generic_format) in mascot_generic_formats.iter().enumerate() {
+    ///     assert_eq!(index[&mascot_generic_format.feature_id()], position);
+    /// }
+    ///
+    /// let mut duplicated = MGFVec::new();
+    /// duplicated.push(mascot_generic_formats[0].clone());
+    /// duplicated.push(mascot_generic_formats[0].clone());
+    ///
+    /// assert!(duplicated.build_feature_index().is_err());
     /// ```
+    pub fn build_feature_index(&self) -> Result<HashMap<I, usize>, String>
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero + Hash,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>,
+    {
+        let mut index = HashMap::with_capacity(self.len());
+        for (position, mascot_generic_format) in self.iter().enumerate() {
+            let feature_id = mascot_generic_format.feature_id();
+            if let Some(previous_position) = index.insert(feature_id, position) {
+                return Err(format!(
+                    concat!(
+                        "The feature ID {:?} is shared by the MGF objects at ",
+                        "positions {} and {}, so the feature index cannot be built."
+                    ),
+                    feature_id, previous_position, position
+                ));
+            }
+        }
+        Ok(index)
+    }
+
+    /// Returns the MGF objects whose metadata fields satisfy the provided predicate.
     ///
-    /// An example of another type of documents that contains both the first and
-    /// second level of fragmentation spectra:
+    /// # Arguments
+    /// * `predicate` - The predicate on the [`FieldPresence`] of each MGF object.
+    ///
+    /// # Examples
     ///
     /// ```
     /// use mascot_rs::prelude::*;
     ///
-    /// let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    /// let document = [
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "SMILES=CCO",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "END IONS",
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=2",
+    ///     "PEPMASS=213.1234",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=41.5",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "END IONS",
+    /// ];
     ///
-    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    /// let mascot_generic_formats: MGFVec<usize, f64> =
+    ///     MGFVec::try_from_iter_with_options(document, ParseOptions::lenient()).unwrap();
     ///
-    /// assert_eq!(mascot_generic_formats.len(), 139);
+    /// let with_structure = mascot_generic_formats
+    ///     .filter_by_presence(|presence| presence.has_structure())
+    ///     .map(|mascot_generic_format| mascot_generic_format.feature_id())
+    ///     .collect::<Vec<usize>>();
+    ///
+    /// assert_eq!(with_structure, [1]);
+    /// ```
+    pub fn filter_by_presence<P>(
+        &self,
+        predicate: P,
+    ) -> impl Iterator<Item = &MascotGenericFormat<I, F>>
+    where
+        P: Fn(FieldPresence) -> bool,
+        I: Copy + Add<Output = I> + Eq + Debug + Zero,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>,
+    {
+        self.iter()
+            .filter(move |mascot_generic_format| predicate(mascot_generic_format.field_presence()))
+    }
+
+    /// Returns an iterator over the MGF objects without the first fragmentation level.
+    ///
+    /// # Examples
     ///
     /// ```
+    /// use mascot_rs::prelude::*;
     ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_001.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
     ///
-    pub fn from_path(path: &str) -> Result<Self, String>
+    /// assert_eq!(mascot_generic_formats.entries_missing_ms1().count(), mascot_generic_formats.len());
+    /// assert_eq!(mascot_generic_formats.entries_missing_ms2().count(), 0);
+    /// ```
+    pub fn entries_missing_ms1(&self) -> impl Iterator<Item = &MascotGenericFormat<I, F>>
     where
-        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        I: Copy + Add<Output = I> + Eq + Debug + Zero,
         F: Copy
             + StrictlyPositive
-            + FromStr
             + PartialEq
-            + Debug
             + PartialOrd
-            + NaN
-            + Sub<F, Output = F>
-            + Add<F, Output = F>,
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>,
     {
-        let file = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
-        Self::try_from_iter(file.lines().filter(|line| !line.is_empty()))
+        self.iter()
+            .filter(|mascot_generic_format| !mascot_generic_format.has_first_level())
     }
 
-    pub fn try_from_iter<'a, T>(iter: T) -> Result<Self, String>
+    /// Returns an iterator over the MGF objects without the second fragmentation level.
+    pub fn entries_missing_ms2(&self) -> impl Iterator<Item = &MascotGenericFormat<I, F>>
     where
-        T: IntoIterator<Item = &'a str>,
-        I: Copy + From<usize> + FromStr + Add<Output = I> + Eq + Debug + Zero + Hash,
+        I: Copy + Add<Output = I> + Eq + Debug + Zero,
         F: Copy
             + StrictlyPositive
-            + FromStr
             + PartialEq
+            + PartialOrd
             + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>,
+    {
+        self.iter()
+            .filter(|mascot_generic_format| !mascot_generic_format.has_second_level())
+    }
+
+    /// Returns an index of the MGF objects sorted by parent ion mass.
+    ///
+    /// The positions returned by the index refer to the MGF objects in this vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// let mut library = MGFVec::new();
+    /// for mascot_generic_format in mascot_generic_formats.iter().take(5) {
+    ///     library.push(mascot_generic_format.clone());
+    /// }
+    ///
+    /// let index = library.precursor_index();
+    ///
+    /// assert_eq!(index.len(), 5);
+    ///
+    /// let query = library[2].precursor_mz();
+    /// let candidates = index.candidates(query, 0.01);
+    ///
+    /// assert!(candidates.contains(&2));
+    /// for &candidate in candidates {
+    ///     assert!((library[candidate].precursor_mz() - query).abs() <= 0.01);
+    /// }
+    /// assert_eq!(index.candidates(query, 1000.0).len(), 5);
+    /// ```
+    pub fn precursor_index(&self) -> PrecursorIndex<F>
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
             + PartialOrd
-            + NaN
-            + Sub<F, Output = F>
-            + Add<F, Output = F>,
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>,
     {
-        let mut mascot_generic_formats = MGFVec::new();
-        let mut mascot_generic_format_builder = MascotGenericFormatBuilder::default();
+        PrecursorIndex::from_comparable(
+            self.iter()
+                .map(|mascot_generic_format| mascot_generic_format.precursor_mz()),
+        )
+    }
 
-        for line in iter {
-            mascot_generic_format_builder.digest_line(line)?;
-            if mascot_generic_format_builder.can_build() {
-                mascot_generic_formats.push(mascot_generic_format_builder.build()?);
-                mascot_generic_format_builder = MascotGenericFormatBuilder::default();
-            }
-        }
+    /// Returns the pairs of MGF objects whose modified cosine similarity is
+    /// at least `min_score` with at least `min_matched_peaks` matched peaks.
+    ///
+    /// Only the pairs whose parent ion masses differ by at most
+    /// `max_precursor_difference` are scored, using the [`PrecursorIndex`]
+    /// to prune the others. Each pair is reported once, as `(i, j, score)`
+    /// with `i < j`, sorted by `i` and then by increasing parent ion mass of `j`.
+    /// MGF objects without a second level are skipped. With the `rayon` feature,
+    /// the rows of the upper triangle are scored in parallel.
+    ///
+    /// # Arguments
+    /// * `tolerance` - The tolerance to use when matching mass-charge ratios.
+    /// * `min_score` - The minimum score of the reported pairs.
+    /// * `min_matched_peaks` - The minimum number of matched peaks of the reported pairs.
+    /// * `max_precursor_difference` - The maximum difference between the parent ion masses of the scored pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=200.0",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "50.0 1.0",
+    ///     "100.0 2.0",
+    ///     "150.0 3.0",
+    ///     "END IONS",
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=2",
+    ///     "PEPMASS=210.0",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "50.0 1.0",
+    ///     "100.0 2.0",
+    ///     "160.0 3.0",
+    ///     "END IONS",
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=3",
+    ///     "PEPMASS=500.0",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "50.0 1.0",
+    ///     "100.0 2.0",
+    ///     "150.0 3.0",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// let pairs = mascot_generic_formats.pairwise_modified_cosine(0.01, 0.7, 3, 100.0);
+    ///
+    /// assert_eq!(pairs.len(), 1);
+    /// assert_eq!((pairs[0].0, pairs[0].1), (0, 1));
+    /// assert!((pairs[0].2 - 1.0).abs() < 1e-12);
+    ///
+    /// let pairs = mascot_generic_formats.pairwise_modified_cosine(0.01, 0.7, 3, 1000.0);
+    ///
+    /// assert_eq!(
+    ///     pairs.iter().map(|&(i, j, _)| (i, j)).collect::<Vec<_>>(),
+    ///     vec![(0, 1), (0, 2)]
+    /// );
+    /// ```
+    pub fn pairwise_modified_cosine(
+        &self,
+        tolerance: F,
+        min_score: F,
+        min_matched_peaks: usize,
+        max_precursor_difference: F,
+    ) -> Vec<(usize, usize, F)>
+    where
+        I: Copy + Add<Output = I> + Eq + Debug + Zero + Send + Sync,
+        F: Float + Debug + Send + Sync,
+    {
+        let precursor_index = self.precursor_index();
+        let score_row = |i: usize| {
+            let mascot_generic_format = &self.mascot_generic_formats[i];
+            precursor_index
+                .candidates(
+                    mascot_generic_format.precursor_mz(),
+                    max_precursor_difference,
+                )
+                .iter()
+                .copied()
+                .filter(|&j| j > i)
+                .filter_map(|j| {
+                    let (score, number_of_matches) = mascot_generic_format
+                        .modified_cosine(&self.mascot_generic_formats[j], tolerance)
+                        .ok()?;
+                    (score >= min_score && number_of_matches >= min_matched_peaks)
+                        .then_some((i, j, score))
+                })
+                .collect::<Vec<(usize, usize, F)>>()
+        };
 
-        // We check that the feature id values are unique.
-        let number_of_unique_feature_ids = mascot_generic_formats
-            .iter()
-            .map(|mgf| mgf.feature_id())
-            .collect::<HashSet<I>>()
-            .len();
-        if number_of_unique_feature_ids != mascot_generic_formats.len() {
-            return Err(format!(
-                concat!(
-                    "We have identified {} duplicated feature ids in the MGF document provided. ",
-                    "Specifically, there were {} entries, but only {} unique feature IDs."
-                ),
-                mascot_generic_formats.len() - number_of_unique_feature_ids,
-                mascot_generic_formats.len(),
-                number_of_unique_feature_ids
-            ));
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            (0..self.len())
+                .into_par_iter()
+                .flat_map_iter(score_row)
+                .collect()
         }
 
-        Ok(mascot_generic_formats)
+        #[cfg(not(feature = "rayon"))]
+        {
+            (0..self.len()).flat_map(score_row).collect()
+        }
     }
 
     pub fn push(&mut self, mascot_generic_format: MascotGenericFormat<I, F>) {
@@ -363,6 +4344,108 @@ impl<I, F> MGFVec<I, F> {
         self.mascot_generic_formats
     }
 
+    /// Moves all the MGF objects of `other` into this vector, leaving `other` empty.
+    ///
+    /// No check is made on the feature IDs, so that the resulting vector may
+    /// contain duplicated feature IDs when the two vectors were parsed from
+    /// different documents.
+    ///
+    /// # Arguments
+    /// * `other` - The vector whose MGF objects are moved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_formats: MGFVec<usize, f64> =
+    ///     MGFVec::from_path("tests/data/20220513_PMA_DBGI_01_04_001.mgf").unwrap();
+    /// let mut other: MGFVec<usize, f64> =
+    ///     MGFVec::from_path("tests/data/20220513_PMA_DBGI_01_04_002.mgf").unwrap();
+    /// let total = mascot_generic_formats.len() + other.len();
+    ///
+    /// mascot_generic_formats.append(&mut other);
+    ///
+    /// assert_eq!(mascot_generic_formats.len(), total);
+    /// assert!(other.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Self) {
+        self.mascot_generic_formats
+            .append(&mut other.mascot_generic_formats);
+    }
+
+    /// Returns the concatenation of the provided vectors, in the order provided.
+    ///
+    /// As for [`MGFVec::append`], no check is made on the feature IDs.
+    ///
+    /// # Arguments
+    /// * `vecs` - The vectors to concatenate.
+    pub fn concat(vecs: Vec<Self>) -> Self {
+        let mut concatenated = Self::with_capacity(
+            vecs.iter()
+                .map(|mascot_generic_formats| mascot_generic_formats.len())
+                .sum(),
+        );
+        for mascot_generic_formats in vecs {
+            concatenated.extend(mascot_generic_formats);
+        }
+        concatenated
+    }
+
+    /// Applies the provided in-place transformation to each MGF object.
+    ///
+    /// # Arguments
+    /// * `transform` - The transformation to apply.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    /// let mut mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    ///
+    /// mascot_generic_formats.map(|mascot_generic_format| {
+    ///     mascot_generic_format.map_intensities(|intensity| intensity * 2.0).unwrap();
+    /// });
+    ///
+    /// let original: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    /// assert_eq!(
+    ///     mascot_generic_formats[0].data()[0].fragment_intensities()[0],
+    ///     original[0].data()[0].fragment_intensities()[0] * 2.0
+    /// );
+    /// ```
+    pub fn map<G>(&mut self, transform: G)
+    where
+        G: FnMut(&mut MascotGenericFormat<I, F>),
+    {
+        self.mascot_generic_formats.iter_mut().for_each(transform);
+    }
+
+    /// Applies the provided fallible in-place transformation to each MGF
+    /// object, stopping at the first error.
+    ///
+    /// The MGF objects preceding the one that failed keep their transformation,
+    /// while the following ones are left untouched.
+    ///
+    /// # Arguments
+    /// * `transform` - The transformation to apply.
+    ///
+    /// # Errors
+    /// * If the transformation fails on any of the MGF objects.
+    pub fn try_map<G>(&mut self, transform: G) -> Result<(), String>
+    where
+        G: FnMut(&mut MascotGenericFormat<I, F>) -> Result<(), String>,
+    {
+        self.mascot_generic_formats
+            .iter_mut()
+            .try_for_each(transform)
+    }
+
+    /// Removes all the MGF objects from the vector.
+    ///
+    /// The allocated capacity is retained, so that the vector can be reused
+    /// as a buffer when parsing several documents.
     pub fn clear(&mut self) {
         self.mascot_generic_formats.clear();
     }
@@ -387,3 +4470,24 @@ impl<I, F> IndexMut<usize> for MGFVec<I, F> {
         &mut self.mascot_generic_formats[index]
     }
 }
+
+impl<I, F> IntoIterator for MGFVec<I, F> {
+    type Item = MascotGenericFormat<I, F>;
+    type IntoIter = std::vec::IntoIter<MascotGenericFormat<I, F>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.mascot_generic_formats.into_iter()
+    }
+}
+
+impl<I, F> Extend<MascotGenericFormat<I, F>> for MGFVec<I, F> {
+    fn extend<T: IntoIterator<Item = MascotGenericFormat<I, F>>>(&mut self, iter: T) {
+        self.mascot_generic_formats.extend(iter);
+    }
+}
+
+impl<I, F> From<Vec<MascotGenericFormat<I, F>>> for MGFVec<I, F> {
+    fn from(mascot_generic_formats: Vec<MascotGenericFormat<I, F>>) -> Self {
+        Self::from_vec(mascot_generic_formats)
+    }
+}
@@ -1,13 +1,29 @@
 use std::{fmt::Debug, ops::Add, ops::Sub, str::FromStr};
 
+use crate::line_parser::{is_comment, is_section_delimiter};
 use crate::prelude::*;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 /// A builder for [`MascotGenericFormat`].
+///
+/// # Multi-section entries
+/// Some documents, such as the ones exported by SIRIUS, split an entry into
+/// several `BEGIN IONS`/`END IONS` sections sharing the same `FEATURE_ID`,
+/// typically one with `MSLEVEL=1` followed by one with `MSLEVEL=2`. All of the
+/// sections but the last one are marked with `SCANS=-1`, and the builder keeps
+/// accumulating the sections until one without this marker is closed. Drivers
+/// feeding lines to the builder should therefore only build the entry once
+/// [`LineParser::can_build`] returns `true`, while
+/// [`MascotGenericFormatBuilder::is_awaiting_second_level`] tells whether a
+/// partial section was closed and the following section is expected to
+/// complete the entry.
 pub struct MascotGenericFormatBuilder<I, F> {
     metadata_builder: MascotGenericFormatMetadataBuilder<I, F>,
     data_builders: Vec<MascotGenericFormatDataBuilder<F>>,
     section_open: bool,
+    pending_section: bool,
+    raw_fields: Vec<Vec<(String, String)>>,
+    options: ParseOptions,
 }
 
 impl<I, F> Default for MascotGenericFormatBuilder<I, F>
@@ -16,10 +32,256 @@ where
     F: Copy + StrictlyPositive + FromStr + PartialEq + Debug,
 {
     fn default() -> Self {
+        Self::with_options(ParseOptions::default())
+    }
+}
+
+impl<I, F> MascotGenericFormatBuilder<I, F> {
+    /// Creates a new builder using the provided [`ParseOptions`].
+    ///
+    /// # Arguments
+    /// * `options` - The options to use while parsing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::with_options(
+    ///     ParseOptions::lenient()
+    /// );
+    ///
+    /// for line in [
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "SOURCE_INSTRUMENT=LC-ESI-qTof",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "END IONS",
+    /// ] {
+    ///     mascot_generic_format_builder.digest_line(line).unwrap();
+    /// }
+    ///
+    /// let mascot_generic_format = mascot_generic_format_builder.build().unwrap();
+    ///
+    /// assert_eq!(
+    ///     mascot_generic_format.extra().get("SOURCE_INSTRUMENT").map(String::as_str),
+    ///     Some("LC-ESI-qTof")
+    /// );
+    /// ```
+    pub fn with_options(options: ParseOptions) -> Self {
+        Self {
+            metadata_builder: MascotGenericFormatMetadataBuilder::with_options(options),
+            data_builders: Vec::new(),
+            section_open: false,
+            pending_section: false,
+            raw_fields: Vec::new(),
+            options,
+        }
+    }
+
+    /// Creates a new builder seeded with the provided metadata builder.
+    ///
+    /// The parse options of the metadata builder are used for the
+    /// whole builder. Together with [`MascotGenericFormatBuilder::push_peak`],
+    /// this allows to synthesize spectra without any text input.
+    ///
+    /// # Arguments
+    /// * `metadata_builder` - The metadata builder to seed the builder with.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut metadata_builder = MascotGenericFormatMetadataBuilder::<usize, f64>::default();
+    /// for line in ["FEATURE_ID=1", "PEPMASS=381.0795", "CHARGE=1", "RTINSECONDS=37.083"] {
+    ///     metadata_builder.digest_line(line).unwrap();
+    /// }
+    ///
+    /// let mut mascot_generic_format_builder = MascotGenericFormatBuilder::with_metadata(metadata_builder);
+    ///
+    /// mascot_generic_format_builder.push_peak(FragmentationSpectraLevel::Two, 60.5425, 2.4E5).unwrap();
+    /// mascot_generic_format_builder.push_peak(FragmentationSpectraLevel::Two, 119.0857, 3.3E5).unwrap();
+    ///
+    /// assert!(mascot_generic_format_builder.push_peak(FragmentationSpectraLevel::Two, 72.6217, 2.1E4).is_err());
+    /// assert!(mascot_generic_format_builder.push_peak(FragmentationSpectraLevel::Two, 150.0, 0.0).is_err());
+    /// assert!(mascot_generic_format_builder.can_build());
+    ///
+    /// let mascot_generic_format = mascot_generic_format_builder.build().unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format.feature_id(), 1);
+    /// assert_eq!(mascot_generic_format.num_peaks(FragmentationSpectraLevel::Two), Some(2));
+    /// assert_eq!(
+    ///     mascot_generic_format.get_second_fragmentation_level().unwrap().fragment_intensities(),
+    ///     &[2.4E5, 3.3E5]
+    /// );
+    /// ```
+    pub fn with_metadata(metadata_builder: MascotGenericFormatMetadataBuilder<I, F>) -> Self {
+        let options = metadata_builder.options();
         Self {
-            metadata_builder: MascotGenericFormatMetadataBuilder::default(),
+            metadata_builder,
             data_builders: Vec::new(),
             section_open: false,
+            pending_section: false,
+            raw_fields: Vec::new(),
+            options,
+        }
+    }
+
+    /// Returns the warnings raised while digesting the lines of the entry being built.
+    pub fn warnings(&self) -> Vec<String> {
+        self.data_builders
+            .iter()
+            .flat_map(|builder| builder.warnings().iter().cloned())
+            .collect()
+    }
+
+    /// Returns whether a `BEGIN IONS` section is currently open.
+    pub fn is_section_open(&self) -> bool {
+        self.section_open
+    }
+
+    /// Returns whether a section marked with `SCANS=-1` was closed, and the
+    /// builder is waiting for the following section of the same entry.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::default();
+    ///
+    /// for line in [
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "SPECTYPE=CORRELATED MS",
+    ///     "MSLEVEL=1",
+    ///     "SCANS=-1",
+    ///     "381.0795 7.8E8",
+    ///     "END IONS",
+    /// ] {
+    ///     mascot_generic_format_builder.digest_line(line).unwrap();
+    /// }
+    ///
+    /// assert!(mascot_generic_format_builder.is_awaiting_second_level());
+    /// assert!(!mascot_generic_format_builder.can_build());
+    ///
+    /// for line in [
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "SCANS=1",
+    ///     "60.5425 2.4E5",
+    ///     "END IONS",
+    /// ] {
+    ///     mascot_generic_format_builder.digest_line(line).unwrap();
+    /// }
+    ///
+    /// assert!(!mascot_generic_format_builder.is_awaiting_second_level());
+    /// assert!(mascot_generic_format_builder.can_build());
+    ///
+    /// let mascot_generic_format = mascot_generic_format_builder.build().unwrap();
+    ///
+    /// assert!(mascot_generic_format.has_second_level());
+    /// ```
+    pub fn is_awaiting_second_level(&self) -> bool {
+        !self.section_open && self.metadata_builder.is_partial()
+    }
+
+    /// Resets the builder to its default state, retaining the allocated
+    /// capacity and the parse options.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::default();
+    ///
+    /// for line in [
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "MERGED_SCANS=1567,1540",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    /// ] {
+    ///     mascot_generic_format_builder.digest_line(line).unwrap();
+    /// }
+    ///
+    /// mascot_generic_format_builder.reset();
+    ///
+    /// assert_eq!(mascot_generic_format_builder, MascotGenericFormatBuilder::default());
+    /// ```
+    pub fn reset(&mut self) {
+        self.metadata_builder.reset();
+        self.data_builders.clear();
+        self.section_open = false;
+        self.pending_section = false;
+        self.raw_fields.clear();
+    }
+
+    /// Opens a new `BEGIN IONS` section.
+    fn open_section(&mut self) {
+        self.section_open = true;
+        self.data_builders
+            .push(MascotGenericFormatDataBuilder::with_options(self.options));
+        if self.options.preserves_field_order() {
+            self.raw_fields.push(Vec::new());
+        }
+    }
+
+    /// Returns the feature ID of the entry being built, if already known.
+    pub fn feature_id(&self) -> Option<I>
+    where
+        I: Copy,
+    {
+        self.metadata_builder.feature_id()
+    }
+
+    /// Returns the parent ion mass derived from the first fragmentation
+    /// level, i.e. its minimum mass divided by charge ratio, when no
+    /// `PEPMASS` line was digested and the parse options allow deriving it.
+    fn derived_parent_ion_mass(&self) -> Option<F>
+    where
+        F: PartialOrd + Copy,
+    {
+        if !self.options.derives_missing_parent_ion_mass()
+            || self.metadata_builder.parent_ion_mass().is_some()
+        {
+            return None;
+        }
+        self.data_builders
+            .iter()
+            .find(|builder| builder.level() == Some(FragmentationSpectraLevel::One))
+            .and_then(|builder| builder.min_mass_divided_by_charge_ratio())
+    }
+
+    /// Sets the parent ion mass derived from the first fragmentation level
+    /// when it is missing, and returns the precursor tolerance to build with.
+    ///
+    /// A derived parent ion mass is the minimum of the first level by
+    /// construction, so it is not validated against it again.
+    fn derive_missing_parent_ion_mass(&mut self) -> Option<f64>
+    where
+        F: PartialOrd + Copy,
+    {
+        match self.derived_parent_ion_mass() {
+            Some(parent_ion_mass) => {
+                self.metadata_builder.set_parent_ion_mass(parent_ion_mass);
+                None
+            }
+            None => self.options.validated_precursor_tolerance(),
         }
     }
 }
@@ -33,35 +295,224 @@ where
         + PartialOrd
         + Debug
         + Sub<F, Output = F>
-        + Add<F, Output = F>,
+        + Add<F, Output = F>
+        + ApproxEq
+        + Zero,
 {
     /// Builds a [`MascotGenericFormat`] from the given data.
-    pub fn build(self) -> Result<MascotGenericFormat<I, F>, String> {
-        MascotGenericFormat::new(
-            self.metadata_builder.build()?,
+    ///
+    /// When the entry has no `PEPMASS` field but has a first fragmentation
+    /// level, and [`ParseOptions::with_derive_missing_parent_ion_mass`] is
+    /// enabled, the parent ion mass is derived from the minimum mass divided
+    /// by charge ratio of the first level.
+    pub fn build(mut self) -> Result<MascotGenericFormat<I, F>, String> {
+        let precursor_tolerance = self.derive_missing_parent_ion_mass();
+        MascotGenericFormat::new_with_precursor_tolerance(
+            self.metadata_builder
+                .build()?
+                .with_raw_fields(self.raw_fields),
             self.data_builders
                 .into_iter()
                 .map(|builder| builder.build())
                 .collect::<Result<Vec<_>, String>>()?,
+            precursor_tolerance,
+        )
+    }
+
+    /// Builds a [`MascotGenericFormat`] from the given data, leaving the
+    /// builder in its default state for the next entry.
+    ///
+    /// The fields and the peaks of the entry are moved into the built
+    /// object, so the metadata and data builders are replaced rather than
+    /// reset in place: only the vector holding the data builders of the
+    /// fragmentation levels retains its allocated capacity.
+    ///
+    /// The builder is reset even when the build fails. When the entry was
+    /// closed by the `BEGIN IONS` line of the next one, as allowed by the
+    /// lenient parse options, the section of the next entry is then opened.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::default();
+    ///
+    /// for feature_id in [1, 2] {
+    ///     for line in [
+    ///         "BEGIN IONS",
+    ///         &format!("FEATURE_ID={}", feature_id),
+    ///         "PEPMASS=381.0795",
+    ///         "CHARGE=1",
+    ///         "RTINSECONDS=37.083",
+    ///         "MSLEVEL=2",
+    ///         "60.5425 2.4E5",
+    ///         "END IONS",
+    ///     ] {
+    ///         mascot_generic_format_builder.digest_line(line).unwrap();
+    ///     }
+    ///
+    ///     let mascot_generic_format = mascot_generic_format_builder.build_and_reset().unwrap();
+    ///
+    ///     assert_eq!(mascot_generic_format.feature_id(), feature_id);
+    ///     assert_eq!(mascot_generic_format_builder, MascotGenericFormatBuilder::default());
+    /// }
+    /// ```
+    pub fn build_and_reset(&mut self) -> Result<MascotGenericFormat<I, F>, String> {
+        let precursor_tolerance = self.derive_missing_parent_ion_mass();
+        let metadata = std::mem::replace(
+            &mut self.metadata_builder,
+            MascotGenericFormatMetadataBuilder::with_options(self.options),
         )
+        .build();
+        let data = self
+            .data_builders
+            .drain(..)
+            .map(|builder| builder.build())
+            .collect::<Result<Vec<_>, String>>();
+        let raw_fields = std::mem::take(&mut self.raw_fields);
+        let pending_section = self.pending_section;
+        self.reset();
+        if pending_section {
+            self.open_section();
+        }
+        MascotGenericFormat::new_with_precursor_tolerance(
+            metadata?.with_raw_fields(raw_fields),
+            data?,
+            precursor_tolerance,
+        )
+    }
+}
+
+impl<I, F> MascotGenericFormatBuilder<I, F>
+where
+    F: Copy + StrictlyPositive + PartialOrd + Debug + NaN + Zero,
+{
+    /// Adds a peak to the requested fragmentation level, bypassing the parsing of a line.
+    ///
+    /// The data of the level is created if it does not exist yet, and the
+    /// peak is validated as if it were digested from a line.
+    ///
+    /// # Arguments
+    /// * `level` - The fragmentation level of the peak.
+    /// * `mass_divided_by_charge_ratio` - The mass divided by charge ratio of the peak.
+    /// * `fragment_intensity` - The intensity of the peak.
+    ///
+    /// # Errors
+    /// * If either value is NaN, zero or negative.
+    /// * If the peaks of a level-two spectrum are not provided in ascending order.
+    pub fn push_peak(
+        &mut self,
+        level: FragmentationSpectraLevel,
+        mass_divided_by_charge_ratio: F,
+        fragment_intensity: F,
+    ) -> Result<(), String> {
+        let data_builder = match self
+            .data_builders
+            .iter()
+            .position(|data_builder| data_builder.level() == Some(level))
+        {
+            Some(position) => &mut self.data_builders[position],
+            None => {
+                let mut data_builder = MascotGenericFormatDataBuilder::with_options(self.options);
+                data_builder.set_level(level);
+                self.data_builders.push(data_builder);
+                self.data_builders.last_mut().unwrap()
+            }
+        };
+        data_builder.push_peak(mass_divided_by_charge_ratio, fragment_intensity)
+    }
+}
+
+impl<I, F> MascotGenericFormatBuilder<I, F>
+where
+    I: Copy + FromStr + Eq + Add<Output = I> + Debug + From<usize>,
+    F: ParsableFloat,
+{
+    /// Returns the current state of the builder.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::default();
+    ///
+    /// assert_eq!(mascot_generic_format_builder.state(), BuilderState::Empty);
+    ///
+    /// for (line, state) in [
+    ///     ("BEGIN IONS", BuilderState::MetadataOpen),
+    ///     ("FEATURE_ID=1", BuilderState::MetadataOpen),
+    ///     ("PEPMASS=381.0795", BuilderState::MetadataOpen),
+    ///     ("CHARGE=1", BuilderState::MetadataOpen),
+    ///     ("RTINSECONDS=37.083", BuilderState::MetadataOpen),
+    ///     ("SPECTYPE=CORRELATED MS", BuilderState::MetadataOpen),
+    ///     ("MSLEVEL=1", BuilderState::FirstLevel),
+    ///     ("SCANS=-1", BuilderState::FirstLevel),
+    ///     ("381.0795 7.8E8", BuilderState::FirstLevel),
+    ///     ("END IONS", BuilderState::AwaitingSecondLevel),
+    ///     ("BEGIN IONS", BuilderState::MetadataOpen),
+    ///     ("MSLEVEL=2", BuilderState::SecondLevel),
+    ///     ("SCANS=1", BuilderState::SecondLevel),
+    ///     ("60.5425 2.4E5", BuilderState::SecondLevel),
+    ///     ("END IONS", BuilderState::Buildable),
+    /// ] {
+    ///     mascot_generic_format_builder.digest_line(line).unwrap();
+    ///     assert_eq!(mascot_generic_format_builder.state(), state, "{}", line);
+    /// }
+    ///
+    /// mascot_generic_format_builder.reset();
+    ///
+    /// for line in ["BEGIN IONS", "FEATURE_ID=1", "MSLEVEL=2", "60.5425 2.4E5", "END IONS"] {
+    ///     mascot_generic_format_builder.digest_line(line).unwrap();
+    /// }
+    ///
+    /// assert_eq!(mascot_generic_format_builder.state(), BuilderState::Corrupted);
+    /// ```
+    pub fn state(&self) -> BuilderState {
+        if self.section_open {
+            return match self
+                .data_builders
+                .last()
+                .and_then(|builder| builder.level())
+            {
+                None => BuilderState::MetadataOpen,
+                Some(FragmentationSpectraLevel::One) => BuilderState::FirstLevel,
+                Some(FragmentationSpectraLevel::Two) => BuilderState::SecondLevel,
+            };
+        }
+        if self.data_builders.is_empty() {
+            BuilderState::Empty
+        } else if self.is_awaiting_second_level() {
+            BuilderState::AwaitingSecondLevel
+        } else if self.can_build() {
+            BuilderState::Buildable
+        } else {
+            BuilderState::Corrupted
+        }
     }
 }
 
 impl<I, F> LineParser for MascotGenericFormatBuilder<I, F>
 where
-    I: Copy + FromStr + Eq + Add<Output = I> + Debug,
-    F: Copy + StrictlyPositive + FromStr + PartialEq + Debug + NaN + PartialOrd,
+    I: Copy + FromStr + Eq + Add<Output = I> + Debug + From<usize>,
+    F: ParsableFloat,
 {
     fn can_parse_line(line: &str) -> bool {
-        line == "BEGIN IONS"
-            || line == "END IONS"
+        let line = line.trim_end();
+        line.is_empty()
+            || is_section_delimiter(line, "BEGIN IONS")
+            || is_section_delimiter(line, "END IONS")
+            || is_comment(line)
             || MascotGenericFormatMetadataBuilder::<I, F>::can_parse_line(line)
             || MascotGenericFormatDataBuilder::<F>::can_parse_line(line)
     }
 
     fn can_build(&self) -> bool {
         !self.section_open
-            && self.metadata_builder.can_build()
+            && (self.metadata_builder.can_build()
+                || self.derived_parent_ion_mass().is_some()
+                    && self.metadata_builder.can_build_without_parent_ion_mass())
             && !self.data_builders.is_empty()
             && self.data_builders.iter().all(|builder| builder.can_build())
     }
@@ -76,6 +527,7 @@ where
     ///
     /// # Errors
     /// * If the line cannot be digested.
+    /// * If a `BEGIN IONS` line closed the previous entry, which was not built yet.
     ///
     /// # Examples
     ///
@@ -88,14 +540,177 @@ where
     /// assert!(mascot_generic_format_builder.digest_line("END IONS").is_ok());
     /// assert!(mascot_generic_format_builder.digest_line("TITLE=File:").is_err());
     /// ```
+    ///
+    /// Blank lines are ignored, even between peaks:
+    ///
+    /// ```rust
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::default();
+    ///
+    /// for line in [
+    ///     "",
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "  ",
+    ///     "119.0857 3.3E5",
+    ///     "END IONS",
+    /// ] {
+    ///     mascot_generic_format_builder.digest_line(line).unwrap();
+    /// }
+    ///
+    /// let mascot_generic_format = mascot_generic_format_builder.build().unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format.num_peaks(FragmentationSpectraLevel::Two), Some(2));
+    /// ```
+    ///
+    /// Lines starting with `#` or `;` are comments, which are ignored
+    /// when allowed by the [`ParseOptions`], even between peaks:
+    ///
+    /// ```rust
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::with_options(
+    ///     ParseOptions::default().with_allow_comments(true)
+    /// );
+    ///
+    /// for line in [
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "# The following peak was manually curated",
+    ///     "119.0857 3.3E5",
+    ///     "END IONS",
+    /// ] {
+    ///     mascot_generic_format_builder.digest_line(line).unwrap();
+    /// }
+    ///
+    /// let mascot_generic_format = mascot_generic_format_builder.build().unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format.num_peaks(FragmentationSpectraLevel::Two), Some(2));
+    /// ```
+    ///
+    /// Trailing whitespace, such as the carriage return left by documents
+    /// authored on Windows, is ignored:
+    ///
+    /// ```rust
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::default();
+    ///
+    /// for line in [
+    ///     "BEGIN IONS\r",
+    ///     "FEATURE_ID=1\r",
+    ///     "PEPMASS=381.0795 \r",
+    ///     "CHARGE=1\r",
+    ///     "RTINSECONDS=37.083\r",
+    ///     "MSLEVEL=2\r",
+    ///     "60.5425 2.4E5\r",
+    ///     "END IONS\r",
+    /// ] {
+    ///     mascot_generic_format_builder.digest_line(line).unwrap();
+    /// }
+    ///
+    /// assert!(mascot_generic_format_builder.can_build());
+    /// ```
+    ///
+    /// With the lenient parse options, a `BEGIN IONS` line closes the section
+    /// left open by a missing `END IONS` line. The new section is opened once
+    /// the complete entry is built with [`MascotGenericFormatBuilder::build_and_reset`]:
+    ///
+    /// ```rust
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::with_options(
+    ///     ParseOptions::lenient()
+    /// );
+    ///
+    /// mascot_generic_format_builder.digest_lines([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "BEGIN IONS",
+    /// ]).unwrap();
+    ///
+    /// assert!(mascot_generic_format_builder.can_build());
+    /// assert!(mascot_generic_format_builder.digest_line("FEATURE_ID=2").is_err());
+    ///
+    /// let mascot_generic_format = mascot_generic_format_builder.build_and_reset().unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format.feature_id(), 1);
+    /// assert!(mascot_generic_format_builder.is_section_open());
+    ///
+    /// mascot_generic_format_builder.digest_line("FEATURE_ID=2").unwrap();
+    /// assert_eq!(mascot_generic_format_builder.feature_id(), Some(2));
+    /// ```
     fn digest_line(&mut self, line: &str) -> Result<(), String> {
-        if line == "BEGIN IONS" {
-            self.section_open = true;
-            self.data_builders
-                .push(MascotGenericFormatDataBuilder::default());
-        } else if line == "END IONS" {
+        let line = line.trim_end();
+        // Blank lines, such as the ones separating the entries of most
+        // documents, are ignored wherever they appear.
+        if line.is_empty() {
+            return Ok(());
+        }
+        if is_comment(line) {
+            if !self.options.allows_comments() {
+                return Err(format!(
+                    concat!(
+                        "Encountered the comment line \"{}\", but comments ",
+                        "are not allowed by the current parse options."
+                    ),
+                    line
+                ));
+            }
+            return Ok(());
+        }
+        if self.pending_section {
+            return Err(format!(
+                concat!(
+                    "Encountered the line \"{}\" after a `BEGIN IONS` line closing ",
+                    "the previous entry, which must be built before digesting the next one."
+                ),
+                line
+            ));
+        }
+        if is_section_delimiter(line, "BEGIN IONS") {
+            // In lenient mode, a `BEGIN IONS` line encountered while a section
+            // is still open is treated as if it were preceded by the missing
+            // `END IONS` line. Unless the closed section is a partial one, the
+            // entry is complete, and the new section is opened once it is built.
+            if self.section_open && !self.options.is_strict() {
+                self.section_open = false;
+                if !self.is_awaiting_second_level() {
+                    self.pending_section = true;
+                    return Ok(());
+                }
+            }
+            self.open_section();
+            return Ok(());
+        }
+        if is_section_delimiter(line, "END IONS") {
             self.section_open = false;
-        } else if MascotGenericFormatMetadataBuilder::<I, F>::can_parse_line(line) {
+            return Ok(());
+        }
+        let is_metadata_line =
+            MascotGenericFormatMetadataBuilder::<I, F>::can_parse_line_with_options(
+                line,
+                self.options,
+            ) || !self.options.is_strict()
+                && line.contains('=')
+                && !MascotGenericFormatDataBuilder::<F>::can_parse_line(line);
+        if is_metadata_line {
             self.metadata_builder.digest_line(line)?;
         } else if let Some(data_builder) = self.data_builders.last_mut() {
             data_builder.digest_line(line)?;
@@ -111,6 +726,12 @@ where
             ));
         }
 
+        if let Some((key, value)) = line.split_once('=') {
+            if let Some(raw_fields) = self.raw_fields.last_mut() {
+                raw_fields.push((key.to_string(), value.to_string()));
+            }
+        }
+
         Ok(())
     }
 }
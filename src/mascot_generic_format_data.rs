@@ -1,8 +1,21 @@
 use crate::prelude::*;
+use std::fmt::Debug;
+use std::ops::{Add, Sub};
 
-#[derive(Debug, Clone)]
+/// The mass difference between the carbon-13 and carbon-12 isotopes, in
+/// daltons, which spaces the peaks of the isotopic envelope of an ion with
+/// a single charge.
+pub const ISOTOPE_SPACING: f64 = 1.003355;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The peaks of a single fragmentation level.
+///
+/// Equality compares the level, the spectrum type and the peaks exactly,
+/// without any tolerance on the floating point values.
 pub struct MascotGenericFormatData<F> {
     level: FragmentationSpectraLevel,
+    spec_type: Option<String>,
     mass_divided_by_charge_ratios: Vec<F>,
     fragment_intensities: Vec<F>,
 }
@@ -80,23 +93,149 @@ impl<F: PartialOrd + Copy> MascotGenericFormatData<F> {
 
         Ok(Self {
             level,
+            spec_type: None,
             mass_divided_by_charge_ratios,
             fragment_intensities,
         })
     }
 
+    /// Creates a new [`MascotGenericFormatData`] from the provided peaks.
+    ///
+    /// # Arguments
+    /// * `level` - The [`FragmentationSpectraLevel`] of the data.
+    /// * `peaks` - The peaks of the data.
+    ///
+    /// # Errors
+    /// * If `peaks` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let peaks = vec![Peak::new(60.5425, 2.4E5), Peak::new(119.0857, 3.3E5)];
+    ///
+    /// let mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::from_peaks(
+    ///    FragmentationSpectraLevel::Two,
+    ///    peaks.clone(),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format_data.mass_divided_by_charge_ratios(), &[60.5425, 119.0857]);
+    /// assert_eq!(mascot_generic_format_data.fragment_intensities(), &[2.4E5, 3.3E5]);
+    /// assert_eq!(mascot_generic_format_data.to_peaks(), peaks);
+    ///
+    /// assert!(MascotGenericFormatData::<f64>::from_peaks(FragmentationSpectraLevel::Two, Vec::new()).is_err());
+    /// ```
+    pub fn from_peaks(
+        level: FragmentationSpectraLevel,
+        peaks: Vec<Peak<F>>,
+    ) -> Result<Self, String> {
+        let (mass_divided_by_charge_ratios, fragment_intensities) =
+            peaks.into_iter().map(<(F, F)>::from).unzip();
+        Self::new(level, mass_divided_by_charge_ratios, fragment_intensities)
+    }
+
+    /// Creates a new [`MascotGenericFormatData`], sorting the peaks by
+    /// increasing mass divided by charge ratio.
+    ///
+    /// The two vectors are sorted together, so that each intensity stays
+    /// paired with its mass divided by charge ratio.
+    ///
+    /// # Arguments
+    /// * `level` - The [`FragmentationSpectraLevel`] of the data.
+    /// * `mass_divided_by_charge_ratios` - The mass divided by charge ratios of the data, in any order.
+    /// * `fragment_intensities` - The fragment intensities of the data.
+    ///
+    /// # Errors
+    /// * If the length of `mass_divided_by_charge_ratios` and `fragment_intensities` are not equal.
+    /// * If `mass_divided_by_charge_ratios` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new_sorted(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![119.0857, 60.5425, 72.6217],
+    ///    vec![3.3E5, 2.4E5, 2.1E4],
+    /// ).unwrap();
+    ///
+    /// assert!(mascot_generic_format_data.is_sorted_by_mz());
+    /// assert_eq!(mascot_generic_format_data.mass_divided_by_charge_ratios(), &[60.5425, 72.6217, 119.0857]);
+    /// assert_eq!(mascot_generic_format_data.fragment_intensities(), &[2.4E5, 2.1E4, 3.3E5]);
+    ///
+    /// assert!(MascotGenericFormatData::new_sorted(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![119.0857, 60.5425],
+    ///    vec![3.3E5],
+    /// ).is_err());
+    /// assert!(MascotGenericFormatData::<f64>::new_sorted(FragmentationSpectraLevel::Two, Vec::new(), Vec::new()).is_err());
+    /// ```
+    pub fn new_sorted(
+        level: FragmentationSpectraLevel,
+        mass_divided_by_charge_ratios: Vec<F>,
+        fragment_intensities: Vec<F>,
+    ) -> Result<Self, String> {
+        let mut data = Self::new(level, mass_divided_by_charge_ratios, fragment_intensities)?;
+        data.sort_by_mz();
+        Ok(data)
+    }
+
+    /// Returns the peaks of the data.
+    pub fn to_peaks(&self) -> Vec<Peak<F>> {
+        self.mass_divided_by_charge_ratios_iter()
+            .copied()
+            .zip(self.fragment_intensities_iter().copied())
+            .map(Peak::from)
+            .collect()
+    }
+
     /// Returns the [`FragmentationSpectraLevel`] of the data.
     pub fn level(&self) -> FragmentationSpectraLevel {
         self.level
     }
 
+    /// Sets the spectrum type provided by the `SPECTYPE` field.
+    ///
+    /// # Arguments
+    /// * `spec_type` - The spectrum type of the data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::One,
+    ///    vec![381.0795],
+    ///    vec![7.8E8],
+    /// ).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format_data.spec_type(), None);
+    ///
+    /// let mascot_generic_format_data = mascot_generic_format_data.with_spec_type(Some("SINGLE SCAN".to_string()));
+    ///
+    /// assert_eq!(mascot_generic_format_data.spec_type(), Some("SINGLE SCAN"));
+    /// ```
+    pub fn with_spec_type(mut self, spec_type: Option<String>) -> Self {
+        self.spec_type = spec_type;
+        self
+    }
+
+    /// Returns the spectrum type provided by the `SPECTYPE` field, such as
+    /// `CORRELATED MS`, if any.
+    pub fn spec_type(&self) -> Option<&str> {
+        self.spec_type.as_deref()
+    }
+
     /// Returns the mass divided by charge ratios of the data.
     pub fn mass_divided_by_charge_ratios(&self) -> &[F] {
         &self.mass_divided_by_charge_ratios
     }
 
     /// Returns iterator over the mass divided by charge ratios of the data.
-    pub fn mass_divided_by_charge_ratios_iter(&self) -> std::slice::Iter<F> {
+    pub fn mass_divided_by_charge_ratios_iter(&self) -> std::slice::Iter<'_, F> {
         self.mass_divided_by_charge_ratios.iter()
     }
 
@@ -118,13 +257,787 @@ impl<F: PartialOrd + Copy> MascotGenericFormatData<F> {
             .unwrap())
     }
 
+    /// Returns whether the peaks are sorted by increasing mass divided by charge ratio.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::One,
+    ///    vec![119.0857, 60.5425, 72.6217],
+    ///    vec![3.3E5, 2.4E5, 2.1E4],
+    /// ).unwrap();
+    ///
+    /// assert!(!mascot_generic_format_data.is_sorted_by_mz());
+    ///
+    /// mascot_generic_format_data.sort_by_mz();
+    ///
+    /// assert!(mascot_generic_format_data.is_sorted_by_mz());
+    /// assert_eq!(mascot_generic_format_data.mass_divided_by_charge_ratios(), &[60.5425, 72.6217, 119.0857]);
+    /// assert_eq!(mascot_generic_format_data.fragment_intensities(), &[2.4E5, 2.1E4, 3.3E5]);
+    /// ```
+    pub fn is_sorted_by_mz(&self) -> bool {
+        self.mass_divided_by_charge_ratios
+            .windows(2)
+            .all(|window| window[0] <= window[1])
+    }
+
+    /// Sorts the peaks by increasing mass divided by charge ratio.
+    ///
+    /// The sort is stable, so peaks with the same mass divided by charge
+    /// ratio keep their relative order.
+    pub fn sort_by_mz(&mut self) {
+        if self.is_sorted_by_mz() {
+            return;
+        }
+        let mut indices = (0..self.mass_divided_by_charge_ratios.len()).collect::<Vec<usize>>();
+        indices.sort_by(|&x, &y| {
+            self.mass_divided_by_charge_ratios[x]
+                .partial_cmp(&self.mass_divided_by_charge_ratios[y])
+                .unwrap()
+        });
+        self.mass_divided_by_charge_ratios = indices
+            .iter()
+            .map(|&index| self.mass_divided_by_charge_ratios[index])
+            .collect();
+        self.fragment_intensities = indices
+            .iter()
+            .map(|&index| self.fragment_intensities[index])
+            .collect();
+    }
+
+    /// Merges the peaks of two data of the same fragmentation level.
+    ///
+    /// The peaks are concatenated and sorted by increasing mass divided by
+    /// charge ratio. Peaks sharing the same mass divided by charge ratio are
+    /// all kept, with the peaks of `self` preceding the ones of `other`.
+    ///
+    /// # Arguments
+    /// * `other` - The data to merge with.
+    ///
+    /// # Errors
+    /// * If the two data have different fragmentation levels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let left: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![60.5425, 119.0857],
+    ///    vec![2.4E5, 3.3E5],
+    /// ).unwrap();
+    /// let right: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![72.6217, 150.0],
+    ///    vec![2.1E4, 1.0E3],
+    /// ).unwrap();
+    ///
+    /// let merged = left.clone().merge(right).unwrap();
+    ///
+    /// assert!(merged.is_sorted_by_mz());
+    /// assert_eq!(merged.mass_divided_by_charge_ratios(), &[60.5425, 72.6217, 119.0857, 150.0]);
+    /// assert_eq!(merged.fragment_intensities(), &[2.4E5, 2.1E4, 3.3E5, 1.0E3]);
+    ///
+    /// let first_level: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::One,
+    ///    vec![381.0795],
+    ///    vec![1.0E6],
+    /// ).unwrap();
+    ///
+    /// assert!(left.merge(first_level).is_err());
+    /// ```
+    pub fn merge(mut self, other: Self) -> Result<Self, String> {
+        if self.level != other.level {
+            return Err(format!(
+                concat!(
+                    "Could not merge MascotGenericFormatData: the levels ",
+                    "{:?} and {:?} are different."
+                ),
+                self.level, other.level
+            ));
+        }
+        self.mass_divided_by_charge_ratios
+            .extend(other.mass_divided_by_charge_ratios);
+        self.fragment_intensities.extend(other.fragment_intensities);
+        self.sort_by_mz();
+        Ok(self)
+    }
+
+    /// Returns whether the data has a peak whose mass divided by charge ratio
+    /// is within the provided tolerance of the provided one.
+    ///
+    /// The peaks are looked up by binary search, which requires them to be
+    /// sorted by increasing mass divided by charge ratio, as the builders ensure
+    /// for level-two data. The result is unspecified when the peaks are not
+    /// sorted, in which case [`MascotGenericFormatData::sort_by_mz`] should be
+    /// called first.
+    ///
+    /// # Arguments
+    /// * `mass_divided_by_charge_ratio` - The mass divided by charge ratio to look for.
+    /// * `tolerance` - The absolute tolerance of the lookup.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![60.5425, 72.6217, 119.0857],
+    ///    vec![2.4E5, 2.1E4, 3.3E5],
+    /// ).unwrap();
+    ///
+    /// assert!(mascot_generic_format_data.contains_mz_within(72.6217, 0.0));
+    /// assert!(mascot_generic_format_data.contains_mz_within(72.62, 0.01));
+    /// assert!(mascot_generic_format_data.contains_mz_within(119.09, 0.01));
+    /// assert!(!mascot_generic_format_data.contains_mz_within(72.6, 0.01));
+    /// assert!(!mascot_generic_format_data.contains_mz_within(50.0, 0.01));
+    /// assert!(!mascot_generic_format_data.contains_mz_within(150.0, 0.01));
+    /// ```
+    pub fn contains_mz_within(&self, mass_divided_by_charge_ratio: F, tolerance: F) -> bool
+    where
+        F: Add<F, Output = F> + Sub<F, Output = F>,
+    {
+        let low_bound = mass_divided_by_charge_ratio - tolerance;
+        let position = self
+            .mass_divided_by_charge_ratios
+            .partition_point(|&value| value < low_bound);
+        self.mass_divided_by_charge_ratios
+            .get(position)
+            .is_some_and(|&value| value <= mass_divided_by_charge_ratio + tolerance)
+    }
+
+    /// Returns whether two peaks have mass divided by charge ratios within
+    /// the provided tolerance of each other.
+    ///
+    /// Duplicated peaks usually indicate an upstream bug, and a tolerance of
+    /// zero only detects exactly duplicated mass divided by charge ratios.
+    ///
+    /// # Arguments
+    /// * `tolerance` - The tolerance within which two peaks are considered duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::One,
+    ///    vec![119.0857, 60.5425, 60.5426],
+    ///    vec![3.3E5, 2.4E5, 2.1E4],
+    /// ).unwrap();
+    ///
+    /// assert!(!mascot_generic_format_data.has_duplicate_mz(0.0));
+    /// assert!(mascot_generic_format_data.has_duplicate_mz(0.001));
+    ///
+    /// let mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::One,
+    ///    vec![60.5425, 119.0857, 60.5425],
+    ///    vec![2.4E5, 3.3E5, 2.1E4],
+    /// ).unwrap();
+    ///
+    /// assert!(mascot_generic_format_data.has_duplicate_mz(0.0));
+    /// ```
+    pub fn has_duplicate_mz(&self, tolerance: F) -> bool
+    where
+        F: Sub<F, Output = F>,
+    {
+        let has_close_neighbours = |mass_divided_by_charge_ratios: &[F]| {
+            mass_divided_by_charge_ratios
+                .windows(2)
+                .any(|window| window[1] - window[0] <= tolerance)
+        };
+        if self.is_sorted_by_mz() {
+            return has_close_neighbours(&self.mass_divided_by_charge_ratios);
+        }
+        let mut mass_divided_by_charge_ratios = self.mass_divided_by_charge_ratios.clone();
+        mass_divided_by_charge_ratios
+            .sort_by(|x, y| x.partial_cmp(y).unwrap_or(std::cmp::Ordering::Equal));
+        has_close_neighbours(&mass_divided_by_charge_ratios)
+    }
+
+    /// Maps the fragment intensities of the data through the provided function.
+    ///
+    /// The data is left untouched when any of the mapped intensities is NaN,
+    /// zero or negative.
+    ///
+    /// # Arguments
+    /// * `map` - The function to apply to each intensity.
+    ///
+    /// # Errors
+    /// * If any of the mapped intensities is NaN, zero or negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![60.5425, 119.0857],
+    ///    vec![2.4E5, 3.3E5],
+    /// ).unwrap();
+    ///
+    /// mascot_generic_format_data.map_intensities(|intensity| intensity * 2.0).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format_data.fragment_intensities(), &[4.8E5, 6.6E5]);
+    ///
+    /// assert!(mascot_generic_format_data.map_intensities(|intensity| intensity - 5.0E5).is_err());
+    /// assert_eq!(mascot_generic_format_data.fragment_intensities(), &[4.8E5, 6.6E5]);
+    /// ```
+    pub fn map_intensities<G: Fn(F) -> F>(&mut self, map: G) -> Result<(), String>
+    where
+        F: NaN + StrictlyPositive + Debug,
+    {
+        let fragment_intensities = self
+            .fragment_intensities
+            .iter()
+            .map(|&fragment_intensity| {
+                let mapped_fragment_intensity = map(fragment_intensity);
+                if mapped_fragment_intensity.is_nan()
+                    || !mapped_fragment_intensity.is_strictly_positive()
+                {
+                    return Err(format!(
+                        concat!(
+                            "The intensity {:?} was mapped to {:?}, but the intensities ",
+                            "must be strictly positive values."
+                        ),
+                        fragment_intensity, mapped_fragment_intensity
+                    ));
+                }
+                Ok(mapped_fragment_intensity)
+            })
+            .collect::<Result<Vec<F>, String>>()?;
+        self.fragment_intensities = fragment_intensities;
+        Ok(())
+    }
+
     /// Returns the fragment intensities of the data.
     pub fn fragment_intensities(&self) -> &[F] {
         &self.fragment_intensities
     }
 
     /// Returns iterator over the fragment intensities of the data.
-    pub fn fragment_intensities_iter(&self) -> std::slice::Iter<F> {
+    pub fn fragment_intensities_iter(&self) -> std::slice::Iter<'_, F> {
         self.fragment_intensities.iter()
     }
+
+    /// Returns the number of peaks of the data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![60.5425, 119.0857],
+    ///    vec![2.4E5, 3.3E5],
+    /// ).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format_data.len(), 2);
+    /// assert!(!mascot_generic_format_data.is_empty());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.mass_divided_by_charge_ratios.len()
+    }
+
+    /// Returns whether the data has no peaks.
+    pub fn is_empty(&self) -> bool {
+        self.mass_divided_by_charge_ratios.is_empty()
+    }
+
+    /// Returns the mass divided by charge ratio and the intensity of the peak
+    /// at the provided index, or `None` if the index is out of bounds.
+    ///
+    /// # Arguments
+    /// * `index` - The index of the peak.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![60.5425, 119.0857],
+    ///    vec![2.4E5, 3.3E5],
+    /// ).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format_data.peak(0), Some((60.5425, 2.4E5)));
+    /// assert_eq!(mascot_generic_format_data.peak(1), Some((119.0857, 3.3E5)));
+    /// assert_eq!(mascot_generic_format_data.peak(2), None);
+    /// ```
+    pub fn peak(&self, index: usize) -> Option<(F, F)> {
+        Some((
+            *self.mass_divided_by_charge_ratios.get(index)?,
+            self.fragment_intensities[index],
+        ))
+    }
+}
+
+impl<F: Float> MascotGenericFormatData<F> {
+    /// Returns the peaks grouped into fixed-width m/z bins.
+    ///
+    /// The `k`-th value of the returned dense vector is the sum of the
+    /// intensities of the peaks whose m/z lies in `[k * bin_width, (k + 1) * bin_width)`.
+    /// Intensities falling in the same bin are summed rather than taking their
+    /// maximum, so that the total intensity of the spectrum is preserved. The
+    /// vector has one bin for each bin width up to and including the bin holding
+    /// `max_mz`, and peaks with an m/z larger than `max_mz` are ignored.
+    ///
+    /// # Arguments
+    /// * `bin_width` - The width of each bin.
+    /// * `max_mz` - The maximum m/z to include.
+    ///
+    /// # Errors
+    /// * If `bin_width` is not strictly positive.
+    /// * If `max_mz` is NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![60.2, 60.7, 119.0857, 250.0],
+    ///    vec![1.0, 2.0, 3.0, 4.0],
+    /// ).unwrap();
+    ///
+    /// let bins = mascot_generic_format_data.bin_peaks(1.0, 200.0).unwrap();
+    ///
+    /// assert_eq!(bins.len(), 201);
+    /// assert_eq!(bins[60], 3.0);
+    /// assert_eq!(bins[119], 3.0);
+    /// assert_eq!(bins.iter().sum::<f64>(), 6.0);
+    ///
+    /// assert!(mascot_generic_format_data.bin_peaks(0.0, 200.0).is_err());
+    /// assert!(mascot_generic_format_data.bin_peaks(-1.0, 200.0).is_err());
+    /// assert!(mascot_generic_format_data.bin_peaks(f64::NAN, 200.0).is_err());
+    /// assert!(mascot_generic_format_data.bin_peaks(1.0, f64::NAN).is_err());
+    /// ```
+    pub fn bin_peaks(&self, bin_width: F, max_mz: F) -> Result<Vec<F>, String> {
+        if !bin_width.is_strictly_positive() {
+            return Err(
+                "Could not bin the peaks: the bin width must be strictly positive.".to_string(),
+            );
+        }
+        if max_mz.is_nan() {
+            return Err("Could not bin the peaks: the maximum m/z must not be NaN.".to_string());
+        }
+        let number_of_bins = if max_mz < F::ZERO {
+            0
+        } else {
+            (max_mz / bin_width).to_usize() + 1
+        };
+        let mut bins = vec![F::ZERO; number_of_bins];
+        for (&mass_divided_by_charge_ratio, &fragment_intensity) in self
+            .mass_divided_by_charge_ratios
+            .iter()
+            .zip(self.fragment_intensities.iter())
+        {
+            if mass_divided_by_charge_ratio > max_mz {
+                continue;
+            }
+            let bin = (mass_divided_by_charge_ratio / bin_width).to_usize();
+            if let Some(value) = bins.get_mut(bin) {
+                *value = *value + fragment_intensity;
+            }
+        }
+        Ok(bins)
+    }
+
+    /// Returns the intensities of the peaks resampled onto a regular m/z grid.
+    ///
+    /// The grid points are `start + k * step`, for each `k` such that the grid
+    /// point does not exceed `end`. Each peak is assigned to its nearest grid
+    /// point, and the intensities of the peaks assigned to the same grid point
+    /// are summed. Peaks farther than half a step from every grid point are ignored.
+    /// Differently from [`bin_peaks`](Self::bin_peaks), where each value
+    /// collects the peaks within a bin starting at a multiple of the bin width,
+    /// here each value collects the peaks centered around a grid point.
+    ///
+    /// # Arguments
+    /// * `start` - The m/z of the first grid point.
+    /// * `end` - The maximum m/z of the grid points.
+    /// * `step` - The distance between consecutive grid points.
+    ///
+    /// # Errors
+    /// * If `step` is not strictly positive.
+    /// * If `start` or `end` is NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![101.0, 106.5],
+    ///    vec![2.0, 3.0],
+    /// ).unwrap();
+    ///
+    /// assert_eq!(
+    ///     mascot_generic_format_data.resample(100.0, 110.0, 2.5).unwrap(),
+    ///     vec![2.0, 0.0, 0.0, 3.0, 0.0]
+    /// );
+    /// assert_eq!(
+    ///     mascot_generic_format_data.resample(100.0, 110.0, 10.0).unwrap(),
+    ///     vec![2.0, 3.0]
+    /// );
+    /// // Peaks assigned to the same grid point are summed:
+    /// assert_eq!(mascot_generic_format_data.resample(100.0, 110.0, 20.0).unwrap(), vec![5.0]);
+    /// assert_eq!(
+    ///     mascot_generic_format_data.resample(102.0, 104.0, 1.0).unwrap(),
+    ///     vec![0.0, 0.0, 0.0]
+    /// );
+    /// assert!(mascot_generic_format_data.resample(110.0, 100.0, 1.0).unwrap().is_empty());
+    ///
+    /// assert!(mascot_generic_format_data.resample(100.0, 110.0, 0.0).is_err());
+    /// assert!(mascot_generic_format_data.resample(100.0, 110.0, -1.0).is_err());
+    /// assert!(mascot_generic_format_data.resample(f64::NAN, 110.0, 1.0).is_err());
+    /// assert!(mascot_generic_format_data.resample(100.0, f64::NAN, 1.0).is_err());
+    /// ```
+    pub fn resample(&self, start: F, end: F, step: F) -> Result<Vec<F>, String>
+    where
+        F: From<u8>,
+    {
+        if !step.is_strictly_positive() {
+            return Err(
+                "Could not resample the peaks: the step must be strictly positive.".to_string(),
+            );
+        }
+        if start.is_nan() || end.is_nan() {
+            return Err(
+                "Could not resample the peaks: the start and the end must not be NaN.".to_string(),
+            );
+        }
+        if end < start {
+            return Ok(Vec::new());
+        }
+        let number_of_grid_points = ((end - start) / step).to_usize() + 1;
+        let half_step = step / F::from(2);
+        let mut grid = vec![F::ZERO; number_of_grid_points];
+        for (&mass_divided_by_charge_ratio, &fragment_intensity) in self
+            .mass_divided_by_charge_ratios
+            .iter()
+            .zip(self.fragment_intensities.iter())
+        {
+            let position = (mass_divided_by_charge_ratio - start + half_step) / step;
+            if position < F::ZERO {
+                continue;
+            }
+            if let Some(value) = grid.get_mut(position.to_usize()) {
+                *value = *value + fragment_intensity;
+            }
+        }
+        Ok(grid)
+    }
+
+    /// Removes the isotopic satellites from the spectrum.
+    ///
+    /// A peak is considered an isotopic satellite when a more intense peak
+    /// lies [`ISOTOPE_SPACING`] divided by the charge below its m/z, within
+    /// the provided tolerance, for any charge between one and `max_charge`.
+    /// The remaining peaks keep their relative order, so that a sorted
+    /// spectrum stays sorted.
+    ///
+    /// Returns the number of peaks removed.
+    ///
+    /// # Arguments
+    /// * `tolerance` - The absolute tolerance on the spacing between the peaks.
+    /// * `max_charge` - The maximum charge of the isotopic envelopes to consider.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![60.5425, 119.0857, 120.0881, 150.0],
+    ///    vec![2.4E5, 3.3E5, 4.1E4, 1.2E4],
+    /// ).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format_data.deisotope(0.01, 1), 1);
+    ///
+    /// assert_eq!(mascot_generic_format_data.mass_divided_by_charge_ratios(), &[60.5425, 119.0857, 150.0]);
+    /// assert_eq!(mascot_generic_format_data.fragment_intensities(), &[2.4E5, 3.3E5, 1.2E4]);
+    ///
+    /// // A doubly charged satellite is only removed when the charge is considered.
+    /// let mut mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![119.0857, 119.5869],
+    ///    vec![3.3E5, 4.1E4],
+    /// ).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format_data.deisotope(0.01, 1), 0);
+    /// assert_eq!(mascot_generic_format_data.len(), 2);
+    ///
+    /// assert_eq!(mascot_generic_format_data.deisotope(0.01, 2), 1);
+    /// assert_eq!(mascot_generic_format_data.mass_divided_by_charge_ratios(), &[119.0857]);
+    ///
+    /// // The spacing is the one between the carbon-13 and carbon-12 isotopes.
+    /// let mut mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![119.0857, 119.0857 + ISOTOPE_SPACING],
+    ///    vec![3.3E5, 4.1E4],
+    /// ).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format_data.deisotope(1e-6, 1), 1);
+    /// ```
+    pub fn deisotope(&mut self, tolerance: F, max_charge: u8) -> usize {
+        let spacings = (1..=max_charge)
+            .map(|charge| F::from_f64(ISOTOPE_SPACING / f64::from(charge)))
+            .collect::<Vec<F>>();
+        let is_satellite = |index: usize| {
+            let mass_divided_by_charge_ratio = self.mass_divided_by_charge_ratios[index];
+            let fragment_intensity = self.fragment_intensities[index];
+            spacings.iter().any(|&spacing| {
+                let expected = mass_divided_by_charge_ratio - spacing;
+                self.mass_divided_by_charge_ratios
+                    .iter()
+                    .zip(self.fragment_intensities.iter())
+                    .any(
+                        |(&other_mass_divided_by_charge_ratio, &other_fragment_intensity)| {
+                            other_fragment_intensity > fragment_intensity
+                                && (other_mass_divided_by_charge_ratio - expected).abs()
+                                    <= tolerance
+                        },
+                    )
+            })
+        };
+        let retained = (0..self.len())
+            .map(|index| !is_satellite(index))
+            .collect::<Vec<bool>>();
+
+        self.retain_peaks(&retained)
+    }
+
+    /// Removes the peaks whose intensity is lower than the provided fraction
+    /// of the intensity of the most intense peak.
+    ///
+    /// The most intense peak is always retained, so that the spectrum never
+    /// becomes empty, and the remaining peaks keep their relative order.
+    ///
+    /// Returns the number of peaks removed.
+    ///
+    /// # Arguments
+    /// * `fraction` - The fraction of the largest intensity below which peaks are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![60.5425, 72.6217, 119.0857, 150.0],
+    ///    vec![2.4E5, 2.1E4, 3.3E5, 1.2E4],
+    /// ).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format_data.filter_by_relative_intensity(0.1), 2);
+    /// assert_eq!(mascot_generic_format_data.mass_divided_by_charge_ratios(), &[60.5425, 119.0857]);
+    /// assert_eq!(mascot_generic_format_data.fragment_intensities(), &[2.4E5, 3.3E5]);
+    ///
+    /// assert_eq!(mascot_generic_format_data.filter_by_relative_intensity(2.0), 1);
+    /// assert_eq!(mascot_generic_format_data.fragment_intensities(), &[3.3E5]);
+    /// ```
+    pub fn filter_by_relative_intensity(&mut self, fraction: F) -> usize {
+        let max_fragment_intensity =
+            self.fragment_intensities
+                .iter()
+                .copied()
+                .fold(
+                    F::ZERO,
+                    |max, intensity| if intensity > max { intensity } else { max },
+                );
+        let threshold = fraction * max_fragment_intensity;
+        let retained = self
+            .fragment_intensities
+            .iter()
+            .map(|&intensity| intensity >= threshold || intensity == max_fragment_intensity)
+            .collect::<Vec<bool>>();
+
+        self.retain_peaks(&retained)
+    }
+
+    /// Removes the peaks whose m/z lies outside of `[low, high]`.
+    ///
+    /// The remaining peaks keep their relative order. Returns the number of
+    /// peaks removed.
+    ///
+    /// # Arguments
+    /// * `low` - The lowest m/z to keep.
+    /// * `high` - The highest m/z to keep.
+    ///
+    /// # Errors
+    /// * If no peak lies within the range, in which case the spectrum is left
+    ///   unchanged, as a spectrum cannot be empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![60.5425, 72.6217, 119.0857, 150.0],
+    ///    vec![2.4E5, 2.1E4, 3.3E5, 1.2E4],
+    /// ).unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format_data.trim_to_mz_range(70.0, 119.0857), Ok(2));
+    /// assert_eq!(mascot_generic_format_data.mass_divided_by_charge_ratios(), &[72.6217, 119.0857]);
+    /// assert_eq!(mascot_generic_format_data.fragment_intensities(), &[2.1E4, 3.3E5]);
+    ///
+    /// assert!(mascot_generic_format_data.trim_to_mz_range(200.0, 300.0).is_err());
+    /// assert_eq!(mascot_generic_format_data.len(), 2);
+    /// ```
+    pub fn trim_to_mz_range(&mut self, low: F, high: F) -> Result<usize, String> {
+        let retained = self
+            .mass_divided_by_charge_ratios
+            .iter()
+            .map(|&mass_divided_by_charge_ratio| {
+                mass_divided_by_charge_ratio >= low && mass_divided_by_charge_ratio <= high
+            })
+            .collect::<Vec<bool>>();
+        if !retained.contains(&true) {
+            return Err(
+                "Could not trim the spectrum: no peak lies within the provided m/z range."
+                    .to_string(),
+            );
+        }
+
+        Ok(self.retain_peaks(&retained))
+    }
+
+    /// Returns the intensities of the peaks as a percentage of the intensity
+    /// of the most intense peak, i.e. the base peak.
+    ///
+    /// The base peak has an intensity of exactly 100, and the peaks are not
+    /// modified. When all of the intensities are zero, all of the returned
+    /// values are zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_format_data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![60.5425, 72.6217, 119.0857],
+    ///    vec![2.4E5, 3.3E4, 3.3E5],
+    /// ).unwrap();
+    ///
+    /// let relative_percent = mascot_generic_format_data.as_relative_percent();
+    ///
+    /// assert_eq!(relative_percent[2], 100.0);
+    /// assert_eq!(relative_percent[1], 10.0);
+    /// assert!((relative_percent[0] - 72.7272).abs() < 1e-4);
+    /// assert_eq!(mascot_generic_format_data.fragment_intensities(), &[2.4E5, 3.3E4, 3.3E5]);
+    /// ```
+    pub fn as_relative_percent(&self) -> Vec<F>
+    where
+        F: From<u8>,
+    {
+        let base_peak_intensity =
+            self.fragment_intensities
+                .iter()
+                .copied()
+                .fold(
+                    F::ZERO,
+                    |max, intensity| if intensity > max { intensity } else { max },
+                );
+        if !base_peak_intensity.is_strictly_positive() {
+            return vec![F::ZERO; self.len()];
+        }
+        self.fragment_intensities
+            .iter()
+            .map(|&intensity| intensity / base_peak_intensity * F::from(100))
+            .collect()
+    }
+
+    /// Retains the peaks flagged in the provided mask, keeping the mass
+    /// divided by charge ratios and the intensities aligned.
+    ///
+    /// Returns the number of peaks removed.
+    ///
+    /// # Arguments
+    /// * `retained` - Whether each peak should be retained.
+    fn retain_peaks(&mut self, retained: &[bool]) -> usize {
+        let number_of_peaks = self.len();
+        let mut keep = retained.iter();
+        self.mass_divided_by_charge_ratios
+            .retain(|_| *keep.next().unwrap());
+        let mut keep = retained.iter();
+        self.fragment_intensities.retain(|_| *keep.next().unwrap());
+        number_of_peaks - self.len()
+    }
+
+    /// Returns whether the two spectra are equal within the provided tolerances.
+    ///
+    /// The spectra must have the same level and the same number of peaks, as
+    /// otherwise they are never equal. The mass divided by charge ratios of
+    /// the peaks are compared within an absolute tolerance, while the
+    /// intensities are compared within a tolerance relative to the larger of
+    /// the two intensities.
+    ///
+    /// # Arguments
+    /// * `other` - The other spectrum.
+    /// * `mz_tolerance` - The absolute tolerance on the mass divided by charge ratios.
+    /// * `relative_tolerance` - The relative tolerance on the intensities.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let data: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![60.5425, 119.0857],
+    ///    vec![2.4E5, 3.3E5],
+    /// ).unwrap();
+    /// let other: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![60.54251, 119.0857],
+    ///    vec![2.4E5, 3.30001E5],
+    /// ).unwrap();
+    /// let shorter: MascotGenericFormatData<f64> = MascotGenericFormatData::new(
+    ///    FragmentationSpectraLevel::Two,
+    ///    vec![60.5425],
+    ///    vec![2.4E5],
+    /// ).unwrap();
+    ///
+    /// assert!(data.approx_eq(&other, 1e-4, 1e-5));
+    /// assert!(!data.approx_eq(&other, 1e-6, 1e-5));
+    /// assert!(!data.approx_eq(&other, 1e-4, 1e-6));
+    /// assert!(!data.approx_eq(&shorter, 1e-4, 1e-5));
+    /// ```
+    pub fn approx_eq(&self, other: &Self, mz_tolerance: F, relative_tolerance: F) -> bool {
+        self.level == other.level
+            && self.spec_type == other.spec_type
+            && self.len() == other.len()
+            && self
+                .mass_divided_by_charge_ratios
+                .iter()
+                .zip(other.mass_divided_by_charge_ratios.iter())
+                .all(|(&left, &right)| (left - right).abs() <= mz_tolerance)
+            && self
+                .fragment_intensities
+                .iter()
+                .zip(other.fragment_intensities.iter())
+                .all(|(&left, &right)| {
+                    let largest = if left.abs() > right.abs() {
+                        left.abs()
+                    } else {
+                        right.abs()
+                    };
+                    (left - right).abs() <= relative_tolerance * largest
+                })
+    }
 }
@@ -1,36 +1,111 @@
-use std::{fmt::Debug, str::FromStr};
+use std::{fmt::Debug, ops::Sub, str::FromStr};
 
+use crate::line_parser::{normalize_decimal_comma, normalize_key};
 use crate::prelude::*;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 /// Struct to hold the data of a single scan in a Mascot Generic Format file.
 pub struct MascotGenericFormatDataBuilder<F> {
     level: Option<FragmentationSpectraLevel>,
+    spec_type: Option<String>,
     mass_divided_by_charge_ratios: Vec<F>,
     fragment_intensities: Vec<F>,
+    options: ParseOptions,
+    warnings: Vec<String>,
 }
 
 impl<F> Default for MascotGenericFormatDataBuilder<F> {
     fn default() -> Self {
+        Self::with_options(ParseOptions::default())
+    }
+}
+
+impl<F> MascotGenericFormatDataBuilder<F> {
+    /// Creates a new builder using the provided [`ParseOptions`].
+    ///
+    /// # Arguments
+    /// * `options` - The options to use while parsing.
+    pub fn with_options(options: ParseOptions) -> Self {
         Self {
             level: None,
+            spec_type: None,
             mass_divided_by_charge_ratios: Vec::new(),
             fragment_intensities: Vec::new(),
+            options,
+            warnings: Vec::new(),
         }
     }
+
+    /// Returns the level of the spectrum, if already known.
+    pub fn level(&self) -> Option<FragmentationSpectraLevel> {
+        self.level
+    }
+
+    /// Sets the level of the spectrum, bypassing the parsing of an `MSLEVEL` line.
+    ///
+    /// # Arguments
+    /// * `level` - The level of the spectrum.
+    pub fn set_level(&mut self, level: FragmentationSpectraLevel) {
+        self.level = Some(level);
+    }
+
+    /// Returns the minimum mass divided by charge ratio digested so far, if any.
+    pub fn min_mass_divided_by_charge_ratio(&self) -> Option<F>
+    where
+        F: PartialOrd + Copy,
+    {
+        self.mass_divided_by_charge_ratios
+            .iter()
+            .copied()
+            .reduce(|min, value| if value < min { value } else { min })
+    }
+
+    /// Returns the warnings raised while digesting the peaks.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Resets the builder to its default state, retaining the allocated
+    /// capacity and the parse options.
+    pub fn reset(&mut self) {
+        self.level = None;
+        self.spec_type = None;
+        self.mass_divided_by_charge_ratios.clear();
+        self.fragment_intensities.clear();
+        self.warnings.clear();
+    }
 }
 
-impl<F: PartialEq + PartialOrd + Copy + Debug> MascotGenericFormatDataBuilder<F> {
+impl<F: PartialEq + PartialOrd + Copy + Debug + Sub<F, Output = F> + Zero>
+    MascotGenericFormatDataBuilder<F>
+{
     pub fn build(self) -> Result<MascotGenericFormatData<F>, String> {
-        MascotGenericFormatData::new(
+        let mut data = MascotGenericFormatData::new(
             self.level.ok_or_else(|| {
                 "Could not build MascotGenericFormatData: level is missing".to_string()
             })?,
             self.mass_divided_by_charge_ratios,
             self.fragment_intensities,
-        )
+        )?
+        .with_spec_type(self.spec_type);
+
+        // When the parse options allow it, the peaks that were digested
+        // out of order are sorted rather than raising an error.
+        if !self.warnings.is_empty() {
+            data.sort_by_mz();
+        }
+
+        if self.options.rejects_duplicate_mz() && data.has_duplicate_mz(F::ZERO) {
+            return Err(
+                "Could not build MascotGenericFormatData: the spectrum contains duplicated mass divided by charge ratios.".to_string(),
+            );
+        }
+
+        Ok(data)
     }
+}
 
+impl<F: PartialEq + PartialOrd + Copy + Debug> MascotGenericFormatDataBuilder<F> {
     /// Returns whether the level is equal to two.
     ///
     /// # Raises
@@ -44,9 +119,36 @@ impl<F: PartialEq + PartialOrd + Copy + Debug> MascotGenericFormatDataBuilder<F>
     }
 }
 
+impl<F: FromStr> MascotGenericFormatDataBuilder<F> {
+    /// Returns whether the line has the shape of a peak line, that is
+    /// `mz intensity` optionally followed by the charge of the fragment.
+    ///
+    /// Lines containing a `=` are never peak lines, as they hold metadata.
+    ///
+    /// # Arguments
+    /// * `line` - The line to check.
+    fn is_peak_line(line: &str) -> bool {
+        if line.contains('=') {
+            return false;
+        }
+        let mut split = line.split(' ');
+        let (Some(mass_divided_by_charge_ratio), Some(fragment_intensity)) =
+            (split.next(), split.next())
+        else {
+            return false;
+        };
+        mass_divided_by_charge_ratio.parse::<F>().is_ok()
+            && fragment_intensity.parse::<F>().is_ok()
+            && split.next().map_or(true, |charge| {
+                Charge::from_str(&format!("CHARGE={}", charge)).is_ok()
+            })
+            && split.next().is_none()
+    }
+}
+
 impl<F> LineParser for MascotGenericFormatDataBuilder<F>
 where
-    F: FromStr + NaN + StrictlyPositive + PartialOrd + Debug + Copy,
+    F: FromStr + NaN + StrictlyPositive + PartialOrd + Debug + Copy + Zero,
 {
     /// Returns whether the line can be parsed by this parser.
     ///
@@ -85,12 +187,31 @@ where
     ///     assert!(MascotGenericFormatDataBuilder::<f64>::can_parse_line(line));
     /// }
     ///
+    /// // Peak lines may carry an optional fragment charge column:
+    /// assert!(MascotGenericFormatDataBuilder::<f64>::can_parse_line("119.0857 3.3E5 1"));
+    /// assert!(MascotGenericFormatDataBuilder::<f64>::can_parse_line("119.0857 3.3E5 2+"));
+    ///
+    /// // While anything not shaped as `mz intensity [charge]` is not a peak:
+    /// for line in [
+    ///     "119.0857",
+    ///     "119.0857 3.3E5 7",
+    ///     "119.0857 3.3E5 1 1",
+    ///     "119.0857  3.3E5",
+    ///     "RTINSECONDS=37.083",
+    ///     "RTINSECONDS=37.083 1",
+    ///     "SOURCE_INSTRUMENT=LC-ESI-QTOF",
+    ///     "SOURCE_INSTRUMENT=LC-ESI 1.0 2.0",
+    /// ] {
+    ///     assert!(!MascotGenericFormatDataBuilder::<f64>::can_parse_line(line), "{}", line);
+    /// }
     /// ```
     ///
     fn can_parse_line(line: &str) -> bool {
-        line.starts_with("MSLEVEL=")
-            || line.starts_with("SPECTYPE=CORRELATED MS")
-            || line.contains(' ') && line.split(' ').all(|s| s.parse::<F>().is_ok())
+        let line = normalize_key(line);
+        let line = line.as_ref();
+        FragmentationSpectraLevel::strip_key(line).is_some()
+            || line.starts_with("SPECTYPE=")
+            || Self::is_peak_line(line)
     }
 
     /// Returns whether the builder can be built.
@@ -102,6 +223,10 @@ where
 
     /// Parses the line and updates the builder.
     ///
+    /// Peak lines may carry an optional third column with the charge of the
+    /// fragment, such as `119.0857 3.3E5 1`: the charge is validated and then
+    /// ignored, as it is not stored in the [`MascotGenericFormatData`].
+    ///
     /// # Arguments
     /// * `line` - The line to parse.
     ///
@@ -133,96 +258,201 @@ where
     /// assert_eq!(mascot_generic_format_data.mass_divided_by_charge_ratios(), &[60.5425, 119.0857]);
     /// assert_eq!(mascot_generic_format_data.fragment_intensities(), &[2.4E5, 3.3E5]);
     ///
+    /// let mut parser = MascotGenericFormatDataBuilder::<f64>::default();
+    ///
+    /// parser.digest_line("MSLEVEL=2").unwrap();
+    /// parser.digest_line("60.5425 2.4E5 1").unwrap();
+    /// parser.digest_line("119.0857 3.3E5 2+").unwrap();
+    /// assert!(parser.digest_line("150.0 1.0E5 7").is_err());
+    /// assert!(parser.digest_line("150.0 1.0E5 1 1").is_err());
+    ///
+    /// let mascot_generic_format_data = parser.build().unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format_data.mass_divided_by_charge_ratios(), &[60.5425, 119.0857]);
+    /// assert_eq!(mascot_generic_format_data.fragment_intensities(), &[2.4E5, 3.3E5]);
+    ///
     /// ```
     ///
     fn digest_line(&mut self, line: &str) -> Result<(), String> {
-        if line.starts_with("MSLEVEL=") {
+        let line = normalize_key(line);
+        let line = line.as_ref();
+        if FragmentationSpectraLevel::strip_key(line).is_some() {
             self.level = Some(FragmentationSpectraLevel::from_str(line)?);
             return Ok(());
         }
 
-        // If we encounter a SPECTYPE line, the MSLEVEL must have already been parsed
-        // and it must be equal to 1:
-        if line.starts_with("SPECTYPE=CORRELATED MS") {
+        // The spectrum type, such as `CORRELATED MS`, is stored as it is.
+        if let Some(spec_type) = line.strip_prefix("SPECTYPE=") {
+            self.spec_type = Some(spec_type.trim().to_string());
             return Ok(());
         }
 
         let mut split = line.split(' ');
+        let parse_value = |value: &str| {
+            if self.options.uses_european_decimals() {
+                normalize_decimal_comma(value).parse::<F>()
+            } else {
+                value.parse::<F>()
+            }
+        };
 
         // We obtain the mass divided by change value:
-        let mass_divided_by_charge_ratio = split
-            .next()
-            .ok_or_else(|| "Could not parse mass divided by charge ratio".to_string())?
-            .parse::<F>()
-            .map_err(|_| "Could not parse mass divided by charge ratio".to_string())?;
+        let mass_divided_by_charge_ratio = parse_value(
+            split
+                .next()
+                .ok_or_else(|| "Could not parse mass divided by charge ratio".to_string())?,
+        )
+        .map_err(|_| "Could not parse mass divided by charge ratio".to_string())?;
 
         // We obtain the fragment intensity:
-        let fragment_intensity = split
-            .next()
-            .ok_or_else(|| "Could not parse fragment intensity".to_string())?
-            .parse::<F>()
-            .map_err(|_| "Could not parse fragment intensity".to_string())?;
+        let fragment_intensity = parse_value(
+            split
+                .next()
+                .ok_or_else(|| "Could not parse fragment intensity".to_string())?,
+        )
+        .map_err(|_| "Could not parse fragment intensity".to_string())?;
 
-        if mass_divided_by_charge_ratio.is_nan() {
+        // Some exporters write a third column with the charge of the fragment,
+        // which we validate but do not store:
+        if let Some(charge) = split.next() {
+            Charge::from_str(&format!("CHARGE={}", charge)).map_err(|_| {
+                format!(
+                    "Could not parse the fragment charge \"{}\" in the line \"{}\".",
+                    charge, line
+                )
+            })?;
+        }
+
+        if split.next().is_some() {
             return Err(format!(
                 concat!(
-                    "The mass divided by charge ratio provided in the ",
-                    "line \"{}\" was interpreted as a NaN."
+                    "The peak line \"{}\" has more than three columns, while ",
+                    "only the mass divided by charge ratio, the fragment intensity ",
+                    "and optionally the fragment charge are expected."
                 ),
                 line
             ));
         }
 
+        self.push_peak(mass_divided_by_charge_ratio, fragment_intensity)
+            .map_err(|error| format!("Could not digest the line \"{}\": {}", line, error))
+    }
+}
+
+impl<F> MascotGenericFormatDataBuilder<F>
+where
+    F: NaN + StrictlyPositive + PartialOrd + Debug + Copy + Zero,
+{
+    /// Adds a peak to the builder, bypassing the parsing of a line.
+    ///
+    /// The peak is validated as if it were digested from a line.
+    ///
+    /// # Arguments
+    /// * `mass_divided_by_charge_ratio` - The mass divided by charge ratio of the peak.
+    /// * `fragment_intensity` - The intensity of the peak.
+    ///
+    /// # Errors
+    /// * If the level has not been set.
+    /// * If either value is NaN, zero or negative, except for zero intensities
+    ///   when the parse options allow zero values.
+    /// * If the peaks of a level-two spectrum are not provided in ascending order,
+    ///   unless the parse options allow to sort them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut parser = MascotGenericFormatDataBuilder::<f64>::default();
+    ///
+    /// assert!(parser.push_peak(60.5425, 2.4E5).is_err());
+    ///
+    /// parser.set_level(FragmentationSpectraLevel::Two);
+    /// parser.push_peak(60.5425, 2.4E5).unwrap();
+    /// assert!(parser.push_peak(f64::NAN, 2.4E5).is_err());
+    /// assert!(parser.push_peak(119.0857, -1.0).is_err());
+    /// assert!(parser.push_peak(50.0, 1.0).is_err());
+    /// parser.push_peak(119.0857, 3.3E5).unwrap();
+    ///
+    /// let mascot_generic_format_data = parser.build().unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format_data.mass_divided_by_charge_ratios(), &[60.5425, 119.0857]);
+    /// ```
+    pub fn push_peak(
+        &mut self,
+        mass_divided_by_charge_ratio: F,
+        fragment_intensity: F,
+    ) -> Result<(), String> {
+        // The level must be known to validate the peak:
+        let is_level_two = self.is_level_two()?;
+
+        if mass_divided_by_charge_ratio.is_nan() {
+            return Err(format!(
+                "The mass divided by charge ratio {:?} was interpreted as a NaN.",
+                mass_divided_by_charge_ratio
+            ));
+        }
+
         if !mass_divided_by_charge_ratio.is_strictly_positive() {
             return Err(format!(
                 concat!(
-                    "The provided line \"{}\" contains a mass divided by charge ratio ",
-                    "that has been interpreted as a zero or negative value. ",
+                    "The mass divided by charge ratio {:?} is a zero or negative value. ",
                     "The mass divided by charge ratio must be a strictly positive value."
                 ),
-                line
+                mass_divided_by_charge_ratio
             ));
         }
 
         if fragment_intensity.is_nan() {
             return Err(format!(
-                concat!(
-                    "The fragment intensity provided in the ",
-                    "line \"{}\" was interpreted as a NaN."
-                ),
-                line
+                "The fragment intensity {:?} was interpreted as a NaN.",
+                fragment_intensity
             ));
         }
 
-        if !fragment_intensity.is_strictly_positive() {
+        if !(fragment_intensity.is_strictly_positive()
+            || self.options.allows_zero_values() && fragment_intensity == F::ZERO)
+        {
             return Err(format!(
                 concat!(
-                    "The provided line \"{}\" contains a fragment intensity ",
-                    "that has been interpreted as a zero or negative value. ",
-                    "The fragment intensity must be a strictly positive value."
+                    "The fragment intensity {:?} is a zero or negative value. ",
+                    "The fragment intensity must be a strictly positive value, ",
+                    "or zero when the parse options allow zero values."
                 ),
-                line
+                fragment_intensity
             ));
         }
 
         // We check that the value of the mass divided by charge ratio is larger
-        // or equal to the previous value:
+        // or equal to the previous value. Level-one spectra are only checked
+        // when explicitly requested by the parse options:
         if let Some(previous_mass_divided_by_charge_ratio) =
             self.mass_divided_by_charge_ratios.last()
         {
-            if self.is_level_two()?
+            if (is_level_two || self.options.requires_sorted_level_one())
                 && *previous_mass_divided_by_charge_ratio > mass_divided_by_charge_ratio
             {
-                return Err(format!(
-                    concat!(
-                        "The mass divided by charge ratio provided in the ",
-                        "line \"{}\" was smaller than the previous value. ",
-                        "The mass divided by charge ratio must be provided in ",
-                        "ascending order. The current value is {:?}, while the ",
-                        "previous value was {:?}."
-                    ),
-                    line, mass_divided_by_charge_ratio, previous_mass_divided_by_charge_ratio
-                ));
+                if self.options.sorts_unsorted_peaks() {
+                    if self.warnings.is_empty() {
+                        self.warnings.push(format!(
+                            concat!(
+                                "The mass divided by charge ratio {:?} was smaller than ",
+                                "the previous value {:?}, so the peaks of the spectrum ",
+                                "have been sorted by mass divided by charge ratio."
+                            ),
+                            mass_divided_by_charge_ratio, previous_mass_divided_by_charge_ratio
+                        ));
+                    }
+                } else {
+                    return Err(format!(
+                        concat!(
+                            "The mass divided by charge ratio {:?} was smaller than ",
+                            "the previous value {:?}. The mass divided by charge ratio ",
+                            "must be provided in ascending order."
+                        ),
+                        mass_divided_by_charge_ratio, previous_mass_divided_by_charge_ratio
+                    ));
+                }
             }
         }
 
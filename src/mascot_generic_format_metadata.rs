@@ -1,19 +1,62 @@
-use std::{fmt::Debug, ops::Add};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    ops::{Add, Div},
+};
 
 use crate::prelude::*;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// The metadata of an MGF entry.
+///
+/// Equality compares all of the fields, including the optional ones and the
+/// extra metadata, and floating point values are compared exactly. The order
+/// in which the fields appeared in the document, recorded by
+/// [`ParseOptions::with_preserve_field_order`], is not compared. Hashing
+/// only uses the feature ID, which is consistent with equality.
 pub struct MascotGenericFormatMetadata<I, F> {
     feature_id: I,
     parent_ion_mass: F,
     retention_time: F,
     charge: Charge,
+    alternative_charges: Vec<Charge>,
+    scan_number: Option<I>,
     merged_scans_metadata: Option<MergeScansMetadata<I>>,
     filename: Option<String>,
+    ion_mode: Option<IonMode>,
+    extra: BTreeMap<String, String>,
+    sentinel_fields: BTreeSet<String>,
+    raw_fields: Vec<Vec<(String, String)>>,
 }
 
-impl<I: Copy + Add<Output = I> + Eq + Debug + Copy + Zero, F: StrictlyPositive + Copy>
-    MascotGenericFormatMetadata<I, F>
+impl<I: PartialEq, F: PartialEq> PartialEq for MascotGenericFormatMetadata<I, F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.feature_id == other.feature_id
+            && self.parent_ion_mass == other.parent_ion_mass
+            && self.retention_time == other.retention_time
+            && self.charge == other.charge
+            && self.alternative_charges == other.alternative_charges
+            && self.scan_number == other.scan_number
+            && self.merged_scans_metadata == other.merged_scans_metadata
+            && self.filename == other.filename
+            && self.ion_mode == other.ion_mode
+            && self.extra == other.extra
+            && self.sentinel_fields == other.sentinel_fields
+    }
+}
+
+impl<I: Hash, F> Hash for MascotGenericFormatMetadata<I, F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.feature_id.hash(state);
+    }
+}
+
+impl<
+        I: Copy + Add<Output = I> + Eq + Debug + Copy + Zero,
+        F: StrictlyPositive + Copy + PartialEq + Zero,
+    > MascotGenericFormatMetadata<I, F>
 {
     /// Creates a new [`MascotGenericFormatMetadata`].
     ///
@@ -30,6 +73,7 @@ impl<I: Copy + Add<Output = I> + Eq + Debug + Copy + Zero, F: StrictlyPositive +
     /// # Errors
     /// * If `parent_ion_mass` is not strictly positive.
     /// * If `retention_time` is not strictly positive.
+    /// * If `charge` is zero.
     /// * If `filename` is empty.
     ///
     /// # Examples
@@ -84,6 +128,28 @@ impl<I: Copy + Add<Output = I> + Eq + Debug + Copy + Zero, F: StrictlyPositive +
     ///     MascotGenericFormatMetadata::new(
     ///         feature_id,
     ///         parent_ion_mass,
+    ///         0.0,
+    ///         charge,
+    ///         None,
+    ///         filename.clone(),
+    ///     ).is_err()
+    /// );
+    ///
+    /// assert!(
+    ///     MascotGenericFormatMetadata::new(
+    ///         feature_id,
+    ///         parent_ion_mass,
+    ///         retention_time,
+    ///         Charge::Zero,
+    ///         None,
+    ///         filename.clone(),
+    ///     ).is_err()
+    /// );
+    ///
+    /// assert!(
+    ///     MascotGenericFormatMetadata::new(
+    ///         feature_id,
+    ///         parent_ion_mass,
     ///         retention_time,
     ///         charge,
     ///         None,
@@ -109,6 +175,46 @@ impl<I: Copy + Add<Output = I> + Eq + Debug + Copy + Zero, F: StrictlyPositive +
             return Err("Could not create MascotGenericFormatMetadata: retention_time must be strictly positive".to_string());
         }
 
+        Self::new_allowing_zero_retention_time(
+            feature_id,
+            parent_ion_mass,
+            retention_time,
+            charge,
+            merged_scans_metadata,
+            filename,
+        )
+    }
+
+    /// Creates a new [`MascotGenericFormatMetadata`], accepting a zero retention time.
+    ///
+    /// This is used by the builders, which decide whether a zero retention time
+    /// is acceptable according to their parse options, or default a missing
+    /// retention time to zero, as for spectral library entries.
+    pub(crate) fn new_allowing_zero_retention_time(
+        feature_id: I,
+        parent_ion_mass: F,
+        retention_time: F,
+        charge: Charge,
+        merged_scans_metadata: Option<MergeScansMetadata<I>>,
+        filename: Option<String>,
+    ) -> Result<Self, String> {
+        if !parent_ion_mass.is_strictly_positive() {
+            return Err("Could not create MascotGenericFormatMetadata: parent_ion_mass must be strictly positive".to_string());
+        }
+
+        if !retention_time.is_strictly_positive() && retention_time != F::ZERO {
+            return Err(
+                "Could not create MascotGenericFormatMetadata: retention_time must not be negative"
+                    .to_string(),
+            );
+        }
+
+        if charge.is_zero() {
+            return Err(
+                "Could not create MascotGenericFormatMetadata: charge must not be zero".to_string(),
+            );
+        }
+
         if let Some(filename) = &filename {
             if filename.is_empty() {
                 return Err(
@@ -123,10 +229,79 @@ impl<I: Copy + Add<Output = I> + Eq + Debug + Copy + Zero, F: StrictlyPositive +
             parent_ion_mass,
             retention_time,
             charge,
+            alternative_charges: Vec::new(),
+            scan_number: None,
             merged_scans_metadata,
             filename,
+            ion_mode: None,
+            extra: BTreeMap::new(),
+            sentinel_fields: BTreeSet::new(),
+            raw_fields: Vec::new(),
         })
     }
+}
+
+impl<I: Copy + Add<Output = I> + Eq + Debug + Copy + Zero, F: StrictlyPositive + Copy>
+    MascotGenericFormatMetadata<I, F>
+{
+    /// Sets the extra metadata, i.e. the `KEY=VALUE` lines that are not
+    /// otherwise recognized by the parser.
+    ///
+    /// # Arguments
+    /// * `extra` - The extra metadata.
+    pub fn with_extra(mut self, extra: BTreeMap<String, String>) -> Self {
+        self.extra = extra;
+        self
+    }
+
+    /// Sets the keys of the optional fields that were present in the document
+    /// with a placeholder value, such as `N/A`, and were therefore ignored.
+    ///
+    /// # Arguments
+    /// * `sentinel_fields` - The keys of the fields holding a placeholder value.
+    pub fn with_sentinel_fields(mut self, sentinel_fields: BTreeSet<String>) -> Self {
+        self.sentinel_fields = sentinel_fields;
+        self
+    }
+
+    /// Sets the `KEY=VALUE` lines of each section of the entry, in the order
+    /// they appeared in the document.
+    ///
+    /// # Arguments
+    /// * `raw_fields` - The keys and values of the lines of each section.
+    pub fn with_raw_fields(mut self, raw_fields: Vec<Vec<(String, String)>>) -> Self {
+        self.raw_fields = raw_fields;
+        self
+    }
+
+    /// Sets the candidate charges listed after the charge of the metadata,
+    /// such as the `2+` of `CHARGE=1+ and 2+`.
+    ///
+    /// # Arguments
+    /// * `alternative_charges` - The alternative candidate charges.
+    pub fn with_alternative_charges(mut self, alternative_charges: Vec<Charge>) -> Self {
+        self.alternative_charges = alternative_charges;
+        self
+    }
+
+    /// Sets the scan number provided by the `SCANS` field, when it differs
+    /// from the feature ID.
+    ///
+    /// # Arguments
+    /// * `scan_number` - The scan number of the metadata.
+    pub fn with_scan_number(mut self, scan_number: Option<I>) -> Self {
+        self.scan_number = scan_number;
+        self
+    }
+
+    /// Sets the ion mode explicitly provided by the `IONMODE` field.
+    ///
+    /// # Arguments
+    /// * `ion_mode` - The ion mode of the metadata.
+    pub fn with_ion_mode(mut self, ion_mode: Option<IonMode>) -> Self {
+        self.ion_mode = ion_mode;
+        self
+    }
 
     /// Returns the feature ID of the metadata.
     pub fn feature_id(&self) -> I {
@@ -138,21 +313,288 @@ impl<I: Copy + Add<Output = I> + Eq + Debug + Copy + Zero, F: StrictlyPositive +
         self.parent_ion_mass
     }
 
-    /// Returns the retention time of the metadata.
+    /// Returns the retention time of the metadata, in seconds.
     pub fn retention_time(&self) -> F {
         self.retention_time
     }
 
+    /// Returns the retention time of the metadata, in minutes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let metadata: MascotGenericFormatMetadata<usize, f64> =
+    ///     MascotGenericFormatMetadata::new(1, 381.0795, 90.0, Charge::One, None, None).unwrap();
+    ///
+    /// assert_eq!(metadata.retention_time_minutes(), 1.5);
+    /// ```
+    pub fn retention_time_minutes(&self) -> F
+    where
+        F: Div<F, Output = F> + From<u8>,
+    {
+        self.retention_time / F::from(60)
+    }
+
+    /// Sets the retention time of the metadata, in seconds.
+    ///
+    /// # Arguments
+    /// * `retention_time` - The new retention time.
+    ///
+    /// # Errors
+    /// * If `retention_time` is not strictly positive, in which case the metadata is left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut metadata: MascotGenericFormatMetadata<usize, f64> =
+    ///     MascotGenericFormatMetadata::new(1, 381.0795, 90.0, Charge::One, None, None).unwrap();
+    ///
+    /// metadata.set_retention_time(45.0).unwrap();
+    /// assert_eq!(metadata.retention_time(), 45.0);
+    ///
+    /// assert!(metadata.set_retention_time(0.0).is_err());
+    /// assert!(metadata.set_retention_time(f64::NAN).is_err());
+    /// assert_eq!(metadata.retention_time(), 45.0);
+    /// ```
+    pub fn set_retention_time(&mut self, retention_time: F) -> Result<(), String> {
+        if !retention_time.is_strictly_positive() {
+            return Err(
+                "Could not set the retention time: retention_time must be strictly positive"
+                    .to_string(),
+            );
+        }
+        self.retention_time = retention_time;
+        Ok(())
+    }
+
+    /// Maps the retention time of the metadata through the provided function,
+    /// such as a linear correction aligning different runs.
+    ///
+    /// # Arguments
+    /// * `map` - The function to apply to the retention time.
+    ///
+    /// # Errors
+    /// * If the mapped retention time is not strictly positive, in which case the metadata is left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut metadata: MascotGenericFormatMetadata<usize, f64> =
+    ///     MascotGenericFormatMetadata::new(1, 381.0795, 90.0, Charge::One, None, None).unwrap();
+    ///
+    /// metadata.map_retention_time(|retention_time| 1.5 * retention_time + 2.0).unwrap();
+    /// assert_eq!(metadata.retention_time(), 137.0);
+    ///
+    /// assert!(metadata.map_retention_time(|retention_time| retention_time - 200.0).is_err());
+    /// assert_eq!(metadata.retention_time(), 137.0);
+    /// ```
+    pub fn map_retention_time<G: Fn(F) -> F>(&mut self, map: G) -> Result<(), String> {
+        self.set_retention_time(map(self.retention_time))
+    }
+
     /// Returns the charge of the metadata.
     pub fn charge(&self) -> Charge {
         self.charge
     }
 
+    /// Returns the alternative candidate charges, when the document listed an
+    /// ambiguous charge such as `CHARGE=1+ and 2+`.
+    ///
+    /// The candidate with the lowest magnitude is the [`charge`](Self::charge)
+    /// of the metadata, and the slice holds the remaining candidates in the
+    /// order they were listed. It is empty when a single charge was provided.
+    pub fn alternative_charges(&self) -> &[Charge] {
+        &self.alternative_charges
+    }
+
+    /// Returns the scan number provided by the `SCANS` field, if it differs
+    /// from the feature ID.
+    ///
+    /// Scan numbers independent of the feature IDs are only accepted with
+    /// [`ParseOptions::with_allow_independent_scan_numbers`], which the lenient
+    /// parse options enable, and the `SCANS` field must otherwise be either
+    /// `-1` or equal to the feature ID.
+    pub fn scan_number(&self) -> Option<I>
+    where
+        I: Copy,
+    {
+        self.scan_number
+    }
+
+    /// Returns the ion mode explicitly provided by the `IONMODE` field, if any.
+    pub fn ion_mode(&self) -> Option<IonMode> {
+        self.ion_mode
+    }
+
+    /// Returns the ion mode of the metadata, inferring it from the
+    /// charge when the `IONMODE` field is absent.
+    ///
+    /// The charge only implies a polarity when it has an explicit sign,
+    /// so `None` is returned for charges such as `CHARGE=1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let metadata: MascotGenericFormatMetadata<usize, f64> = MascotGenericFormatMetadata::new(
+    ///     1,
+    ///     381.0795,
+    ///     37.083,
+    ///     Charge::ThreeMinus,
+    ///     None,
+    ///     None,
+    /// ).unwrap();
+    ///
+    /// assert_eq!(metadata.ion_mode(), None);
+    /// assert_eq!(metadata.effective_ion_mode(), Some(IonMode::Negative));
+    ///
+    /// let metadata = metadata.with_ion_mode(Some(IonMode::Positive));
+    ///
+    /// assert_eq!(metadata.effective_ion_mode(), Some(IonMode::Positive));
+    ///
+    /// let metadata: MascotGenericFormatMetadata<usize, f64> = MascotGenericFormatMetadata::new(
+    ///     1,
+    ///     381.0795,
+    ///     37.083,
+    ///     Charge::One,
+    ///     None,
+    ///     None,
+    /// ).unwrap();
+    ///
+    /// assert_eq!(metadata.effective_ion_mode(), None);
+    /// ```
+    pub fn effective_ion_mode(&self) -> Option<IonMode> {
+        self.ion_mode.or_else(|| IonMode::from_charge(self.charge))
+    }
+
+    /// Returns whether the ion mode provided by the `IONMODE` field agrees
+    /// with the sign of the charge.
+    ///
+    /// `None` is returned when the `IONMODE` field is absent, or when the
+    /// charge has no explicit sign, as the two cannot then be compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let metadata: MascotGenericFormatMetadata<usize, f64> = MascotGenericFormatMetadata::new(
+    ///     1,
+    ///     381.0795,
+    ///     37.083,
+    ///     Charge::OneMinus,
+    ///     None,
+    ///     None,
+    /// ).unwrap();
+    ///
+    /// assert_eq!(metadata.ion_mode_consistent(), None);
+    /// assert_eq!(metadata.clone().with_ion_mode(Some(IonMode::Negative)).ion_mode_consistent(), Some(true));
+    /// assert_eq!(metadata.with_ion_mode(Some(IonMode::Positive)).ion_mode_consistent(), Some(false));
+    /// ```
+    pub fn ion_mode_consistent(&self) -> Option<bool> {
+        self.ion_mode
+            .zip(IonMode::from_charge(self.charge))
+            .map(|(ion_mode, charge_ion_mode)| ion_mode == charge_ion_mode)
+    }
+
     /// Returns the filename of the metadata.
     pub fn filename(&self) -> Option<&str> {
         self.filename.as_deref()
     }
 
+    /// Returns the metadata of the merged scans, if the spectrum was merged.
+    pub fn merged_scans_metadata(&self) -> Option<&MergeScansMetadata<I>> {
+        self.merged_scans_metadata.as_ref()
+    }
+
+    /// Returns the extra metadata collected while parsing in lenient mode.
+    pub fn extra(&self) -> &BTreeMap<String, String> {
+        &self.extra
+    }
+
+    /// Returns which of the optional fields of the metadata were provided.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let metadata: MascotGenericFormatMetadata<usize, f64> = MascotGenericFormatMetadata::new(
+    ///     1,
+    ///     381.0795,
+    ///     37.083,
+    ///     Charge::One,
+    ///     None,
+    ///     None,
+    /// ).unwrap();
+    ///
+    /// assert_eq!(metadata.field_presence(), FieldPresence::default());
+    ///
+    /// let metadata = metadata.with_extra(BTreeMap::from([
+    ///     ("INCHI".to_string(), "InChI=1S/CH4/h1H4".to_string()),
+    ///     ("PUBMED".to_string(), "297".to_string()),
+    /// ]));
+    ///
+    /// assert!(!metadata.has_smiles());
+    /// assert!(metadata.has_structure());
+    /// assert!(metadata.has_reference());
+    /// ```
+    pub fn field_presence(&self) -> FieldPresence {
+        FieldPresence::new(
+            self.extra.contains_key("SMILES"),
+            self.extra.contains_key("INCHI"),
+            self.extra.contains_key("PUBMED"),
+            self.filename.is_some(),
+            self.ion_mode.is_some(),
+            self.merged_scans_metadata.is_some(),
+        )
+    }
+
+    /// Returns whether the `SMILES` field was provided.
+    pub fn has_smiles(&self) -> bool {
+        self.field_presence().has_smiles()
+    }
+
+    /// Returns whether a structure was provided, either as a `SMILES` or as an `INCHI`.
+    pub fn has_structure(&self) -> bool {
+        self.field_presence().has_structure()
+    }
+
+    /// Returns whether a bibliographic reference was provided, i.e. a `PUBMED` identifier.
+    pub fn has_reference(&self) -> bool {
+        self.field_presence().has_reference()
+    }
+
+    /// Returns the keys of the optional fields that were present in the document
+    /// with a placeholder value, such as `N/A`, `*..*` or an empty value.
+    pub fn sentinel_fields(&self) -> &BTreeSet<String> {
+        &self.sentinel_fields
+    }
+
+    /// Returns the `KEY=VALUE` lines of each section of the entry, in the
+    /// order they appeared in the document.
+    ///
+    /// The lines are only recorded when the entry was parsed with
+    /// [`ParseOptions::with_preserve_field_order`], and the slice is
+    /// otherwise empty.
+    pub fn raw_fields(&self) -> &[Vec<(String, String)>] {
+        &self.raw_fields
+    }
+
+    /// Returns whether any optional field was present in the document with a
+    /// placeholder value, as opposed to being omitted altogether.
+    pub fn had_sentinel_fields(&self) -> bool {
+        !self.sentinel_fields.is_empty()
+    }
+
     /// Returns the number of scans removed due to low quality.
     pub fn number_of_scans_removed_due_to_low_quality(&self) -> I {
         self.merged_scans_metadata
@@ -161,3 +603,53 @@ impl<I: Copy + Add<Output = I> + Eq + Debug + Copy + Zero, F: StrictlyPositive +
             .unwrap_or(I::ZERO)
     }
 }
+
+impl<I: PartialEq, F: Float> MascotGenericFormatMetadata<I, F> {
+    /// Returns whether the two metadata are equal within the provided tolerances.
+    ///
+    /// The parent ion masses are compared within an absolute tolerance and
+    /// the retention times within a relative tolerance, while all of the other
+    /// fields are compared exactly.
+    ///
+    /// # Arguments
+    /// * `other` - The other metadata.
+    /// * `mz_tolerance` - The absolute tolerance on the parent ion mass.
+    /// * `relative_tolerance` - The relative tolerance on the retention time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let metadata: MascotGenericFormatMetadata<usize, f64> =
+    ///     MascotGenericFormatMetadata::new(1, 381.0795, 37.083, Charge::One, None, None).unwrap();
+    /// let other: MascotGenericFormatMetadata<usize, f64> =
+    ///     MascotGenericFormatMetadata::new(1, 381.07951, 37.0830001, Charge::One, None, None).unwrap();
+    /// let different_charge: MascotGenericFormatMetadata<usize, f64> =
+    ///     MascotGenericFormatMetadata::new(1, 381.0795, 37.083, Charge::Two, None, None).unwrap();
+    ///
+    /// assert!(metadata.approx_eq(&other, 1e-4, 1e-6));
+    /// assert!(!metadata.approx_eq(&other, 1e-6, 1e-6));
+    /// assert!(!metadata.approx_eq(&other, 1e-4, 1e-10));
+    /// assert!(!metadata.approx_eq(&different_charge, 1e-4, 1e-6));
+    /// ```
+    pub fn approx_eq(&self, other: &Self, mz_tolerance: F, relative_tolerance: F) -> bool {
+        let largest_retention_time = if self.retention_time > other.retention_time {
+            self.retention_time
+        } else {
+            other.retention_time
+        };
+        self.feature_id == other.feature_id
+            && (self.parent_ion_mass - other.parent_ion_mass).abs() <= mz_tolerance
+            && (self.retention_time - other.retention_time).abs()
+                <= relative_tolerance * largest_retention_time
+            && self.charge == other.charge
+            && self.alternative_charges == other.alternative_charges
+            && self.scan_number == other.scan_number
+            && self.merged_scans_metadata == other.merged_scans_metadata
+            && self.filename == other.filename
+            && self.ion_mode == other.ion_mode
+            && self.extra == other.extra
+            && self.sentinel_fields == other.sentinel_fields
+    }
+}
@@ -1,36 +1,266 @@
-use core::ops::Add;
-use std::{fmt::Debug, str::FromStr};
+use core::ops::{Add, Mul};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+    str::FromStr,
+};
 
+use crate::line_parser::{
+    is_plausible_smiles, is_sentinel_value, normalize_decimal_comma, normalize_key,
+};
 use crate::prelude::*;
 
+/// The optional fields that documents, such as the ones exported by GNPS,
+/// may fill with a placeholder value when the value is missing.
+const OPTIONAL_SENTINEL_FIELDS: &[&str] = &[
+    "SEQ",
+    "IONMODE",
+    "SMILES",
+    "INCHI",
+    "INCHIAUX",
+    "INCHIKEY",
+    "NAME",
+    "ORGANISM",
+    "SOURCE_INSTRUMENT",
+    "FILENAME",
+    "ADDUCT",
+    "PI",
+    "DATACOLLECTOR",
+    "PUBMED",
+    "SUBMITUSER",
+    "LIBRARYQUALITY",
+    "SPECTRUMID",
+];
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MascotGenericFormatMetadataBuilder<I, F> {
     feature_id: Option<I>,
     parent_ion_mass: Option<F>,
     retention_time: Option<F>,
     charge: Option<Charge>,
+    adduct_charge: Option<Charge>,
+    alternative_charges: Vec<Charge>,
     minus_one_scans: bool,
+    scan_number: Option<I>,
     merge_scans_metadata_builder: Option<MergeScansMetadataBuilder<I>>,
     filename: Option<String>,
+    ion_mode: Option<IonMode>,
+    extra: BTreeMap<String, String>,
+    sentinel_fields: BTreeSet<String>,
+    options: ParseOptions,
 }
 
 impl<I, F> Default for MascotGenericFormatMetadataBuilder<I, F> {
     fn default() -> Self {
+        Self::with_options(ParseOptions::default())
+    }
+}
+
+impl<I, F> MascotGenericFormatMetadataBuilder<I, F> {
+    /// Creates a new builder using the provided [`ParseOptions`].
+    ///
+    /// # Arguments
+    /// * `options` - The options to use while parsing.
+    pub fn with_options(options: ParseOptions) -> Self {
         Self {
             feature_id: None,
             parent_ion_mass: None,
             retention_time: None,
             charge: None,
+            adduct_charge: None,
+            alternative_charges: Vec::new(),
             minus_one_scans: false,
+            scan_number: None,
             merge_scans_metadata_builder: None,
             filename: None,
+            ion_mode: None,
+            extra: BTreeMap::new(),
+            sentinel_fields: BTreeSet::new(),
+            options,
+        }
+    }
+
+    /// Returns the parse options used by the builder.
+    pub fn options(&self) -> ParseOptions {
+        self.options
+    }
+
+    /// Returns whether the last `SCANS` line was `SCANS=-1`, which marks
+    /// the current section as a partial read of the entry.
+    ///
+    /// A `SCANS=-1` line sets the flag, while a later `SCANS=<id>` line with
+    /// a valid scan clears it. The metadata cannot be built while the flag is
+    /// set, and resetting the builder also clears it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::default();
+    /// assert!(!parser.is_partial());
+    ///
+    /// for line in ["FEATURE_ID=1", "PEPMASS=381.0795", "CHARGE=1", "RTINSECONDS=37.083", "SCANS=-1"] {
+    ///     parser.digest_line(line).unwrap();
+    /// }
+    /// assert!(parser.is_partial());
+    /// assert!(parser.clone().build().is_err());
+    ///
+    /// parser.digest_line("SCANS=1").unwrap();
+    /// assert!(!parser.is_partial());
+    /// assert_eq!(parser.feature_id(), Some(1));
+    /// assert!(parser.build().is_ok());
+    /// ```
+    pub fn is_partial(&self) -> bool {
+        self.minus_one_scans
+    }
+
+    /// Returns the feature ID parsed so far, if any.
+    ///
+    /// When no `FEATURE_ID` line was parsed, the scan number of the `SCANS`
+    /// line is used as the feature ID.
+    pub fn feature_id(&self) -> Option<I>
+    where
+        I: Copy,
+    {
+        self.feature_id.or(self.scan_number)
+    }
+
+    /// Returns the parent ion mass parsed so far, if any.
+    pub fn parent_ion_mass(&self) -> Option<F>
+    where
+        F: Copy,
+    {
+        self.parent_ion_mass
+    }
+
+    /// Sets the parent ion mass, bypassing the parsing of a `PEPMASS` line.
+    ///
+    /// # Arguments
+    /// * `parent_ion_mass` - The parent ion mass of the entry.
+    pub fn set_parent_ion_mass(&mut self, parent_ion_mass: F) {
+        self.parent_ion_mass = Some(parent_ion_mass);
+    }
+
+    /// Returns whether the builder could be built once the parent ion mass
+    /// is provided, i.e. whether all of the other required fields are known.
+    pub(crate) fn can_build_without_parent_ion_mass(&self) -> bool
+    where
+        I: FromStr + Add<Output = I> + Eq + Copy + From<usize>,
+    {
+        self.feature_id().is_some()
+            && self.retention_time.is_some()
+            && (self.charge.is_some() || self.adduct_charge.is_some())
+            && !self.minus_one_scans
+            && self
+                .merge_scans_metadata_builder
+                .as_ref()
+                .map_or(true, |builder| builder.can_build())
+    }
+
+    /// Returns whether the line is an optional `KEY=VALUE` field holding a
+    /// placeholder value, such as `SEQ=*..*`, `IONMODE=N/A` or `SMILES=`.
+    ///
+    /// Only the known optional fields of [`OPTIONAL_SENTINEL_FIELDS`] are
+    /// treated as placeholders: the absence of a required field is reported
+    /// as an error, and unknown fields are still rejected in strict mode.
+    fn is_optional_sentinel_line(line: &str) -> bool {
+        line.split_once('=').is_some_and(|(key, value)| {
+            is_sentinel_value(value) && OPTIONAL_SENTINEL_FIELDS.contains(&key)
+        })
+    }
+
+    /// Parses a numeric value, accepting a decimal comma when allowed by the parse options.
+    ///
+    /// # Arguments
+    /// * `value` - The value to parse.
+    fn parse_float(&self, value: &str) -> Result<F, F::Err>
+    where
+        F: FromStr,
+    {
+        if self.options.uses_european_decimals() {
+            F::from_str(&normalize_decimal_comma(value))
+        } else {
+            F::from_str(value)
         }
     }
+
+    /// Fills the retention time and the charge when the entry provides
+    /// neither of them, as is common in spectral libraries, if the parse
+    /// options allow zero values.
+    ///
+    /// # Arguments
+    /// * `retention_time` - The retention time to use when it is missing.
+    /// * `charge` - The charge to use when it is missing.
+    pub(crate) fn set_missing_retention_time_and_charge(
+        &mut self,
+        retention_time: F,
+        charge: Charge,
+    ) {
+        if !self.options.allows_zero_values() {
+            return;
+        }
+        self.retention_time.get_or_insert(retention_time);
+        if self.adduct_charge.is_none() {
+            self.charge.get_or_insert(charge);
+        }
+    }
+
+    /// Stores a field that is not part of the metadata in the extra fields.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the field.
+    /// * `value` - The value of the field.
+    ///
+    /// # Errors
+    /// * If the field was already encountered with a different value.
+    pub(crate) fn digest_extra(&mut self, key: &str, value: &str) -> Result<(), String> {
+        if let Some(observed_value) = self.extra.get_mut(key) {
+            if observed_value != value {
+                let error = || {
+                    format!(
+                        "Could not parse {} line: value was already encountered and it is now different: {}={}",
+                        key, key, value
+                    )
+                };
+                // The source instrument and the organism are subject to the
+                // same conflicts as the ion mode in merged documents.
+                if !matches!(key, "SOURCE_INSTRUMENT" | "ORGANISM") {
+                    return Err(error());
+                }
+                self.options.ion_mode_conflict().resolve(
+                    observed_value,
+                    value.to_string(),
+                    error,
+                )?;
+            }
+        } else {
+            self.extra.insert(key.to_string(), value.to_string());
+        }
+        Ok(())
+    }
+
+    /// Resets the builder to its default state, retaining the parse options.
+    pub fn reset(&mut self) {
+        self.feature_id = None;
+        self.parent_ion_mass = None;
+        self.retention_time = None;
+        self.charge = None;
+        self.adduct_charge = None;
+        self.alternative_charges.clear();
+        self.minus_one_scans = false;
+        self.scan_number = None;
+        self.merge_scans_metadata_builder = None;
+        self.filename = None;
+        self.ion_mode = None;
+        self.extra.clear();
+        self.sentinel_fields.clear();
+    }
 }
 
 impl<
         I: Copy + PartialEq + Eq + From<usize> + Debug + FromStr + Add<Output = I> + Zero,
-        F: StrictlyPositive + Copy,
+        F: StrictlyPositive + Copy + PartialEq + Zero,
     > MascotGenericFormatMetadataBuilder<I, F>
 {
     pub fn build(self) -> Result<MascotGenericFormatMetadata<I, F>, String> {
@@ -42,8 +272,38 @@ impl<
             .to_string());
         }
 
-        MascotGenericFormatMetadata::new(
-            self.feature_id.ok_or_else(|| {
+        // An explicit charge is authoritative, but it must agree with the
+        // charge implied by the adduct, when both are known.
+        if let (Some(charge), Some(adduct_charge)) = (self.charge, self.adduct_charge) {
+            if charge.magnitude() != adduct_charge.magnitude()
+                || charge.has_explicit_sign() && charge.sign() != adduct_charge.sign()
+            {
+                return Err(format!(
+                    concat!(
+                        "Could not build MascotGenericFormatMetadata: the charge {} ",
+                        "disagrees with the charge {} implied by the adduct."
+                    ),
+                    charge, adduct_charge
+                ));
+            }
+        }
+
+        let feature_id = self.feature_id();
+        // The scan number is only kept when it differs from the feature ID,
+        // which the parse options may allow.
+        let scan_number = self
+            .scan_number
+            .filter(|&scan_number| Some(scan_number) != feature_id);
+
+        // A zero retention time is only accepted when the parse options allow
+        // zero values, which is also when a missing one is defaulted to zero.
+        let new = if self.options.allows_zero_values() {
+            MascotGenericFormatMetadata::new_allowing_zero_retention_time
+        } else {
+            MascotGenericFormatMetadata::new
+        };
+        new(
+            feature_id.ok_or_else(|| {
                 "Could not build MascotGenericFormatMetadata: feature_id is missing".to_string()
             })?,
             self.parent_ion_mass.ok_or_else(|| {
@@ -53,7 +313,7 @@ impl<
             self.retention_time.ok_or_else(|| {
                 "Could not build MascotGenericFormatMetadata: retention_time is missing".to_string()
             })?,
-            self.charge.ok_or_else(|| {
+            self.charge.or(self.adduct_charge).ok_or_else(|| {
                 "Could not build MascotGenericFormatMetadata: charge is missing".to_string()
             })?,
             self.merge_scans_metadata_builder
@@ -61,12 +321,82 @@ impl<
                 .transpose()?,
             self.filename,
         )
+        .map(|metadata| {
+            metadata
+                .with_ion_mode(self.ion_mode)
+                .with_alternative_charges(self.alternative_charges)
+                .with_scan_number(scan_number)
+                .with_extra(self.extra)
+                .with_sentinel_fields(self.sentinel_fields)
+        })
     }
 }
 
 impl<
-        I: FromStr + Eq + Copy + Add<Output = I>,
-        F: FromStr + PartialEq + Copy + NaN + StrictlyPositive,
+        I: FromStr + Eq + Copy + Add<Output = I> + From<usize>,
+        F: FromStr
+            + PartialEq
+            + Copy
+            + NaN
+            + StrictlyPositive
+            + ApproxEq
+            + Mul<F, Output = F>
+            + From<u8>
+            + Zero,
+    > MascotGenericFormatMetadataBuilder<I, F>
+{
+    /// Returns whether the line can be parsed by a parser using the provided options.
+    ///
+    /// Unlike [`LineParser::can_parse_line`], which only accepts the lines
+    /// recognized by the default parse options, this also accepts the `ADDUCT`
+    /// and `SMILES` fields when the options enable inferring the charge from
+    /// the adduct or validating the SMILES, as [`LineParser::digest_line`] does.
+    ///
+    /// # Arguments
+    /// * `line` - The line to parse.
+    /// * `options` - The options of the parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// type Parser = MascotGenericFormatMetadataBuilder<usize, f64>;
+    ///
+    /// assert!(!Parser::can_parse_line("ADDUCT=[M+H]+"));
+    /// assert!(!Parser::can_parse_line_with_options("ADDUCT=[M+H]+", ParseOptions::default()));
+    /// assert!(Parser::can_parse_line_with_options(
+    ///     "ADDUCT=[M+H]+",
+    ///     ParseOptions::default().with_infer_charge_from_adduct(true)
+    /// ));
+    ///
+    /// assert!(!Parser::can_parse_line("SMILES=CCO"));
+    /// assert!(Parser::can_parse_line_with_options(
+    ///     "SMILES=CCO",
+    ///     ParseOptions::default().with_validate_smiles(true)
+    /// ));
+    /// ```
+    pub fn can_parse_line_with_options(line: &str, options: ParseOptions) -> bool {
+        if Self::can_parse_line(line) {
+            return true;
+        }
+        let line = normalize_key(line);
+        options.infers_charge_from_adduct() && line.starts_with("ADDUCT=")
+            || options.validates_smiles() && line.starts_with("SMILES=")
+    }
+}
+
+impl<
+        I: FromStr + Eq + Copy + Add<Output = I> + From<usize>,
+        F: FromStr
+            + PartialEq
+            + Copy
+            + NaN
+            + StrictlyPositive
+            + ApproxEq
+            + Mul<F, Output = F>
+            + From<u8>
+            + Zero,
     > LineParser for MascotGenericFormatMetadataBuilder<I, F>
 {
     /// Returns whether the line can be parsed by this parser.
@@ -90,33 +420,32 @@ impl<
     ///     "CHARGE=3+",
     ///     "CHARGE=4+",
     ///     "RTINSECONDS=37.083",
+    ///     "RTINMINUTES=0.61805",
     ///     "FILENAME=20220513_PMA_DBGI_01_04_003.mzML",
     ///     "SCANS=-1",
+    ///     "IONMODE=Positive",
     /// ] {
     ///     assert!(MascotGenericFormatMetadataBuilder::<usize, f64>::can_parse_line(line));
     /// }
     /// ```
     fn can_parse_line(line: &str) -> bool {
+        let line = normalize_key(line);
+        let line = line.as_ref();
         line.starts_with("FEATURE_ID=")
             || line.starts_with("PEPMASS=")
             || line.starts_with("SCANS=")
             || line.starts_with("RTINSECONDS=")
+            || line.starts_with("RTINMINUTES=")
             || line.starts_with("FILENAME=")
             || line.starts_with("CHARGE=")
+            || line.starts_with("IONMODE=")
             || MergeScansMetadataBuilder::<I>::can_parse_line(line)
+            || Self::is_optional_sentinel_line(line)
     }
 
     /// Returns whether the parser can build a [`MascotGenericFormatMetadata`] from the lines
     fn can_build(&self) -> bool {
-        self.feature_id.is_some()
-            && self.parent_ion_mass.is_some()
-            && self.retention_time.is_some()
-            && self.charge.is_some()
-            && !self.minus_one_scans
-            && self
-                .merge_scans_metadata_builder
-                .as_ref()
-                .map_or(true, |builder| builder.can_build())
+        self.parent_ion_mass.is_some() && self.can_build_without_parent_ion_mass()
     }
 
     /// Parses a line to a [`MascotGenericFormatMetadataBuilder`].
@@ -175,12 +504,50 @@ impl<
     /// assert!(parser.digest_line("RTINSECONDS=37.084").is_err());
     ///
     /// let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::default();
+    /// parser.digest_line("PEPMASS=381.0795").unwrap();
+    /// parser.digest_line("PEPMASS=381.07950").unwrap();
+    /// parser.digest_line("PEPMASS=381.07950000001").unwrap();
+    ///
+    /// let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::with_options(
+    ///     ParseOptions::default().with_relative_tolerance(0.0)
+    /// );
+    /// parser.digest_line("PEPMASS=381.0795").unwrap();
+    /// assert!(parser.digest_line("PEPMASS=381.07950000001").is_err());
+    ///
+    /// let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::default();
     /// parser.digest_line("CHARGE=1").unwrap();
     /// assert!(parser.digest_line("CHARGE=2").is_err());
     ///
+    /// let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::default();
+    /// assert!(parser.digest_line("SOURCE_INSTRUMENT=LC-ESI-qTof").is_err());
+    ///
+    /// let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::with_options(ParseOptions::lenient());
+    /// parser.digest_line("SOURCE_INSTRUMENT=LC-ESI-qTof").unwrap();
+    /// assert!(parser.digest_line("SOURCE_INSTRUMENT=Orbitrap").is_err());
+    ///
+    /// let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::default();
+    /// parser.digest_line("Pepmass=381.0795").unwrap();
+    /// parser.digest_line(" pepmass = 381.0795").unwrap();
+    /// parser.digest_line("CHARGE = 1+").unwrap();
+    /// assert!(parser.digest_line("charge=2+").is_err());
+    ///
+    /// let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::default();
+    /// parser.digest_line("IONMODE=Negative").unwrap();
+    /// assert!(parser.digest_line("IONMODE=Positive").is_err());
+    ///
     /// ```
     ///
     fn digest_line(&mut self, line: &str) -> Result<(), String> {
+        let line = normalize_key(line);
+        let line = line.as_ref();
+        // Optional fields holding a placeholder value are treated as absent,
+        // but we keep track of them to tell them apart from omitted fields.
+        if Self::is_optional_sentinel_line(line) {
+            if let Some((key, _)) = line.split_once('=') {
+                self.sentinel_fields.insert(key.to_string());
+            }
+            return Ok(());
+        }
         if let Some(stripped) = line.strip_prefix("FEATURE_ID=") {
             let feature_id = I::from_str(stripped).map_err(|_| {
                 format!(
@@ -202,7 +569,7 @@ impl<
         }
 
         if let Some(stripped) = line.strip_prefix("PEPMASS=") {
-            let parent_ion_mass = F::from_str(stripped).map_err(|_| {
+            let parent_ion_mass = self.parse_float(stripped).map_err(|_| {
                 format!(
                     "Could not parse PEPMASS line: could not parse parent ion mass: {}",
                     line
@@ -228,7 +595,10 @@ impl<
                 ));
             }
             if let Some(observerd_parent_ion_mass) = self.parent_ion_mass {
-                if parent_ion_mass != observerd_parent_ion_mass {
+                if !parent_ion_mass.approx_eq(
+                    &observerd_parent_ion_mass,
+                    self.options.relative_tolerance(),
+                ) {
                     return Err(format!(
                         "Could not parse PEPMASS line: parent_ion_mass was already encountered and it is now different: {}",
                         line
@@ -252,6 +622,12 @@ impl<
                     line
                 )
             })?;
+            // The scan numbering may be independent of the feature IDs, in
+            // which case the scan number is stored separately.
+            if self.options.allows_independent_scan_numbers() {
+                self.scan_number = Some(scans);
+                return Ok(());
+            }
             if let Some(feature_id) = self.feature_id {
                 if scans != feature_id {
                     return Err(format!(
@@ -266,14 +642,21 @@ impl<
         }
 
         if line.starts_with("CHARGE=") {
-            let charge = Charge::from_str(line).map_err(|_| {
+            // Ambiguous charges, such as `CHARGE=1+ and 2+`, are parsed as a
+            // list of candidates, using the one with the lowest magnitude as
+            // the charge and keeping the others as alternatives.
+            let mut alternative_charges = Charge::from_str_multi(line).map_err(|_| {
                 format!(
                     "Could not parse CHARGE line: could not parse charge: {}",
                     line
                 )
             })?;
+            let primary_position = (0..alternative_charges.len())
+                .min_by_key(|&position| alternative_charges[position].magnitude())
+                .unwrap();
+            let charge = alternative_charges.remove(primary_position);
             if let Some(observed_charge) = self.charge {
-                if observed_charge != charge {
+                if observed_charge != charge || self.alternative_charges != alternative_charges {
                     return Err(format!(
                         "Could not parse CHARGE line: charge was already encountered and it is now different: {}",
                         line
@@ -281,17 +664,53 @@ impl<
                 }
             } else {
                 self.charge = Some(charge);
+                self.alternative_charges = alternative_charges;
+            }
+            return Ok(());
+        }
+
+        if line.starts_with("IONMODE=") {
+            let ion_mode = IonMode::from_str(line)?;
+            if let Some(observed_ion_mode) = self.ion_mode.as_mut() {
+                if *observed_ion_mode != ion_mode {
+                    self.options.ion_mode_conflict().resolve(
+                        observed_ion_mode,
+                        ion_mode,
+                        || {
+                            format!(
+                                "Could not parse IONMODE line: ion mode was already encountered and it is now different: {}",
+                                line
+                            )
+                        },
+                    )?;
+                }
+            } else {
+                self.ion_mode = Some(ion_mode);
             }
             return Ok(());
         }
 
-        if let Some(stripped) = line.strip_prefix("RTINSECONDS=") {
-            let retention_time = F::from_str(stripped).map_err(|_| {
+        // Some tools export the retention time in minutes, under the `RTINMINUTES`
+        // key, which we convert to seconds as they are used for storage.
+        let retention_time_line = line
+            .strip_prefix("RTINSECONDS=")
+            .map(|stripped| ("RTINSECONDS", stripped, false))
+            .or_else(|| {
+                line.strip_prefix("RTINMINUTES=")
+                    .map(|stripped| ("RTINMINUTES", stripped, true))
+            });
+        if let Some((key, stripped, in_minutes)) = retention_time_line {
+            let retention_time = self.parse_float(stripped).map_err(|_| {
                 format!(
-                    "Could not parse RTINSECONDS line: could not parse retention time: {}",
-                    line
+                    "Could not parse {} line: could not parse retention time: {}",
+                    key, line
                 )
             })?;
+            let retention_time = if in_minutes {
+                retention_time * F::from(60)
+            } else {
+                retention_time
+            };
             if retention_time.is_nan() {
                 return Err(format!(
                     concat!(
@@ -301,21 +720,26 @@ impl<
                     line
                 ));
             }
-            if !retention_time.is_strictly_positive() {
+            if !(retention_time.is_strictly_positive()
+                || self.options.allows_zero_values() && retention_time == F::ZERO)
+            {
                 return Err(format!(
                     concat!(
                         "The provided line \"{}\" contains a retention time ",
                         "that has been interpreted as a zero or negative value. ",
-                        "The retention time must be a strictly positive value."
+                        "The retention time must be a strictly positive value, ",
+                        "or zero when the parse options allow zero values."
                     ),
                     line
                 ));
             }
             if let Some(observed_retention_time) = self.retention_time {
-                if observed_retention_time != retention_time {
+                if !retention_time
+                    .approx_eq(&observed_retention_time, self.options.relative_tolerance())
+                {
                     return Err(format!(
-                        "Could not parse RTINSECONDS line: retention_time was already encountered and it is now different: {}",
-                        line
+                        "Could not parse {} line: retention_time was already encountered and it is now different: {}",
+                        key, line
                     ));
                 }
             } else {
@@ -350,6 +774,41 @@ impl<
             return Ok(());
         }
 
+        if let Some(adduct) = line.strip_prefix("ADDUCT=") {
+            if self.options.infers_charge_from_adduct() {
+                let adduct_charge = Charge::from_adduct(adduct)?;
+                if let Some(observed_adduct) = self.extra.get("ADDUCT") {
+                    if observed_adduct != adduct {
+                        return Err(format!(
+                            "Could not parse ADDUCT line: adduct was already encountered and it is now different: {}",
+                            line
+                        ));
+                    }
+                }
+                self.adduct_charge = Some(adduct_charge);
+                self.extra.insert("ADDUCT".to_string(), adduct.to_string());
+                return Ok(());
+            }
+        }
+
+        if let Some(smiles) = line.strip_prefix("SMILES=") {
+            if self.options.validates_smiles() {
+                if !is_plausible_smiles(smiles) {
+                    return Err(format!(
+                        "Could not parse SMILES line: the value does not look like a SMILES string: {}",
+                        line
+                    ));
+                }
+                return self.digest_extra("SMILES", smiles);
+            }
+        }
+
+        if !self.options.is_strict() {
+            if let Some((key, value)) = line.split_once('=') {
+                return self.digest_extra(key, value);
+            }
+        }
+
         Err(format!(
             "Encountered unexpected line while parsing MascotGenericFormatMetadata: {}",
             line
@@ -1,6 +1,7 @@
 use std::{fmt::Debug, ops::Add};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MergeScansMetadata<I> {
     scans: Vec<I>,
     removed_due_to_low_quality: I,
@@ -38,6 +39,40 @@ impl<I: Add + Eq + Debug + Copy> MergeScansMetadata<I> {
         &self.scans
     }
 
+    /// Returns an iterator over the ids of the scans that were merged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot = MergeScansMetadata::new(vec![1, 2, 3], 4, 5).unwrap();
+    ///
+    /// assert_eq!(mascot.scans_iter().copied().collect::<Vec<usize>>(), vec![1, 2, 3]);
+    /// ```
+    pub fn scans_iter(&self) -> std::slice::Iter<'_, I> {
+        self.scans.iter()
+    }
+
+    /// Returns the number of scans that were merged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot: MergeScansMetadata<usize> = MergeScansMetadata::default();
+    ///
+    /// assert_eq!(mascot.num_merged(), 0);
+    ///
+    /// let mascot = MergeScansMetadata::new(vec![1, 2, 3], 4, 5).unwrap();
+    ///
+    /// assert_eq!(mascot.num_merged(), 3);
+    /// ```
+    pub fn num_merged(&self) -> usize {
+        self.scans.len()
+    }
+
     /// Returns the number of scans that were removed due to low quality.
     ///
     /// # Example
@@ -76,6 +111,31 @@ impl<I: Add + Eq + Debug + Copy> MergeScansMetadata<I> {
         self.removed_due_to_low_cosine
     }
 
+    /// Returns the total number of scans, i.e. the number of scans that
+    /// were merged plus the number of scans that were removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot: MergeScansMetadata<usize> = MergeScansMetadata::default();
+    ///
+    /// assert_eq!(mascot.total_scans(), 0);
+    ///
+    /// let mascot = MergeScansMetadata::new(vec![1, 2, 3], 4, 5).unwrap();
+    ///
+    /// assert_eq!(mascot.total_scans(), 12);
+    /// ```
+    pub fn total_scans(&self) -> I
+    where
+        I: Add<Output = I> + From<usize>,
+    {
+        I::from(self.num_merged())
+            + self.removed_due_to_low_quality
+            + self.removed_due_to_low_cosine
+    }
+
     /// Create new instance of `MergeScansMetadata`.
     ///
     /// # Example
@@ -106,7 +166,7 @@ impl<I: Add + Eq + Debug + Copy> MergeScansMetadata<I> {
         removed_due_to_low_cosine: I,
     ) -> Result<Self, String> {
         if scans.is_empty() {
-            return Err(concat!("No scans were provided.",).to_string());
+            return Err("No scans were provided.".to_string());
         }
 
         Ok(Self {
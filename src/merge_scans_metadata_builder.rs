@@ -1,6 +1,19 @@
 use std::{fmt::Debug, ops::Add, str::FromStr};
 
-use crate::{line_parser::LineParser, prelude::*};
+use crate::{
+    line_parser::{normalize_key, LineParser},
+    prelude::*,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+/// A scan number or an inclusive range of scan numbers from a `MERGED_SCANS` line.
+///
+/// Ranges are only expanded once the builder knows the total number of scans,
+/// so that a malformed line cannot request an arbitrarily large allocation.
+enum MergedScans<I> {
+    Scan(I),
+    Range(usize, usize),
+}
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 /// Builder for [`MergeScansMetadata`].
@@ -18,7 +31,7 @@ use crate::{line_parser::LineParser, prelude::*};
 /// the number of scans that were merged, the number of scans that were removed due to low quality, and the number
 /// of scans that were removed due to low cosine.
 pub struct MergeScansMetadataBuilder<I> {
-    scans: Vec<I>,
+    scans: Vec<MergedScans<I>>,
     removed_due_to_low_quality: Option<I>,
     removed_due_to_low_cosine: Option<I>,
     total_scans: Option<I>,
@@ -35,6 +48,16 @@ impl<I> Default for MergeScansMetadataBuilder<I> {
     }
 }
 
+impl<I> MergeScansMetadataBuilder<I> {
+    /// Resets the builder to its default state, retaining the allocated capacity.
+    pub fn reset(&mut self) {
+        self.scans.clear();
+        self.removed_due_to_low_quality = None;
+        self.removed_due_to_low_cosine = None;
+        self.total_scans = None;
+    }
+}
+
 impl<I: FromStr + Add<Output = I> + Eq + Copy + From<usize> + Debug> MergeScansMetadataBuilder<I> {
     pub fn build(self) -> Result<MergeScansMetadata<I>, String> {
         if self.removed_due_to_low_quality.is_none() {
@@ -52,11 +75,22 @@ impl<I: FromStr + Add<Output = I> + Eq + Copy + From<usize> + Debug> MergeScansM
             .to_string());
         }
 
+        // We count the scans that were merged, without expanding the ranges,
+        // so that we can check them against the total number of scans first.
+        let number_of_merged_scans = self
+            .scans
+            .iter()
+            .try_fold(0_usize, |count, scans| match scans {
+                MergedScans::Scan(_) => count.checked_add(1),
+                MergedScans::Range(start, end) => count.checked_add(end - start)?.checked_add(1),
+            })
+            .ok_or_else(|| "The number of scans that were merged overflows.".to_string())?;
+
         // We check that the total number of scans is equal to the sum of the
         // number of scans that were merged and the number of scans that were
         // removed.
         if self.total_scans.unwrap()
-            != I::from(self.scans.len())
+            != I::from(number_of_merged_scans)
                 + self.removed_due_to_low_quality.unwrap()
                 + self.removed_due_to_low_cosine.unwrap()
         {
@@ -68,15 +102,25 @@ impl<I: FromStr + Add<Output = I> + Eq + Copy + From<usize> + Debug> MergeScansM
             .to_string());
         }
 
+        let mut scans = Vec::with_capacity(number_of_merged_scans);
+        for merged_scans in self.scans {
+            match merged_scans {
+                MergedScans::Scan(scan) => scans.push(scan),
+                MergedScans::Range(start, end) => scans.extend((start..=end).map(I::from)),
+            }
+        }
+
         MergeScansMetadata::new(
-            self.scans,
+            scans,
             self.removed_due_to_low_quality.unwrap(),
             self.removed_due_to_low_cosine.unwrap(),
         )
     }
 }
 
-impl<I: FromStr + Add<Output = I> + Eq + Copy> LineParser for MergeScansMetadataBuilder<I> {
+impl<I: FromStr + Add<Output = I> + Eq + Copy + From<usize>> LineParser
+    for MergeScansMetadataBuilder<I>
+{
     /// Returns `true` if the line can be parsed by the data structure.
     ///
     /// # Example
@@ -93,6 +137,8 @@ impl<I: FromStr + Add<Output = I> + Eq + Copy> LineParser for MergeScansMetadata
     /// assert!(!MergeScansMetadataBuilder::<usize>::can_parse_line("STATS=2 / 2 (0 removed due to low quality, 0 removed due to low cosine)."));
     /// ```
     fn can_parse_line(line: &str) -> bool {
+        let line = normalize_key(line);
+        let line = line.as_ref();
         line.starts_with("MERGED_SCANS=") || line.starts_with("MERGED_STATS=")
     }
 
@@ -131,7 +177,69 @@ impl<I: FromStr + Add<Output = I> + Eq + Copy> LineParser for MergeScansMetadata
     /// assert_eq!(metadata.removed_due_to_low_quality(), 0);
     /// assert_eq!(metadata.removed_due_to_low_cosine(), 0);
     /// ```
+    ///
+    /// Ranges of scans are expanded, and count towards the total number of scans:
+    ///
+    /// ```rust
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut builder: MergeScansMetadataBuilder<usize> = MergeScansMetadataBuilder::default();
+    /// builder.digest_line("MERGED_SCANS=1540-1543,1560").unwrap();
+    /// builder.digest_line("MERGED_STATS=5 / 6 (1 removed due to low quality, 0 removed due to low cosine).").unwrap();
+    /// let metadata = builder.build().unwrap();
+    ///
+    /// assert_eq!(metadata.scans(), &[1540, 1541, 1542, 1543, 1560]);
+    /// assert_eq!(metadata.total_scans(), 6);
+    ///
+    /// let mut builder: MergeScansMetadataBuilder<usize> = MergeScansMetadataBuilder::default();
+    /// assert!(builder.digest_line("MERGED_SCANS=1545-1540").is_err());
+    /// assert!(builder.digest_line("MERGED_SCANS=1540-").is_err());
+    /// ```
+    ///
+    /// A range larger than the total number of scans is rejected before it is expanded:
+    ///
+    /// ```rust
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut builder: MergeScansMetadataBuilder<usize> = MergeScansMetadataBuilder::default();
+    /// builder.digest_line("MERGED_SCANS=1-4000000000000").unwrap();
+    /// builder.digest_line("MERGED_STATS=5 / 6 (1 removed due to low quality, 0 removed due to low cosine).").unwrap();
+    /// assert!(builder.build().is_err());
+    ///
+    /// let mut builder: MergeScansMetadataBuilder<usize> = MergeScansMetadataBuilder::default();
+    /// builder.digest_line(&format!("MERGED_SCANS=0-{},0-{}", usize::MAX, usize::MAX)).unwrap();
+    /// builder.digest_line("MERGED_STATS=5 / 6 (1 removed due to low quality, 0 removed due to low cosine).").unwrap();
+    /// assert!(builder.build().is_err());
+    /// ```
+    ///
+    /// The phrasing of the `MERGED_STATS` line may vary:
+    ///
+    /// ```rust
+    /// use mascot_rs::prelude::*;
+    ///
+    /// for line in [
+    ///     "MERGED_STATS=2 / 5 (1 removed due to low quality, 2 removed due to low cosine).",
+    ///     "MERGED_STATS=2 / 5 (1 removed due to low quality, 2 removed due to low cosine)",
+    ///     "MERGED_STATS=2/5 (1 removed due to low quality,  2 removed due to low cosine score).",
+    ///     "MERGED_STATS= 2  /  5  ( 1 removed due to low quality , 2 removed due to low cosine )",
+    /// ] {
+    ///     let mut builder: MergeScansMetadataBuilder<usize> = MergeScansMetadataBuilder::default();
+    ///     builder.digest_line("MERGED_SCANS=1567,1540").unwrap();
+    ///     builder.digest_line(line).unwrap();
+    ///     let metadata = builder.build().unwrap();
+    ///
+    ///     assert_eq!(metadata.removed_due_to_low_quality(), 1);
+    ///     assert_eq!(metadata.removed_due_to_low_cosine(), 2);
+    ///     assert_eq!(metadata.total_scans(), 5);
+    /// }
+    ///
+    /// let mut builder: MergeScansMetadataBuilder<usize> = MergeScansMetadataBuilder::default();
+    /// assert!(builder.digest_line("MERGED_STATS=2 / 6 (1 removed due to low quality, 2 removed due to low cosine).").is_err());
+    /// assert!(builder.digest_line("MERGED_STATS=2 / 3 (1 removed due to low quality).").is_err());
+    /// ```
     fn digest_line(&mut self, line: &str) -> Result<(), String> {
+        let line = normalize_key(line);
+        let line = line.as_ref();
         // This first check is meant to capture lines such as:
         //
         // ```text
@@ -141,18 +249,39 @@ impl<I: FromStr + Add<Output = I> + Eq + Copy> LineParser for MergeScansMetadata
         // We expect to populate the `scans` field with the values
         // `1567` and `1540` which represent the scan numbers that
         // were merged.
+        //
+        // Some exporters compress consecutive scans into ranges, such as
+        // `MERGED_SCANS=1540-1542,1560`, which we expand into the scans
+        // `1540`, `1541`, `1542` and `1560`. The expansion is deferred to
+        // the build step, after the number of scans in the ranges has been
+        // checked against the total number of scans from `MERGED_STATS`.
         if line.starts_with("MERGED_SCANS=") {
-            let scans = line
-                .trim_start_matches("MERGED_SCANS=")
-                .split(',')
-                .map(|scan| scan.parse::<I>())
-                .collect::<Result<Vec<I>, _>>()
-                .map_err(|_| {
-                    format!(
-                        concat!("Failed to parse the scan numbers from the line: ", "\"{}\"",),
-                        line
-                    )
-                })?;
+            let parse_error = || {
+                format!(
+                    concat!("Failed to parse the scan numbers from the line: ", "\"{}\"",),
+                    line
+                )
+            };
+            let mut scans = Vec::new();
+            for token in line.trim_start_matches("MERGED_SCANS=").split(',') {
+                let token = token.trim();
+                match token.split_once('-') {
+                    Some((start, end)) => {
+                        let start = start.trim().parse::<usize>().map_err(|_| parse_error())?;
+                        let end = end.trim().parse::<usize>().map_err(|_| parse_error())?;
+                        if start > end {
+                            return Err(format!(
+                                "The scan range \"{}\" in the line \"{}\" is decreasing.",
+                                token, line
+                            ));
+                        }
+                        scans.push(MergedScans::Range(start, end));
+                    }
+                    None => scans.push(MergedScans::Scan(
+                        token.parse::<I>().map_err(|_| parse_error())?,
+                    )),
+                }
+            }
             self.scans = scans;
             return Ok(());
         }
@@ -240,46 +369,19 @@ impl<I: FromStr + Add<Output = I> + Eq + Copy> LineParser for MergeScansMetadata
                 ))
             }?;
 
-            // We expect the fraction to have two parts, the first containing
-            // the number of scans that were merged and the second containing
-            // the total number of scans. We assign the two parts and proceed
-            // to split the second part into two parts:
-            // 1. The number of scans that were removed due to low quality.
-            // 2. The number of scans that were removed due to low cosine.
-            let (low_quality, low_cosine) = if let Some(removed_scans) = parts.next() {
-                let mut removed_scans = removed_scans.split(',');
-                let low_quality = if let Some(low_quality) = removed_scans.next() {
-                    Ok(low_quality)
-                } else {
-                    Err(format!(
-                        concat!(
-                            "The builder for the data structure ",
-                            "`MergeScansMetadata` ",
-                            "does not extract the low quality ",
-                            "scans count from the line: ",
-                            "\"{}\"",
-                        ),
-                        line,
-                    ))
-                }?;
-
-                let low_cosine = if let Some(low_cosine) = removed_scans.next() {
-                    Ok(low_cosine)
-                } else {
-                    Err(format!(
-                        concat!(
-                            "The builder for the data structure ",
-                            "`MergeScansMetadata` ",
-                            "does not extract the low cosine ",
-                            "scans count from the line: ",
-                            "\"{}\"",
-                        ),
-                        line,
-                    ))
-                }?;
-                Ok((low_quality, low_cosine))
-            } else {
-                Err(format!(
+            // The second part contains the number of scans that were removed,
+            // in a phrasing that varies across documents, such as:
+            //
+            // ```text
+            // (1 removed due to low quality, 2 removed due to low cosine).
+            // (1 removed due to low quality,  2 removed due to low cosine score)
+            // ```
+            //
+            // We therefore extract the first two integers by position: the first
+            // is the number of scans that were removed due to low quality, and the
+            // second the number of scans that were removed due to low cosine.
+            let removed_scans = parts.next().ok_or_else(|| {
+                format!(
                     concat!(
                         "The builder for the data structure ",
                         "`MergeScansMetadata` ",
@@ -287,61 +389,39 @@ impl<I: FromStr + Add<Output = I> + Eq + Copy> LineParser for MergeScansMetadata
                         "\"{}\"",
                     ),
                     line,
-                ))
-            }?;
+                )
+            })?;
+            let mut removed_scans = removed_scans
+                .split(|character: char| !character.is_ascii_digit())
+                .filter(|token| !token.is_empty());
 
-            // We expect the number of scans that were removed to have two parts,
-            // the first containing the number of scans that were removed due to
-            // low quality and the second containing the number of scans that were
-            // removed due to low cosine. We assign the two parts and proceed to
-            // parse the values.
-            let removed_due_to_low_quality =
-                if let Some(low_quality) = low_quality.trim().split(' ').next() {
-                    low_quality.parse::<I>().map_err(|_| {
-                        format!(
-                            concat!(
-                                "Failed to parse the number of scans that were removed ",
-                                "due to low quality from the line: ",
-                                "\"{}\"",
-                            ),
-                            line
-                        )
-                    })
-                } else {
-                    Err(format!(
+            let removed_due_to_low_quality = removed_scans
+                .next()
+                .and_then(|low_quality| low_quality.parse::<I>().ok())
+                .ok_or_else(|| {
+                    format!(
                         concat!(
-                            "The builder for the data structure ",
-                            "`MergeScansMetadata` ",
-                            "does not support the line: ",
+                            "Failed to parse the number of scans that were removed ",
+                            "due to low quality from the line: ",
                             "\"{}\"",
                         ),
-                        line,
-                    ))
-                }?;
+                        line
+                    )
+                })?;
 
-            let removed_due_to_low_cosine =
-                if let Some(low_cosine) = low_cosine.trim().split(' ').next() {
-                    low_cosine.parse::<I>().map_err(|_| {
-                        format!(
-                            concat!(
-                                "Failed to parse the number of scans that were removed ",
-                                "due to low cosine from the line: ",
-                                "\"{}\"",
-                            ),
-                            line
-                        )
-                    })
-                } else {
-                    Err(format!(
+            let removed_due_to_low_cosine = removed_scans
+                .next()
+                .and_then(|low_cosine| low_cosine.parse::<I>().ok())
+                .ok_or_else(|| {
+                    format!(
                         concat!(
-                            "The builder for the data structure ",
-                            "`MergeScansMetadata` ",
-                            "does not support the line: ",
+                            "Failed to parse the number of scans that were removed ",
+                            "due to low cosine from the line: ",
                             "\"{}\"",
                         ),
-                        line,
-                    ))
-                }?;
+                        line
+                    )
+                })?;
 
             // We check whether the sum of removed scans plus the number of scans
             // that were merged equals the total number of scans.
@@ -0,0 +1,270 @@
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Sub};
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// Summary statistics of the entries of an [`MGFVec`].
+///
+/// The statistics aggregate the per-entry accessors, and are meant to give
+/// an overview of a dataset. The minimum, mean and maximum values are `None`
+/// when no entry contributes to them.
+pub struct MgfStats {
+    number_of_entries: usize,
+    number_of_first_level_only: usize,
+    number_of_second_level_only: usize,
+    number_of_two_levels: usize,
+    min_precursor_mz: Option<f64>,
+    mean_precursor_mz: Option<f64>,
+    max_precursor_mz: Option<f64>,
+    min_second_level_peaks: Option<usize>,
+    mean_second_level_peaks: Option<f64>,
+    max_second_level_peaks: Option<usize>,
+    charge_histogram: BTreeMap<Charge, usize>,
+    ion_mode_counts: BTreeMap<Option<IonMode>, usize>,
+}
+
+impl MgfStats {
+    /// Creates a new [`MgfStats`] from the provided entries.
+    ///
+    /// # Arguments
+    /// * `mascot_generic_formats` - The entries to aggregate.
+    pub fn new<'a, I, F, T>(mascot_generic_formats: T) -> Self
+    where
+        I: Copy + Zero + PartialEq + Debug + Add<Output = I> + Eq + 'a,
+        F: Copy
+            + StrictlyPositive
+            + PartialEq
+            + PartialOrd
+            + Debug
+            + Add<F, Output = F>
+            + Sub<F, Output = F>
+            + Into<f64>
+            + 'a,
+        T: IntoIterator<Item = &'a MascotGenericFormat<I, F>>,
+    {
+        let mut statistics = Self::default();
+        let mut total_precursor_mz = 0.0;
+        let mut total_second_level_peaks = 0;
+        let mut number_of_second_levels = 0;
+
+        for mascot_generic_format in mascot_generic_formats {
+            statistics.number_of_entries += 1;
+
+            let first_level = mascot_generic_format.get_level(FragmentationSpectraLevel::One);
+            let second_level = mascot_generic_format.get_level(FragmentationSpectraLevel::Two);
+            match (first_level.is_some(), second_level.is_some()) {
+                (true, true) => statistics.number_of_two_levels += 1,
+                (true, false) => statistics.number_of_first_level_only += 1,
+                (false, true) => statistics.number_of_second_level_only += 1,
+                (false, false) => {}
+            }
+
+            let precursor_mz: f64 = mascot_generic_format.precursor_mz().into();
+            total_precursor_mz += precursor_mz;
+            statistics.min_precursor_mz = Some(
+                statistics
+                    .min_precursor_mz
+                    .map_or(precursor_mz, |min| min.min(precursor_mz)),
+            );
+            statistics.max_precursor_mz = Some(
+                statistics
+                    .max_precursor_mz
+                    .map_or(precursor_mz, |max| max.max(precursor_mz)),
+            );
+
+            if let Some(second_level) = second_level {
+                let number_of_peaks = second_level.len();
+                total_second_level_peaks += number_of_peaks;
+                number_of_second_levels += 1;
+                statistics.min_second_level_peaks = Some(
+                    statistics
+                        .min_second_level_peaks
+                        .map_or(number_of_peaks, |min| min.min(number_of_peaks)),
+                );
+                statistics.max_second_level_peaks = Some(
+                    statistics
+                        .max_second_level_peaks
+                        .map_or(number_of_peaks, |max| max.max(number_of_peaks)),
+                );
+            }
+
+            *statistics
+                .charge_histogram
+                .entry(mascot_generic_format.charge())
+                .or_insert(0) += 1;
+            *statistics
+                .ion_mode_counts
+                .entry(mascot_generic_format.effective_ion_mode())
+                .or_insert(0) += 1;
+        }
+
+        if statistics.number_of_entries > 0 {
+            statistics.mean_precursor_mz =
+                Some(total_precursor_mz / statistics.number_of_entries as f64);
+        }
+        if number_of_second_levels > 0 {
+            statistics.mean_second_level_peaks =
+                Some(total_second_level_peaks as f64 / number_of_second_levels as f64);
+        }
+
+        statistics
+    }
+
+    /// Returns the number of entries.
+    pub fn number_of_entries(&self) -> usize {
+        self.number_of_entries
+    }
+
+    /// Returns the number of entries with only the first fragmentation level.
+    pub fn number_of_first_level_only(&self) -> usize {
+        self.number_of_first_level_only
+    }
+
+    /// Returns the number of entries with only the second fragmentation level.
+    pub fn number_of_second_level_only(&self) -> usize {
+        self.number_of_second_level_only
+    }
+
+    /// Returns the number of entries with both the first and the second fragmentation levels.
+    pub fn number_of_two_levels(&self) -> usize {
+        self.number_of_two_levels
+    }
+
+    /// Returns the minimum parent ion mass of the entries.
+    pub fn min_precursor_mz(&self) -> Option<f64> {
+        self.min_precursor_mz
+    }
+
+    /// Returns the mean parent ion mass of the entries.
+    pub fn mean_precursor_mz(&self) -> Option<f64> {
+        self.mean_precursor_mz
+    }
+
+    /// Returns the maximum parent ion mass of the entries.
+    pub fn max_precursor_mz(&self) -> Option<f64> {
+        self.max_precursor_mz
+    }
+
+    /// Returns the minimum number of peaks of the second fragmentation levels.
+    pub fn min_second_level_peaks(&self) -> Option<usize> {
+        self.min_second_level_peaks
+    }
+
+    /// Returns the mean number of peaks of the second fragmentation levels.
+    pub fn mean_second_level_peaks(&self) -> Option<f64> {
+        self.mean_second_level_peaks
+    }
+
+    /// Returns the maximum number of peaks of the second fragmentation levels.
+    pub fn max_second_level_peaks(&self) -> Option<usize> {
+        self.max_second_level_peaks
+    }
+
+    /// Returns the number of entries with each charge.
+    pub fn charge_histogram(&self) -> &BTreeMap<Charge, usize> {
+        &self.charge_histogram
+    }
+
+    /// Returns the number of entries with each effective ion mode.
+    pub fn ion_mode_counts(&self) -> &BTreeMap<Option<IonMode>, usize> {
+        &self.ion_mode_counts
+    }
+}
+
+impl Display for MgfStats {
+    /// Formats the statistics as a readable report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1+",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=2",
+    ///     "60.5425 2.4E5",
+    ///     "119.0857 3.3E5",
+    ///     "END IONS",
+    /// ]).unwrap();
+    ///
+    /// assert_eq!(
+    ///     mascot_generic_formats.statistics().to_string(),
+    ///     concat!(
+    ///         "Entries: 1\n",
+    ///         "  first level only: 0\n",
+    ///         "  second level only: 1\n",
+    ///         "  both levels: 0\n",
+    ///         "Precursor m/z: min 381.0795, mean 381.0795, max 381.0795\n",
+    ///         "Peaks per second level: min 2, mean 2, max 2\n",
+    ///         "Charges: 1+ (1)\n",
+    ///         "Ion modes: Positive (1)\n",
+    ///     )
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Entries: {}", self.number_of_entries)?;
+        writeln!(f, "  first level only: {}", self.number_of_first_level_only)?;
+        writeln!(
+            f,
+            "  second level only: {}",
+            self.number_of_second_level_only
+        )?;
+        writeln!(f, "  both levels: {}", self.number_of_two_levels)?;
+        match (
+            self.min_precursor_mz,
+            self.mean_precursor_mz,
+            self.max_precursor_mz,
+        ) {
+            (Some(min), Some(mean), Some(max)) => {
+                writeln!(f, "Precursor m/z: min {}, mean {}, max {}", min, mean, max)?
+            }
+            _ => writeln!(f, "Precursor m/z: none")?,
+        }
+        match (
+            self.min_second_level_peaks,
+            self.mean_second_level_peaks,
+            self.max_second_level_peaks,
+        ) {
+            (Some(min), Some(mean), Some(max)) => writeln!(
+                f,
+                "Peaks per second level: min {}, mean {}, max {}",
+                min, mean, max
+            )?,
+            _ => writeln!(f, "Peaks per second level: none")?,
+        }
+        writeln!(
+            f,
+            "Charges: {}",
+            self.charge_histogram
+                .iter()
+                .map(|(charge, count)| {
+                    let charge = charge.to_string();
+                    format!(
+                        "{} ({})",
+                        charge.strip_prefix("CHARGE=").unwrap_or(&charge),
+                        count
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(", ")
+        )?;
+        writeln!(
+            f,
+            "Ion modes: {}",
+            self.ion_mode_counts
+                .iter()
+                .map(|(ion_mode, count)| match ion_mode {
+                    Some(ion_mode) => format!("{:?} ({})", ion_mode, count),
+                    None => format!("Unknown ({})", count),
+                })
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
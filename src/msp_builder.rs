@@ -0,0 +1,441 @@
+use std::{fmt::Debug, ops::Add, ops::Mul, ops::Sub, str::FromStr};
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A builder for [`MascotGenericFormat`] digesting NIST-style MSP entries.
+///
+/// # Example
+/// The data structure is meant to digest lines from MSP files such as:
+///
+/// ```text
+/// NAME: 1
+/// PRECURSORMZ: 381.0795
+/// CHARGE: 1+
+/// RETENTIONTIME: 37.083
+/// Num Peaks: 2
+/// 60.5425 240000
+/// 119.0857 330000
+/// ```
+///
+/// The fields overlapping with the MGF format are forwarded to a
+/// [`MascotGenericFormatMetadataBuilder`], while the peaks are collected
+/// into a single second fragmentation level. The `Name` field is used as the
+/// feature ID when it is a valid one, and it is otherwise kept as the `NAME`
+/// extra field, as for compound names. The other standard NIST fields,
+/// such as `Synon`, `Formula` or `InChIKey`, are kept as extra fields, and
+/// the `Precursor_type` field is kept as the `ADDUCT` of the entry.
+///
+/// As spectral libraries often omit them, when the parse options allow zero
+/// values, see [`ParseOptions::with_allow_zero_values`], entries without a
+/// retention time get a retention time of zero, and entries without a charge
+/// (either explicit or implied by the adduct) get a charge of one. Otherwise,
+/// entries missing either of them are reported as errors.
+pub struct MSPBuilder<I, F> {
+    metadata_builder: MascotGenericFormatMetadataBuilder<I, F>,
+    data_builder: MascotGenericFormatDataBuilder<F>,
+    expected_number_of_peaks: Option<usize>,
+    number_of_peaks: usize,
+    number_of_digested_lines: usize,
+    synonyms: Vec<String>,
+    options: ParseOptions,
+}
+
+/// The standard NIST fields that are kept as extra fields of the metadata.
+const NIST_EXTRA_FIELDS: &[&str] = &[
+    "FORMULA",
+    "MW",
+    "EXACTMASS",
+    "EXACT_MASS",
+    "INCHIKEY",
+    "INCHI",
+    "SMILES",
+    "COMMENT",
+    "COMMENTS",
+    "NOTES",
+    "SPECTRUM_TYPE",
+    "INSTRUMENT",
+    "INSTRUMENT_TYPE",
+    "IONIZATION",
+    "COLLISION_ENERGY",
+    "COLLISION_GAS",
+    "SAMPLE_INLET",
+    "DB#",
+    "NIST#",
+    "NISTNO",
+    "CAS#",
+    "RELATED_CAS#",
+    "ID",
+];
+
+impl<I, F> Default for MSPBuilder<I, F>
+where
+    I: Copy + FromStr + Eq + Add<Output = I> + Debug + From<usize>,
+    F: ParsableFloat,
+{
+    fn default() -> Self {
+        Self::with_options(ParseOptions::default())
+    }
+}
+
+impl<I, F> MSPBuilder<I, F>
+where
+    I: Copy + FromStr + Eq + Add<Output = I> + Debug + From<usize>,
+    F: ParsableFloat,
+{
+    /// Creates a new builder using the provided [`ParseOptions`].
+    ///
+    /// # Arguments
+    /// * `options` - The options to use while parsing.
+    pub fn with_options(options: ParseOptions) -> Self {
+        let mut data_builder = MascotGenericFormatDataBuilder::with_options(options);
+        // MSP entries only carry the fragmentation spectrum.
+        data_builder.set_level(FragmentationSpectraLevel::Two);
+        Self {
+            metadata_builder: MascotGenericFormatMetadataBuilder::with_options(options),
+            data_builder,
+            expected_number_of_peaks: None,
+            number_of_peaks: 0,
+            number_of_digested_lines: 0,
+            synonyms: Vec::new(),
+            options,
+        }
+    }
+
+    /// Returns whether all of the peaks declared by `Num Peaks` were digested.
+    pub fn has_all_peaks(&self) -> bool {
+        self.number_of_peaks > 0 && self.expected_number_of_peaks == Some(self.number_of_peaks)
+    }
+
+    /// Sets the feature ID of an entry that does not provide a valid one.
+    ///
+    /// # Arguments
+    /// * `feature_id` - The feature ID to use for the entry.
+    ///
+    /// # Errors
+    /// * If the entry already has a feature ID.
+    pub fn set_default_feature_id(&mut self, feature_id: usize) -> Result<(), String> {
+        self.metadata_builder
+            .digest_line(&format!("FEATURE_ID={}", feature_id))
+    }
+
+    /// Resets the builder to its default state, retaining the allocated
+    /// capacity and the parse options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut builder = MSPBuilder::<usize, f64>::default();
+    ///
+    /// for line in ["NAME: 1", "PRECURSORMZ: 381.0795", "Num Peaks: 2", "60.5425 240000"] {
+    ///     builder.digest_line(line).unwrap();
+    /// }
+    ///
+    /// builder.reset();
+    ///
+    /// assert!(builder.is_empty());
+    /// assert_eq!(builder, MSPBuilder::default());
+    /// ```
+    pub fn reset(&mut self) {
+        self.metadata_builder.reset();
+        self.data_builder.reset();
+        self.data_builder.set_level(FragmentationSpectraLevel::Two);
+        self.expected_number_of_peaks = None;
+        self.number_of_peaks = 0;
+        self.number_of_digested_lines = 0;
+        self.synonyms.clear();
+    }
+
+    /// Returns whether no line has been digested yet.
+    pub fn is_empty(&self) -> bool {
+        self.number_of_digested_lines == 0
+    }
+}
+
+impl<I, F> MSPBuilder<I, F>
+where
+    I: Copy + Eq + Debug + Add<Output = I> + FromStr + From<usize> + Zero,
+    F: Copy
+        + StrictlyPositive
+        + PartialEq
+        + PartialOrd
+        + Debug
+        + Sub<F, Output = F>
+        + Add<F, Output = F>
+        + ApproxEq
+        + Zero
+        + Mul<F, Output = F>
+        + From<u8>,
+{
+    /// Returns the feature ID of the entry being built, if already known.
+    pub fn feature_id(&self) -> Option<I> {
+        self.metadata_builder.feature_id()
+    }
+
+    /// Returns why the entry being built cannot be built, if it cannot.
+    pub(crate) fn incomplete_entry_error(&self) -> Option<String> {
+        match self.expected_number_of_peaks {
+            None => Some(
+                "The entry does not declare its number of peaks with a `Num Peaks` field."
+                    .to_string(),
+            ),
+            Some(expected_number_of_peaks) if self.number_of_peaks < expected_number_of_peaks => {
+                Some(format!(
+                    concat!(
+                        "The entry provides {} of the {} peaks ",
+                        "declared by its `Num Peaks` field."
+                    ),
+                    self.number_of_peaks, expected_number_of_peaks
+                ))
+            }
+            Some(_) => self.metadata_builder.clone().build().err(),
+        }
+    }
+
+    /// Builds a [`MascotGenericFormat`] from the digested entry.
+    pub fn build(self) -> Result<MascotGenericFormat<I, F>, String> {
+        MascotGenericFormat::new_with_precursor_tolerance(
+            self.metadata_builder.build()?,
+            vec![self.data_builder.build()?],
+            self.options.validated_precursor_tolerance(),
+        )
+    }
+
+    /// Builds a [`MascotGenericFormat`] from the digested entry, leaving
+    /// the builder in its default state for the next entry.
+    ///
+    /// The fields and the peaks of the entry are moved into the built
+    /// object, so the metadata and data builders are replaced by fresh
+    /// ones rather than reset in place.
+    ///
+    /// The builder is reset even when the build fails.
+    pub fn build_and_reset(&mut self) -> Result<MascotGenericFormat<I, F>, String>
+    where
+        F: FromStr + NaN,
+    {
+        let metadata = std::mem::replace(
+            &mut self.metadata_builder,
+            MascotGenericFormatMetadataBuilder::with_options(self.options),
+        )
+        .build();
+        let data = std::mem::replace(
+            &mut self.data_builder,
+            MascotGenericFormatDataBuilder::with_options(self.options),
+        )
+        .build();
+        self.reset();
+        MascotGenericFormat::new_with_precursor_tolerance(
+            metadata?,
+            vec![data?],
+            self.options.validated_precursor_tolerance(),
+        )
+    }
+}
+
+impl<I, F> LineParser for MSPBuilder<I, F>
+where
+    I: Copy + FromStr + Eq + Add<Output = I> + Debug + From<usize>,
+    F: ParsableFloat,
+{
+    /// Returns whether the line can be parsed by this parser.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// assert!(MSPBuilder::<usize, f64>::can_parse_line("PRECURSORMZ: 381.0795"));
+    /// assert!(MSPBuilder::<usize, f64>::can_parse_line("60.5425 240000"));
+    /// assert!(MSPBuilder::<usize, f64>::can_parse_line("60.5425\t240000"));
+    /// assert!(!MSPBuilder::<usize, f64>::can_parse_line("BEGIN IONS"));
+    /// ```
+    fn can_parse_line(line: &str) -> bool {
+        line.contains(':') || {
+            let mut tokens = line.split_whitespace();
+            tokens
+                .next()
+                .is_some_and(|token| token.parse::<F>().is_ok())
+                && tokens
+                    .next()
+                    .is_some_and(|token| token.parse::<F>().is_ok())
+        }
+    }
+
+    /// Returns whether the entry is complete.
+    fn can_build(&self) -> bool {
+        self.metadata_builder.can_build() && self.has_all_peaks()
+    }
+
+    /// Parses the line and updates the builder.
+    ///
+    /// # Arguments
+    /// * `line` - The line to parse.
+    ///
+    /// # Errors
+    /// * If a field cannot be parsed.
+    /// * If the `Num Peaks` field declares an empty spectrum.
+    /// * If more peaks than declared by `Num Peaks` are provided.
+    /// * If a field appears after the peaks of the entry have started.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut builder = MSPBuilder::<usize, f64>::default();
+    ///
+    /// for line in [
+    ///     "NAME: 1",
+    ///     "PRECURSORMZ: 381.0795",
+    ///     "CHARGE: 1",
+    ///     "RETENTIONTIME: 37.083",
+    ///     "Num Peaks: 2",
+    ///     "60.5425\t240000",
+    ///     "119.0857 330000",
+    /// ] {
+    ///     builder.digest_line(line).unwrap();
+    /// }
+    ///
+    /// assert!(builder.can_build());
+    /// assert!(builder.digest_line("120.0 10.0").is_err());
+    ///
+    /// let mascot_generic_format = builder.build().unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format.feature_id(), 1);
+    /// assert_eq!(mascot_generic_format.precursor_mz(), 381.0795);
+    /// assert_eq!(
+    ///     mascot_generic_format.get_second_fragmentation_level().unwrap().fragment_intensities(),
+    ///     &[240000.0, 330000.0]
+    /// );
+    /// ```
+    fn digest_line(&mut self, line: &str) -> Result<(), String> {
+        self.number_of_digested_lines += 1;
+
+        if let Some((key, value)) = line.split_once(':') {
+            if self.number_of_peaks > 0 {
+                return Err(format!(
+                    concat!(
+                        "Encountered the field line \"{}\" after the peaks ",
+                        "of the current entry, which must come last."
+                    ),
+                    line
+                ));
+            }
+            let value = value.trim();
+            return match key.trim().to_uppercase().as_str() {
+                "NAME" => {
+                    // The name is used as the feature ID only when it is
+                    // a valid one, as most libraries store compound names,
+                    // which are kept as extra field instead.
+                    if value.parse::<I>().is_ok() {
+                        self.metadata_builder
+                            .digest_line(&format!("FEATURE_ID={}", value))
+                    } else {
+                        self.metadata_builder.digest_extra("NAME", value)
+                    }
+                }
+                "FEATURE_ID" => self
+                    .metadata_builder
+                    .digest_line(&format!("FEATURE_ID={}", value)),
+                "PRECURSORMZ" => self
+                    .metadata_builder
+                    .digest_line(&format!("PEPMASS={}", value)),
+                "CHARGE" => self
+                    .metadata_builder
+                    .digest_line(&format!("CHARGE={}", value)),
+                "PRECURSOR_TYPE" | "PRECURSORTYPE" => {
+                    if self.options.infers_charge_from_adduct() {
+                        self.metadata_builder
+                            .digest_line(&format!("ADDUCT={}", value))
+                    } else {
+                        self.metadata_builder.digest_extra("ADDUCT", value)
+                    }
+                }
+                "ION_MODE" | "IONMODE" => {
+                    let ion_mode = match value.to_uppercase().as_str() {
+                        "P" => "Positive",
+                        "N" => "Negative",
+                        _ => value,
+                    };
+                    self.metadata_builder
+                        .digest_line(&format!("IONMODE={}", ion_mode))
+                }
+                "SYNON" => {
+                    self.synonyms.push(value.to_string());
+                    Ok(())
+                }
+                key if NIST_EXTRA_FIELDS.contains(&key) => {
+                    self.metadata_builder.digest_extra(key, value)
+                }
+                "RETENTIONTIME" | "RTINSECONDS" => self
+                    .metadata_builder
+                    .digest_line(&format!("RTINSECONDS={}", value)),
+                "FILENAME" => self
+                    .metadata_builder
+                    .digest_line(&format!("FILENAME={}", value)),
+                "NUM PEAKS" => {
+                    let expected_number_of_peaks = value.parse::<usize>().map_err(|_| {
+                        format!(
+                            "Could not parse the number of peaks from the `Num Peaks` line: {}",
+                            line
+                        )
+                    })?;
+                    if expected_number_of_peaks == 0 {
+                        return Err(format!(
+                            concat!(
+                                "The `Num Peaks` line \"{}\" declares an empty spectrum, ",
+                                "while entries must provide at least one peak."
+                            ),
+                            line
+                        ));
+                    }
+                    self.expected_number_of_peaks = Some(expected_number_of_peaks);
+                    Ok(())
+                }
+                key => self
+                    .metadata_builder
+                    .digest_line(&format!("{}={}", key, value)),
+            };
+        }
+
+        let expected_number_of_peaks = self.expected_number_of_peaks.ok_or_else(|| {
+            format!(
+                "Encountered the peak line \"{}\" before the `Num Peaks` field.",
+                line
+            )
+        })?;
+
+        if self.number_of_peaks == expected_number_of_peaks {
+            return Err(format!(
+                concat!(
+                    "The peak line \"{}\" exceeds the {} peaks declared ",
+                    "by the `Num Peaks` field of the current entry."
+                ),
+                line, expected_number_of_peaks
+            ));
+        }
+
+        let mut tokens = line.split_whitespace();
+        let mass_divided_by_charge_ratio = tokens.next().unwrap_or_default();
+        let fragment_intensity = tokens.next().unwrap_or_default();
+        self.data_builder.digest_line(&format!(
+            "{} {}",
+            mass_divided_by_charge_ratio, fragment_intensity
+        ))?;
+        self.number_of_peaks += 1;
+
+        // Once the peaks are complete, no other field can follow.
+        if self.has_all_peaks() {
+            if !self.synonyms.is_empty() {
+                self.metadata_builder
+                    .digest_extra("SYNON", &self.synonyms.join("; "))?;
+            }
+            self.metadata_builder
+                .set_missing_retention_time_and_charge(F::ZERO, Charge::One);
+        }
+
+        Ok(())
+    }
+}
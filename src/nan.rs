@@ -13,4 +13,12 @@ impl NaN for f64 {
     fn is_nan(&self) -> bool {
         f64::is_nan(*self)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "rust_decimal")]
+impl NaN for rust_decimal::Decimal {
+    /// Decimals always represent a number.
+    fn is_nan(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,46 @@
+use std::{
+    fmt::Debug,
+    ops::{Add, Mul, Sub},
+    str::FromStr,
+};
+
+use crate::prelude::*;
+
+/// Trait for the floating point types that entries can be parsed into.
+///
+/// It gathers the bounds required by the readers of the crate, and is
+/// implemented for every type satisfying them, such as `f32`, `f64` and,
+/// with the `rust_decimal` feature, `rust_decimal::Decimal`.
+pub trait ParsableFloat:
+    Copy
+    + StrictlyPositive
+    + FromStr
+    + PartialEq
+    + Debug
+    + PartialOrd
+    + NaN
+    + Sub<Self, Output = Self>
+    + Add<Self, Output = Self>
+    + ApproxEq
+    + Mul<Self, Output = Self>
+    + From<u8>
+    + Zero
+{
+}
+
+impl<F> ParsableFloat for F where
+    F: Copy
+        + StrictlyPositive
+        + FromStr
+        + PartialEq
+        + Debug
+        + PartialOrd
+        + NaN
+        + Sub<F, Output = F>
+        + Add<F, Output = F>
+        + ApproxEq
+        + Mul<F, Output = F>
+        + From<u8>
+        + Zero
+{
+}
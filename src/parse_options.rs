@@ -0,0 +1,683 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Options controlling how the MGF builders react to unusual lines.
+///
+/// The default options are strict, i.e. any line that is not recognized
+/// by the builders is treated as a corruption of the current entry.
+pub struct ParseOptions {
+    strict: bool,
+    require_sorted_level_one: bool,
+    allow_comments: bool,
+    allow_zero_values: bool,
+    allow_independent_scan_numbers: bool,
+    reject_duplicate_mz: bool,
+    sort_unsorted_peaks: bool,
+    european_decimals: bool,
+    validate_precursor: bool,
+    preserve_field_order: bool,
+    validate_smiles: bool,
+    ion_mode_conflict: ConflictPolicy,
+    infer_charge_from_adduct: bool,
+    derive_missing_parent_ion_mass: bool,
+    // The tolerance is stored as the bits of the `f64`, so that the
+    // options, and the builders holding them, can implement `Eq` and `Hash`.
+    relative_tolerance_bits: u64,
+    precursor_tolerance_bits: u64,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            strict: true,
+            require_sorted_level_one: false,
+            allow_comments: false,
+            allow_zero_values: false,
+            allow_independent_scan_numbers: false,
+            reject_duplicate_mz: false,
+            sort_unsorted_peaks: false,
+            european_decimals: false,
+            validate_precursor: true,
+            preserve_field_order: false,
+            validate_smiles: false,
+            ion_mode_conflict: ConflictPolicy::Error,
+            infer_charge_from_adduct: false,
+            derive_missing_parent_ion_mass: false,
+            relative_tolerance_bits: DEFAULT_RELATIVE_TOLERANCE.to_bits(),
+            precursor_tolerance_bits: DEFAULT_RELATIVE_TOLERANCE.to_bits(),
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Returns lenient parse options.
+    ///
+    /// In lenient mode, unrecognized `KEY=VALUE` metadata lines are collected
+    /// into the extra metadata instead of raising an error, comment lines
+    /// are ignored, peaks that are not sorted by m/z are sorted, and scan
+    /// numbers independent of the feature IDs are accepted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// assert!(ParseOptions::default().is_strict());
+    /// assert!(!ParseOptions::lenient().is_strict());
+    /// assert!(ParseOptions::lenient().allows_comments());
+    /// assert!(ParseOptions::lenient().sorts_unsorted_peaks());
+    /// assert!(ParseOptions::lenient().allows_independent_scan_numbers());
+    /// ```
+    pub fn lenient() -> Self {
+        Self::default()
+            .with_strict(false)
+            .with_allow_comments(true)
+            .with_sort_unsorted_peaks(true)
+            .with_allow_independent_scan_numbers(true)
+    }
+
+    /// Sets whether the parsing should be strict.
+    ///
+    /// # Arguments
+    /// * `strict` - Whether unrecognized lines should raise an error.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Returns whether the parsing is strict.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Sets whether the peaks of level-one spectra must be sorted by m/z.
+    ///
+    /// The peaks of level-two spectra are always required to be sorted,
+    /// while the ones of level-one spectra are only checked when this
+    /// option is enabled.
+    ///
+    /// # Arguments
+    /// * `require_sorted_level_one` - Whether unsorted level-one peaks should raise an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut parser = MascotGenericFormatDataBuilder::<f64>::default();
+    /// parser.digest_line("MSLEVEL=1").unwrap();
+    /// parser.digest_line("119.0857 3.3E5").unwrap();
+    /// parser.digest_line("60.5425 2.4E5").unwrap();
+    ///
+    /// let mut parser = MascotGenericFormatDataBuilder::<f64>::with_options(
+    ///     ParseOptions::default().with_require_sorted_level_one(true)
+    /// );
+    /// parser.digest_line("MSLEVEL=1").unwrap();
+    /// parser.digest_line("119.0857 3.3E5").unwrap();
+    /// assert!(parser.digest_line("60.5425 2.4E5").is_err());
+    /// ```
+    pub fn with_require_sorted_level_one(mut self, require_sorted_level_one: bool) -> Self {
+        self.require_sorted_level_one = require_sorted_level_one;
+        self
+    }
+
+    /// Returns whether the peaks of level-one spectra must be sorted by m/z.
+    pub fn requires_sorted_level_one(&self) -> bool {
+        self.require_sorted_level_one
+    }
+
+    /// Sets whether comment lines, i.e. lines starting with `#` or `;`, are ignored.
+    ///
+    /// When comments are not allowed, comment lines raise an error.
+    ///
+    /// # Arguments
+    /// * `allow_comments` - Whether comment lines should be ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut parser = MascotGenericFormatBuilder::<usize, f64>::default();
+    /// parser.digest_line("BEGIN IONS").unwrap();
+    /// assert!(parser.digest_line("# Exported by some tool").is_err());
+    ///
+    /// let mut parser = MascotGenericFormatBuilder::<usize, f64>::with_options(
+    ///     ParseOptions::default().with_allow_comments(true)
+    /// );
+    /// parser.digest_line("BEGIN IONS").unwrap();
+    /// parser.digest_line("# Exported by some tool").unwrap();
+    /// parser.digest_line("; Another comment").unwrap();
+    /// ```
+    pub fn with_allow_comments(mut self, allow_comments: bool) -> Self {
+        self.allow_comments = allow_comments;
+        self
+    }
+
+    /// Returns whether comment lines are ignored.
+    pub fn allows_comments(&self) -> bool {
+        self.allow_comments
+    }
+
+    /// Sets whether the `SCANS` field may hold a scan number independent of the feature ID.
+    ///
+    /// By default, the `SCANS` field of the last section of an entry must be
+    /// equal to its feature ID, or `-1`. Some documents number their scans
+    /// independently, and when this option is enabled a differing value is
+    /// stored as the scan number of the entry, see
+    /// [`MascotGenericFormatMetadata::scan_number`]. An entry without a
+    /// `FEATURE_ID` field then takes its scan number as feature ID.
+    ///
+    /// When reading documents with [`MGFVec::try_from_iter_with_string_feature_ids_and_options`],
+    /// the `SCANS` values are mapped to integers together with the feature IDs,
+    /// so that the scan numbers are also indices into the returned strings.
+    ///
+    /// # Arguments
+    /// * `allow_independent_scan_numbers` - Whether the scan numbers may differ from the feature IDs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::default();
+    /// parser.digest_line("FEATURE_ID=10").unwrap();
+    /// assert!(parser.digest_line("SCANS=42").is_err());
+    ///
+    /// let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::with_options(
+    ///     ParseOptions::default().with_allow_independent_scan_numbers(true)
+    /// );
+    /// parser.digest_line("FEATURE_ID=10").unwrap();
+    /// parser.digest_line("SCANS=42").unwrap();
+    ///
+    /// let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::with_options(
+    ///     ParseOptions::lenient().with_allow_independent_scan_numbers(false)
+    /// );
+    /// parser.digest_line("FEATURE_ID=10").unwrap();
+    /// assert!(parser.digest_line("SCANS=42").is_err());
+    /// ```
+    pub fn with_allow_independent_scan_numbers(
+        mut self,
+        allow_independent_scan_numbers: bool,
+    ) -> Self {
+        self.allow_independent_scan_numbers = allow_independent_scan_numbers;
+        self
+    }
+
+    /// Returns whether the `SCANS` field may hold a scan number independent of the feature ID.
+    pub fn allows_independent_scan_numbers(&self) -> bool {
+        self.allow_independent_scan_numbers
+    }
+
+    /// Sets whether zero retention times and zero fragment intensities are accepted.
+    ///
+    /// Some documents legitimately carry a `RTINSECONDS=0.0`, such as the one
+    /// of the injection peak, or zero-intensity placeholder peaks. Negative
+    /// values are always rejected, and the parent ion mass and the mass divided
+    /// by charge ratios of the peaks must always be strictly positive.
+    ///
+    /// # Arguments
+    /// * `allow_zero_values` - Whether zero retention times and intensities should be accepted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut parser = MascotGenericFormatDataBuilder::<f64>::default();
+    /// parser.digest_line("MSLEVEL=2").unwrap();
+    /// assert!(parser.digest_line("60.5425 0.0").is_err());
+    ///
+    /// let mut parser = MascotGenericFormatDataBuilder::<f64>::with_options(
+    ///     ParseOptions::default().with_allow_zero_values(true)
+    /// );
+    /// parser.digest_line("MSLEVEL=2").unwrap();
+    /// parser.digest_line("60.5425 0.0").unwrap();
+    /// assert!(parser.digest_line("119.0857 -1.0").is_err());
+    /// assert!(parser.digest_line("0.0 3.3E5").is_err());
+    /// ```
+    pub fn with_allow_zero_values(mut self, allow_zero_values: bool) -> Self {
+        self.allow_zero_values = allow_zero_values;
+        self
+    }
+
+    /// Returns whether zero retention times and zero fragment intensities are accepted.
+    pub fn allows_zero_values(&self) -> bool {
+        self.allow_zero_values
+    }
+
+    /// Sets whether spectra with exactly duplicated mass divided by charge ratios are rejected.
+    ///
+    /// This complements the check on the order of the peaks, which accepts
+    /// consecutive peaks with the same mass divided by charge ratio.
+    ///
+    /// # Arguments
+    /// * `reject_duplicate_mz` - Whether duplicated mass divided by charge ratios should raise an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut parser = MascotGenericFormatDataBuilder::<f64>::default();
+    /// parser.digest_line("MSLEVEL=2").unwrap();
+    /// parser.digest_line("60.5425 2.4E5").unwrap();
+    /// parser.digest_line("60.5425 2.1E4").unwrap();
+    /// assert!(parser.build().is_ok());
+    ///
+    /// let mut parser = MascotGenericFormatDataBuilder::<f64>::with_options(
+    ///     ParseOptions::default().with_reject_duplicate_mz(true)
+    /// );
+    /// parser.digest_line("MSLEVEL=2").unwrap();
+    /// parser.digest_line("60.5425 2.4E5").unwrap();
+    /// parser.digest_line("60.5425 2.1E4").unwrap();
+    /// assert!(parser.build().is_err());
+    /// ```
+    pub fn with_reject_duplicate_mz(mut self, reject_duplicate_mz: bool) -> Self {
+        self.reject_duplicate_mz = reject_duplicate_mz;
+        self
+    }
+
+    /// Returns whether spectra with exactly duplicated mass divided by charge ratios are rejected.
+    pub fn rejects_duplicate_mz(&self) -> bool {
+        self.reject_duplicate_mz
+    }
+
+    /// Sets whether peaks that are not sorted by m/z are sorted when the spectrum
+    /// is built, instead of raising an error.
+    ///
+    /// This applies to the peaks required to be sorted, i.e. the ones of level-two
+    /// spectra, and the ones of level-one spectra when
+    /// [`with_require_sorted_level_one`](Self::with_require_sorted_level_one) is set.
+    /// A warning is raised for each sorted spectrum, which is collected in the
+    /// [`ParseReport`] returned by [`MGFVec::try_from_iter_with_report`].
+    ///
+    /// # Arguments
+    /// * `sort_unsorted_peaks` - Whether unsorted peaks should be sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut parser = MascotGenericFormatDataBuilder::<f64>::with_options(
+    ///     ParseOptions::default().with_sort_unsorted_peaks(true)
+    /// );
+    /// parser.digest_line("MSLEVEL=2").unwrap();
+    /// parser.digest_line("119.0857 3.3E5").unwrap();
+    /// parser.digest_line("60.5425 2.4E5").unwrap();
+    ///
+    /// assert_eq!(parser.warnings().len(), 1);
+    ///
+    /// let mascot_generic_format_data = parser.build().unwrap();
+    ///
+    /// assert_eq!(mascot_generic_format_data.mass_divided_by_charge_ratios(), &[60.5425, 119.0857]);
+    /// assert_eq!(mascot_generic_format_data.fragment_intensities(), &[2.4E5, 3.3E5]);
+    /// ```
+    pub fn with_sort_unsorted_peaks(mut self, sort_unsorted_peaks: bool) -> Self {
+        self.sort_unsorted_peaks = sort_unsorted_peaks;
+        self
+    }
+
+    /// Returns whether peaks that are not sorted by m/z are sorted instead of raising an error.
+    pub fn sorts_unsorted_peaks(&self) -> bool {
+        self.sort_unsorted_peaks
+    }
+
+    /// Sets whether a comma is accepted as the decimal separator of the numeric values.
+    ///
+    /// Documents exported with a European locale may write values such as
+    /// `PEPMASS=381,0795`. When enabled, a single comma in the parent ion mass,
+    /// the retention time and the peak values is read as a decimal separator,
+    /// while values with more than one comma or with a dot are left untouched.
+    ///
+    /// # Arguments
+    /// * `european_decimals` - Whether commas should be read as decimal separators.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut parser = MascotGenericFormatDataBuilder::<f64>::default();
+    /// parser.digest_line("MSLEVEL=2").unwrap();
+    /// assert!(parser.digest_line("60,5425 2,4E5").is_err());
+    ///
+    /// let mut parser = MascotGenericFormatDataBuilder::<f64>::with_options(
+    ///     ParseOptions::default().with_european_decimals(true)
+    /// );
+    /// parser.digest_line("MSLEVEL=2").unwrap();
+    /// parser.digest_line("60,5425 2,4E5").unwrap();
+    ///
+    /// assert_eq!(parser.build().unwrap().mass_divided_by_charge_ratios(), &[60.5425]);
+    /// ```
+    pub fn with_european_decimals(mut self, european_decimals: bool) -> Self {
+        self.european_decimals = european_decimals;
+        self
+    }
+
+    /// Returns whether a comma is accepted as the decimal separator of the numeric values.
+    pub fn uses_european_decimals(&self) -> bool {
+        self.european_decimals
+    }
+
+    /// Sets whether the parent ion mass should be validated against the first fragmentation level.
+    ///
+    /// By default, when an entry contains a first fragmentation level, its
+    /// parent ion mass must match the minimum mass-charge ratio of that level
+    /// within the precursor tolerance of the options. Deconvoluted documents may
+    /// report a parent ion mass that is not among the measured peaks, and such
+    /// entries can be accepted by disabling this validation.
+    ///
+    /// # Arguments
+    /// * `validate_precursor` - Whether the parent ion mass should be validated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// assert!(ParseOptions::default().validates_precursor());
+    /// assert!(!ParseOptions::default()
+    ///     .with_validate_precursor(false)
+    ///     .validates_precursor());
+    /// ```
+    pub fn with_validate_precursor(mut self, validate_precursor: bool) -> Self {
+        self.validate_precursor = validate_precursor;
+        self
+    }
+
+    /// Returns whether the parent ion mass is validated against the first fragmentation level.
+    pub fn validates_precursor(&self) -> bool {
+        self.validate_precursor
+    }
+
+    /// Sets whether the metadata lines of each section should be recorded as
+    /// they appear in the document.
+    ///
+    /// The MGF objects are otherwise written with a fixed order of the fields,
+    /// so that documents written by other tools do not round-trip exactly.
+    /// When enabled, the keys of the `KEY=VALUE` lines of each section are
+    /// recorded, and the fields are written in their original order, with
+    /// their current values, when the entry is written.
+    /// This is disabled by default, as it keeps a copy of every metadata line.
+    ///
+    /// # Arguments
+    /// * `preserve_field_order` - Whether the metadata lines should be recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let document = [
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "MSLEVEL=2",
+    ///     "CHARGE=1+",
+    ///     "RTINSECONDS=37.0830",
+    ///     "PEPMASS=381.0795",
+    ///     "SCANS=1",
+    ///     "60.5425 240000.0000",
+    ///     "END IONS",
+    ///     "",
+    /// ];
+    ///
+    /// let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter_with_options(
+    ///     document,
+    ///     ParseOptions::default().with_preserve_field_order(true),
+    /// ).unwrap();
+    ///
+    /// assert_eq!(
+    ///     mascot_generic_formats[0].to_mgf_string(DEFAULT_MGF_PRECISION),
+    ///     document.join("\n") + "\n"
+    /// );
+    /// ```
+    pub fn with_preserve_field_order(mut self, preserve_field_order: bool) -> Self {
+        self.preserve_field_order = preserve_field_order;
+        self
+    }
+
+    /// Returns whether the metadata lines of each section are recorded as they appear in the document.
+    pub fn preserves_field_order(&self) -> bool {
+        self.preserve_field_order
+    }
+
+    /// Sets whether the `SMILES` field should be checked to look like a SMILES string.
+    ///
+    /// The check only looks at the characters used by the value and at the
+    /// balance of its branches and bracket atoms, so that clearly malformed
+    /// values, such as free text, are rejected without validating the
+    /// chemistry of the structure. It is disabled by default, so that
+    /// unusual but valid SMILES are never rejected.
+    ///
+    /// When enabled, the `SMILES` field is accepted also by strict parse
+    /// options and kept among the extra metadata.
+    ///
+    /// # Arguments
+    /// * `validate_smiles` - Whether the `SMILES` field should be checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// for options in [ParseOptions::default(), ParseOptions::lenient()] {
+    ///     let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::with_options(
+    ///         options.with_validate_smiles(true)
+    ///     );
+    ///
+    ///     assert!(parser.digest_line("SMILES=C[C@@H](C(=O)O)[NH3+]").is_ok());
+    ///     assert!(parser.digest_line("SMILES=see supplementary table").is_err());
+    /// }
+    ///
+    /// let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::default();
+    /// assert!(parser.digest_line("SMILES=C[C@@H](C(=O)O)[NH3+]").is_err());
+    /// ```
+    pub fn with_validate_smiles(mut self, validate_smiles: bool) -> Self {
+        self.validate_smiles = validate_smiles;
+        self
+    }
+
+    /// Returns whether the `SMILES` field is checked to look like a SMILES string.
+    pub fn validates_smiles(&self) -> bool {
+        self.validate_smiles
+    }
+
+    /// Sets how an `IONMODE` field repeated with a different value within an
+    /// entry is handled.
+    ///
+    /// Merged documents may report conflicting polarities in the different
+    /// sections of an entry. The same policy applies to the `SOURCE_INSTRUMENT`
+    /// and `ORGANISM` fields, which are collected into the extra metadata in
+    /// lenient mode. By default, conflicting values raise an error.
+    ///
+    /// # Arguments
+    /// * `ion_mode_conflict` - The policy to apply to conflicting values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::with_options(
+    ///     ParseOptions::default().with_ion_mode_conflict(ConflictPolicy::KeepLast)
+    /// );
+    ///
+    /// parser.digest_line("IONMODE=Negative").unwrap();
+    /// parser.digest_line("IONMODE=Positive").unwrap();
+    /// ```
+    pub fn with_ion_mode_conflict(mut self, ion_mode_conflict: ConflictPolicy) -> Self {
+        self.ion_mode_conflict = ion_mode_conflict;
+        self
+    }
+
+    /// Returns how an `IONMODE`, `SOURCE_INSTRUMENT` or `ORGANISM` field
+    /// repeated with a different value within an entry is handled.
+    pub fn ion_mode_conflict(&self) -> ConflictPolicy {
+        self.ion_mode_conflict
+    }
+
+    /// Sets whether the charge should be inferred from the `ADDUCT` field,
+    /// such as `[M-H]-`, when the `CHARGE` field is absent.
+    ///
+    /// When enabled, the `ADDUCT` field is accepted also by strict parse
+    /// options and kept among the extra metadata. An explicit `CHARGE` field
+    /// remains authoritative, and an adduct implying a different charge is
+    /// reported as an error. It is disabled by default.
+    ///
+    /// # Arguments
+    /// * `infer_charge_from_adduct` - Whether the charge should be inferred from the adduct.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let mut parser = MascotGenericFormatMetadataBuilder::<usize, f64>::with_options(
+    ///     ParseOptions::default().with_infer_charge_from_adduct(true)
+    /// );
+    ///
+    /// for line in ["FEATURE_ID=1", "PEPMASS=381.0795", "RTINSECONDS=37.083", "ADDUCT=[M-H]-"] {
+    ///     parser.digest_line(line).unwrap();
+    /// }
+    ///
+    /// assert_eq!(parser.build().unwrap().charge(), Charge::OneMinus);
+    /// ```
+    pub fn with_infer_charge_from_adduct(mut self, infer_charge_from_adduct: bool) -> Self {
+        self.infer_charge_from_adduct = infer_charge_from_adduct;
+        self
+    }
+
+    /// Returns whether the charge is inferred from the `ADDUCT` field when
+    /// the `CHARGE` field is absent.
+    pub fn infers_charge_from_adduct(&self) -> bool {
+        self.infer_charge_from_adduct
+    }
+
+    /// Sets whether a missing `PEPMASS` field is derived from the first fragmentation level.
+    ///
+    /// When enabled, an entry without a `PEPMASS` field but with a first
+    /// fragmentation level takes the minimum mass divided by charge ratio of
+    /// that level as its parent ion mass. It is disabled by default, so that
+    /// an entry without a `PEPMASS` field is reported as an error.
+    ///
+    /// # Arguments
+    /// * `derive_missing_parent_ion_mass` - Whether a missing parent ion mass should be derived.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let lines = [
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "MSLEVEL=1",
+    ///     "CHARGE=1+",
+    ///     "RTINSECONDS=37.083",
+    ///     "381.0795 7.8E8",
+    ///     "420.5 3.3E5",
+    ///     "END IONS",
+    /// ];
+    ///
+    /// let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::default();
+    /// mascot_generic_format_builder.digest_lines(lines).unwrap();
+    /// assert!(!mascot_generic_format_builder.can_build());
+    ///
+    /// let mascot_generic_formats = MGFVec::<usize, f64>::try_from_iter_with_options(
+    ///     lines,
+    ///     ParseOptions::default().with_derive_missing_parent_ion_mass(true),
+    /// )
+    /// .unwrap();
+    /// assert_eq!(mascot_generic_formats[0].precursor_mz(), 381.0795);
+    /// ```
+    pub fn with_derive_missing_parent_ion_mass(
+        mut self,
+        derive_missing_parent_ion_mass: bool,
+    ) -> Self {
+        self.derive_missing_parent_ion_mass = derive_missing_parent_ion_mass;
+        self
+    }
+
+    /// Returns whether a missing `PEPMASS` field is derived from the first fragmentation level.
+    pub fn derives_missing_parent_ion_mass(&self) -> bool {
+        self.derive_missing_parent_ion_mass
+    }
+
+    /// Returns the relative tolerance used to validate the parent ion mass, if the validation is enabled.
+    pub(crate) fn validated_precursor_tolerance(&self) -> Option<f64> {
+        if self.validate_precursor {
+            Some(self.precursor_tolerance())
+        } else {
+            None
+        }
+    }
+
+    /// Sets the relative tolerance used to validate the parent ion mass
+    /// against the minimum mass-charge ratio of the first fragmentation level.
+    ///
+    /// This tolerance is independent from the one used to compare repeated
+    /// fields, as the parent ion mass of some exporters only approximates the
+    /// measured peak. It has no effect when the validation is disabled.
+    ///
+    /// # Arguments
+    /// * `precursor_tolerance` - The relative tolerance of the validation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let lines = [
+    ///     "BEGIN IONS",
+    ///     "FEATURE_ID=1",
+    ///     "PEPMASS=381.0795",
+    ///     "CHARGE=1",
+    ///     "RTINSECONDS=37.083",
+    ///     "MSLEVEL=1",
+    ///     "381.0800 7.8E8",
+    ///     "END IONS",
+    /// ];
+    ///
+    /// assert!(MGFVec::<usize, f64>::try_from_iter(lines).is_err());
+    ///
+    /// let options = ParseOptions::default().with_precursor_tolerance(1e-5);
+    /// assert_eq!(options.precursor_tolerance(), 1e-5);
+    /// assert_eq!(options.relative_tolerance(), DEFAULT_RELATIVE_TOLERANCE);
+    /// assert_eq!(
+    ///     MGFVec::<usize, f64>::try_from_iter_with_options(lines, options)
+    ///         .unwrap()
+    ///         .len(),
+    ///     1
+    /// );
+    /// ```
+    pub fn with_precursor_tolerance(mut self, precursor_tolerance: f64) -> Self {
+        self.precursor_tolerance_bits = precursor_tolerance.to_bits();
+        self
+    }
+
+    /// Returns the relative tolerance used to validate the parent ion mass.
+    pub fn precursor_tolerance(&self) -> f64 {
+        f64::from_bits(self.precursor_tolerance_bits)
+    }
+
+    /// Sets the relative tolerance used to compare the values of repeated fields.
+    ///
+    /// Fields such as `PEPMASS` and `RTINSECONDS` are repeated in each section
+    /// of an entry, possibly written with a different number of decimals, and
+    /// their values are considered in conflict only when they differ by more
+    /// than this tolerance.
+    ///
+    /// # Arguments
+    /// * `relative_tolerance` - The relative tolerance of the comparisons.
+    pub fn with_relative_tolerance(mut self, relative_tolerance: f64) -> Self {
+        self.relative_tolerance_bits = relative_tolerance.to_bits();
+        self
+    }
+
+    /// Returns the relative tolerance used to compare the values of repeated fields.
+    pub fn relative_tolerance(&self) -> f64 {
+        f64::from_bits(self.relative_tolerance_bits)
+    }
+}
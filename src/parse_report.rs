@@ -0,0 +1,34 @@
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The warnings raised while parsing a document.
+///
+/// Warnings are raised when the parse options allow to recover from an
+/// irregularity of the document, such as peaks that are not sorted by m/z,
+/// which would otherwise be an error.
+pub struct ParseReport {
+    warnings: Vec<String>,
+}
+
+impl ParseReport {
+    /// Creates a new empty [`ParseReport`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a warning to the report.
+    ///
+    /// # Arguments
+    /// * `warning` - The warning to add.
+    pub fn push_warning(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    /// Returns the warnings of the report, in the order they were raised.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Returns whether any warning was raised.
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+}
@@ -0,0 +1,53 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A single peak of a fragmentation spectrum.
+///
+/// The peaks of a [`MascotGenericFormatData`](crate::prelude::MascotGenericFormatData)
+/// are stored column-wise, and this struct only provides a convenient row-wise view.
+pub struct Peak<F> {
+    mz: F,
+    intensity: F,
+}
+
+impl<F: Copy> Peak<F> {
+    /// Creates a new [`Peak`].
+    ///
+    /// # Arguments
+    /// * `mz` - The mass divided by charge ratio of the peak.
+    /// * `intensity` - The intensity of the peak.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let peak = Peak::new(60.5425, 2.4E5);
+    ///
+    /// assert_eq!(peak.mz(), 60.5425);
+    /// assert_eq!(peak.intensity(), 2.4E5);
+    /// ```
+    pub fn new(mz: F, intensity: F) -> Self {
+        Self { mz, intensity }
+    }
+
+    /// Returns the mass divided by charge ratio of the peak.
+    pub fn mz(&self) -> F {
+        self.mz
+    }
+
+    /// Returns the intensity of the peak.
+    pub fn intensity(&self) -> F {
+        self.intensity
+    }
+}
+
+impl<F> From<(F, F)> for Peak<F> {
+    fn from((mz, intensity): (F, F)) -> Self {
+        Self { mz, intensity }
+    }
+}
+
+impl<F> From<Peak<F>> for (F, F) {
+    fn from(peak: Peak<F>) -> Self {
+        (peak.mz, peak.intensity)
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    cmp::Ordering,
+    ops::{Add, Sub},
+};
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone)]
+/// Index of the entries of an [`MGFVec`](crate::prelude::MGFVec) sorted by parent ion mass.
+///
+/// The index allows to retrieve, by binary search, the positions of the
+/// entries whose parent ion mass lies within a tolerance of a query mass.
+pub struct PrecursorIndex<F> {
+    parent_ion_masses: Vec<F>,
+    indices: Vec<usize>,
+}
+
+impl<F> PrecursorIndex<F>
+where
+    F: Copy + PartialOrd + Add<F, Output = F> + Sub<F, Output = F>,
+{
+    /// Creates a new [`PrecursorIndex`] from the parent ion masses of the entries.
+    ///
+    /// # Arguments
+    /// * `parent_ion_masses` - The parent ion masses, in the order of the entries.
+    ///
+    /// # Errors
+    /// * If any of the parent ion masses is NaN, as it could not be sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// assert!(PrecursorIndex::new([300.0, f64::NAN, 100.0]).is_err());
+    /// ```
+    pub fn new<T>(parent_ion_masses: T) -> Result<Self, String>
+    where
+        T: IntoIterator<Item = F>,
+        F: NaN,
+    {
+        let parent_ion_masses = parent_ion_masses.into_iter().collect::<Vec<F>>();
+        if let Some(index) = parent_ion_masses
+            .iter()
+            .position(|parent_ion_mass| parent_ion_mass.is_nan())
+        {
+            return Err(format!(
+                "Could not create PrecursorIndex: the parent ion mass at position {} is NaN.",
+                index
+            ));
+        }
+        Ok(Self::from_comparable(parent_ion_masses))
+    }
+
+    /// Creates a new [`PrecursorIndex`] from parent ion masses known not to be NaN,
+    /// such as the ones of [`MascotGenericFormat`] objects, which are strictly positive.
+    ///
+    /// # Arguments
+    /// * `parent_ion_masses` - The parent ion masses, in the order of the entries.
+    pub(crate) fn from_comparable<T>(parent_ion_masses: T) -> Self
+    where
+        T: IntoIterator<Item = F>,
+    {
+        let mut pairs = parent_ion_masses
+            .into_iter()
+            .enumerate()
+            .map(|(index, parent_ion_mass)| (parent_ion_mass, index))
+            .collect::<Vec<(F, usize)>>();
+        // Without NaN values, any two parent ion masses are comparable.
+        pairs.sort_by(|(left, _), (right, _)| left.partial_cmp(right).unwrap_or(Ordering::Equal));
+        let (parent_ion_masses, indices) = pairs.into_iter().unzip();
+        Self {
+            parent_ion_masses,
+            indices,
+        }
+    }
+
+    /// Returns the number of indexed entries.
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Returns whether the index is empty.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Returns the positions of the entries whose parent ion mass lies within
+    /// `[mz - tolerance, mz + tolerance]`, sorted by increasing parent ion mass.
+    ///
+    /// # Arguments
+    /// * `mz` - The mass to query.
+    /// * `tolerance` - The tolerance around the mass to query.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mascot_rs::prelude::*;
+    ///
+    /// let index = PrecursorIndex::new([300.0, 100.0, 200.05, 199.98, 500.0]).unwrap();
+    ///
+    /// assert_eq!(index.len(), 5);
+    /// assert_eq!(index.candidates(200.0, 0.1), &[3, 2]);
+    /// assert_eq!(index.candidates(200.0, 0.01), &[] as &[usize]);
+    /// assert_eq!(index.candidates(100.0, 0.0), &[1]);
+    /// assert_eq!(index.candidates(350.0, 200.0), &[3, 2, 0, 4]);
+    /// ```
+    pub fn candidates(&self, mz: F, tolerance: F) -> &[usize] {
+        let lower_bound = mz - tolerance;
+        let upper_bound = mz + tolerance;
+        let start = self
+            .parent_ion_masses
+            .partition_point(|&parent_ion_mass| parent_ion_mass < lower_bound);
+        let end = self
+            .parent_ion_masses
+            .partition_point(|&parent_ion_mass| parent_ion_mass <= upper_bound);
+        &self.indices[start..end.max(start)]
+    }
+}
@@ -12,4 +12,11 @@ impl StrictlyPositive for f64 {
     fn is_strictly_positive(&self) -> bool {
         *self > 0.0
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "rust_decimal")]
+impl StrictlyPositive for rust_decimal::Decimal {
+    fn is_strictly_positive(&self) -> bool {
+        *self > rust_decimal::Decimal::ZERO
+    }
+}
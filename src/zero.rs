@@ -24,4 +24,17 @@ impl Zero for u128 {
 
 impl Zero for usize {
     const ZERO: Self = 0;
-}
\ No newline at end of file
+}
+
+impl Zero for f32 {
+    const ZERO: Self = 0.0;
+}
+
+impl Zero for f64 {
+    const ZERO: Self = 0.0;
+}
+
+#[cfg(feature = "rust_decimal")]
+impl Zero for rust_decimal::Decimal {
+    const ZERO: Self = rust_decimal::Decimal::ZERO;
+}
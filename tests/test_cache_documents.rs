@@ -0,0 +1,47 @@
+//! Test that parsed documents can be cached and reloaded.
+#![cfg(feature = "serde")]
+use mascot_rs::prelude::*;
+
+#[test]
+fn test_read_mgf_documents_through_cache() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    let cache_path =
+        std::env::temp_dir().join("mascot_rs_test_read_mgf_documents_through_cache.bin");
+    let cache_path = cache_path.to_str().unwrap();
+    let _ = std::fs::remove_file(cache_path);
+
+    let parsed: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+
+    // The first call parses the document and writes the cache.
+    let written: MGFVec<usize, f64> = MGFVec::try_from_path_cached(path, cache_path).unwrap();
+    assert!(std::path::Path::new(cache_path).exists());
+
+    // The second call loads the cache, which is more recent than the document.
+    let cached: MGFVec<usize, f64> = MGFVec::try_from_path_cached(path, cache_path).unwrap();
+
+    for reloaded in [&written, &cached] {
+        assert_eq!(reloaded.len(), parsed.len());
+        for (left, right) in reloaded.iter().zip(parsed.iter()) {
+            assert!(
+                left.approx_eq(right, 1e-9, 1e-9),
+                "{:?} != {:?}",
+                left,
+                right
+            );
+        }
+    }
+
+    std::fs::remove_file(cache_path).unwrap();
+}
+
+#[test]
+fn test_load_invalid_cache() {
+    let cache_path = std::env::temp_dir().join("mascot_rs_test_load_invalid_cache.bin");
+    let cache_path = cache_path.to_str().unwrap();
+    std::fs::write(cache_path, b"not a cache").unwrap();
+
+    assert!(MGFVec::<usize, f64>::load_cache(cache_path).is_err());
+    assert!(MGFVec::<usize, f64>::load_cache("tests/data/missing.bin").is_err());
+
+    std::fs::remove_file(cache_path).unwrap();
+}
@@ -0,0 +1,62 @@
+//! Test that documents can be parsed with exact decimal peaks.
+#![cfg(feature = "rust_decimal")]
+use std::str::FromStr;
+
+use mascot_rs::prelude::*;
+use rust_decimal::Decimal;
+
+#[test]
+fn test_read_mgf_document_with_decimal_peaks() {
+    let mascot_generic_formats: MGFVec<usize, Decimal> = MGFVec::try_from_iter([
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "SPECTYPE=CORRELATED MS",
+        "MSLEVEL=1",
+        "CHARGE=1+",
+        "RTINSECONDS=37.083",
+        "SCANS=-1",
+        "381.0795 7.8E8",
+        "END IONS",
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "MSLEVEL=2",
+        "CHARGE=1+",
+        "RTINSECONDS=37.083",
+        "SCANS=1",
+        "60.5425 2.4E5",
+        "119.0857 3.3E5",
+        "END IONS",
+    ])
+    .unwrap();
+
+    assert_eq!(mascot_generic_formats.len(), 1);
+    let mascot_generic_format = &mascot_generic_formats[0];
+
+    assert_eq!(
+        mascot_generic_format.precursor_mz(),
+        Decimal::from_str("381.0795").unwrap()
+    );
+    assert_eq!(
+        mascot_generic_format.retention_time(),
+        Decimal::from_str("37.083").unwrap()
+    );
+
+    let second_level = mascot_generic_format
+        .get_level(FragmentationSpectraLevel::Two)
+        .unwrap();
+    assert_eq!(
+        second_level.mass_divided_by_charge_ratios(),
+        &[
+            Decimal::from_str("60.5425").unwrap(),
+            Decimal::from_str("119.0857").unwrap()
+        ]
+    );
+    // The sum of the values is exact, unlike with floating point values.
+    assert_eq!(
+        second_level.mass_divided_by_charge_ratios()[0]
+            + second_level.mass_divided_by_charge_ratios()[1],
+        Decimal::from_str("179.6282").unwrap()
+    );
+}
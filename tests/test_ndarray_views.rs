@@ -0,0 +1,30 @@
+//! Test that the ndarray views match the slice accessors of the peaks.
+#![cfg(feature = "ndarray")]
+use mascot_rs::prelude::*;
+
+#[test]
+fn test_ndarray_views() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+
+    for mascot_generic_format in mascot_generic_formats.iter() {
+        for data in mascot_generic_format.levels() {
+            let mz_array = mascot_generic_format.mz_array(data.level()).unwrap();
+            let intensity_array = mascot_generic_format.intensity_array(data.level()).unwrap();
+            let peaks = mascot_generic_format.peaks_array2(data.level()).unwrap();
+
+            assert_eq!(mz_array.len(), data.len());
+            assert_eq!(intensity_array.len(), data.len());
+            assert_eq!(peaks.shape(), &[data.len(), 2]);
+
+            assert_eq!(mz_array[0], data.mass_divided_by_charge_ratios()[0]);
+            assert_eq!(intensity_array[0], data.fragment_intensities()[0]);
+            assert_eq!(peaks[[0, 0]], data.mass_divided_by_charge_ratios()[0]);
+            assert_eq!(peaks[[0, 1]], data.fragment_intensities()[0]);
+            assert_eq!(
+                mz_array.as_slice().unwrap(),
+                data.mass_divided_by_charge_ratios()
+            );
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Tests on the error messages raised while parsing malformed documents.
+use mascot_rs::prelude::*;
+
+#[test]
+fn test_error_reports_line_number_and_feature_id() {
+    let document = "BEGIN IONS
+FEATURE_ID=1
+PEPMASS=381.0795
+CHARGE=1
+
+MSLEVEL=2
+60.5425 -2.4E5
+END IONS
+";
+
+    let error = MGFVec::<usize, f64>::try_from_iter(document.lines()).unwrap_err();
+
+    assert!(error.contains("line 7"), "{}", error);
+    assert!(error.contains("feature ID 1"), "{}", error);
+}
+
+#[test]
+fn test_error_reports_invalid_fragment_charge_column() {
+    let document = "BEGIN IONS
+FEATURE_ID=1
+PEPMASS=381.0795
+CHARGE=1
+MSLEVEL=2
+60.5425 2.4E5 9
+END IONS
+";
+
+    let error = MGFVec::<usize, f64>::try_from_iter(document.lines()).unwrap_err();
+
+    assert!(error.contains("line 6"), "{}", error);
+    assert!(error.contains("fragment charge"), "{}", error);
+}
+
+#[test]
+fn test_digest_lines_stops_at_first_error() {
+    let lines = [
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "60.5425 -2.4E5",
+        "END IONS",
+    ];
+
+    let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::default();
+    assert!(mascot_generic_format_builder.digest_lines(lines).is_err());
+    // The `END IONS` line following the invalid peak was not digested.
+    assert!(mascot_generic_format_builder.is_section_open());
+
+    let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::default();
+    mascot_generic_format_builder
+        .digest_lines(lines.iter().map(|line| line.replace("-2.4E5", "2.4E5")))
+        .unwrap();
+    assert!(mascot_generic_format_builder.can_build());
+}
+
+#[test]
+fn test_zero_charge_is_rejected() {
+    let document = "BEGIN IONS
+FEATURE_ID=1
+PEPMASS=381.0795
+CHARGE=0
+RTINSECONDS=37.083
+MSLEVEL=2
+60.5425 2.4E5
+END IONS
+";
+
+    let error = MGFVec::<usize, f64>::try_from_iter(document.lines()).unwrap_err();
+    assert!(error.contains("CHARGE=0"), "{}", error);
+
+    let options = ParseOptions::default().with_infer_charge_from_adduct(true);
+    let error = MGFVec::<usize, f64>::try_from_iter_with_options(
+        document.replace("CHARGE=0", "ADDUCT=[M]0+").lines(),
+        options,
+    )
+    .unwrap_err();
+    assert!(error.contains("[M]0+"), "{}", error);
+}
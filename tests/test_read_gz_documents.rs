@@ -0,0 +1,51 @@
+//! Test that gzip-compressed documents parse identically to their plain counterparts.
+#![cfg(feature = "flate2")]
+use mascot_rs::prelude::*;
+
+#[test]
+fn test_read_gz_documents() {
+    let plain: MGFVec<usize, f32> =
+        MGFVec::from_path("tests/data/20220513_PMA_DBGI_01_04_003.mgf").unwrap();
+    let compressed: MGFVec<usize, f32> =
+        MGFVec::from_path_auto("tests/data/20220513_PMA_DBGI_01_04_003.mgf.gz").unwrap();
+
+    assert_eq!(plain.len(), compressed.len());
+    for (left, right) in plain.iter().zip(compressed.iter()) {
+        assert_eq!(left.feature_id(), right.feature_id());
+        assert_eq!(left.precursor_mz(), right.precursor_mz());
+    }
+}
+
+#[test]
+fn test_read_gz_document_errors_name_the_path() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    let error = MGFVec::<usize, f32>::try_from_gz_path(path).unwrap_err();
+    assert!(error.starts_with(path), "{}", error);
+
+    let path = "tests/data/missing.mgf.gz";
+    let error = MGFVec::<usize, f32>::try_from_gz_path(path).unwrap_err();
+    assert!(error.starts_with(path), "{}", error);
+}
+
+#[test]
+fn test_read_gz_documents_from_dir() {
+    let dir = std::env::temp_dir().join("mascot_rs_test_read_gz_documents_from_dir");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(
+        "tests/data/20220513_PMA_DBGI_01_04_003.mgf.gz",
+        dir.join("compressed.mgf.gz"),
+    )
+    .unwrap();
+    std::fs::write(dir.join("broken.mgf.gz"), b"not gzip").unwrap();
+
+    let (mascot_generic_formats, errors, report): (MGFVec<usize, f32>, _, _) =
+        MGFVec::try_from_dir_with_report(dir.to_str().unwrap(), ParseOptions::default()).unwrap();
+
+    assert_eq!(mascot_generic_formats.len(), 74);
+    assert!(!report.has_warnings());
+    // The IO error of the stream is prefixed with the path of the file.
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].1.starts_with(&errors[0].0), "{:?}", errors);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -17,3 +17,1719 @@ fn test_read_mgf_documents() {
         assert!(!vec.is_empty());
     }
 }
+
+#[test]
+fn test_read_mgf_document_with_crlf_line_endings() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    let document = std::fs::read_to_string(path).unwrap();
+    let windows_document = document.replace('\n', "\r\n");
+
+    let mascot_generic_formats: MGFVec<usize, f64> =
+        MGFVec::try_from_iter(document.lines()).unwrap();
+    // We split on the line feed only, so that each line keeps its carriage return.
+    let windows_mascot_generic_formats: MGFVec<usize, f64> =
+        MGFVec::try_from_iter(windows_document.split('\n')).unwrap();
+
+    assert_eq!(
+        mascot_generic_formats.len(),
+        windows_mascot_generic_formats.len()
+    );
+    for (left, right) in mascot_generic_formats
+        .iter()
+        .zip(windows_mascot_generic_formats.iter())
+    {
+        assert_eq!(left.feature_id(), right.feature_id());
+        assert_eq!(left.precursor_mz(), right.precursor_mz());
+    }
+}
+
+#[test]
+fn test_read_mgf_document_with_decorated_section_delimiters() {
+    let document = |begin_ions: &'static str, end_ions: &'static str| {
+        [
+            begin_ions,
+            "FEATURE_ID=1",
+            "PEPMASS=381.0795",
+            "CHARGE=1",
+            "RTINSECONDS=37.083",
+            "MSLEVEL=2",
+            "60.5425 2.4E5",
+            "119.0857 3.3E5",
+            end_ions,
+        ]
+    };
+
+    for (begin_ions, end_ions) in [
+        ("BEGIN IONS", "END IONS "),
+        ("BEGIN IONS\t", "END IONS"),
+        ("  BEGIN IONS", "END IONS; end of the entry"),
+        ("BEGIN IONS # first entry", "END IONS\t# end of the entry"),
+    ] {
+        let mascot_generic_formats: MGFVec<usize, f64> =
+            MGFVec::try_from_iter(document(begin_ions, end_ions)).unwrap();
+        assert_eq!(mascot_generic_formats.len(), 1);
+        assert_eq!(
+            mascot_generic_formats[0].num_peaks(FragmentationSpectraLevel::Two),
+            Some(2)
+        );
+    }
+
+    // Lines that merely start with a delimiter are still rejected.
+    for (begin_ions, end_ions) in [("BEGIN IONSX", "END IONS"), ("BEGIN IONS", "END IONS2")] {
+        assert!(MGFVec::<usize, f64>::try_from_iter(document(begin_ions, end_ions)).is_err());
+    }
+}
+
+#[test]
+fn test_read_mgf_document_with_ms_level_variants() {
+    for ms_level in [
+        "MSLEVEL=2",
+        "MSLEVEL= 2",
+        "MS LEVEL=2",
+        "MSLEVEL=MS2",
+        "mslevel=ms2",
+    ] {
+        let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+            "BEGIN IONS",
+            "FEATURE_ID=1",
+            "PEPMASS=381.0795",
+            "CHARGE=1",
+            "RTINSECONDS=37.083",
+            ms_level,
+            "60.5425 2.4E5",
+            "END IONS",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            mascot_generic_formats[0].max_fragmentation_level(),
+            FragmentationSpectraLevel::Two,
+            "{}",
+            ms_level
+        );
+    }
+}
+
+#[test]
+fn test_read_mgf_document_with_unnormalized_keys() {
+    let document = "BEGIN IONS
+Feature_id=1
+Pepmass=381.0795
+CHARGE = 1+
+rtinseconds =37.083
+MsLevel=2
+60.5425 2.4E5
+END IONS
+";
+
+    let mascot_generic_formats: MGFVec<usize, f64> =
+        MGFVec::try_from_iter(document.lines()).unwrap();
+
+    assert_eq!(mascot_generic_formats.len(), 1);
+    assert_eq!(mascot_generic_formats[0].precursor_mz(), 381.0795);
+    assert_eq!(mascot_generic_formats[0].charge(), Charge::OnePlus);
+    assert_eq!(mascot_generic_formats[0].retention_time(), 37.083);
+}
+
+#[test]
+fn test_read_mgf_document_with_missing_end_ions() {
+    let document = "BEGIN IONS
+FEATURE_ID=1
+PEPMASS=381.0795
+CHARGE=1
+RTINSECONDS=37.083
+MSLEVEL=2
+60.5425 2.4E5
+BEGIN IONS
+FEATURE_ID=2
+PEPMASS=415.1234
+CHARGE=1
+RTINSECONDS=41.2
+MSLEVEL=2
+72.6217 2.1E4
+END IONS
+";
+
+    assert!(MGFVec::<usize, f64>::try_from_iter(document.lines()).is_err());
+
+    let mascot_generic_formats: MGFVec<usize, f64> =
+        MGFVec::try_from_iter_with_options(document.lines(), ParseOptions::lenient()).unwrap();
+
+    assert_eq!(mascot_generic_formats.len(), 2);
+    assert_eq!(mascot_generic_formats[0].feature_id(), 1);
+    assert_eq!(mascot_generic_formats[1].feature_id(), 2);
+    assert_eq!(mascot_generic_formats[1].precursor_mz(), 415.1234);
+}
+
+#[test]
+fn test_read_mgf_document_with_byte_order_mark() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    let document = std::fs::read_to_string(path).unwrap();
+    let document_with_byte_order_mark = format!("\u{FEFF}{}", document);
+
+    let mascot_generic_formats: MGFVec<usize, f64> =
+        MGFVec::try_from_iter(document.lines()).unwrap();
+    let mascot_generic_formats_with_byte_order_mark: MGFVec<usize, f64> =
+        MGFVec::try_from_iter(document_with_byte_order_mark.lines()).unwrap();
+
+    assert_eq!(
+        mascot_generic_formats.as_slice(),
+        mascot_generic_formats_with_byte_order_mark.as_slice()
+    );
+}
+
+#[test]
+fn test_read_mgf_document_with_comments() {
+    let document = [
+        "# Exported for testing purposes",
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "60.5425 2.4E5",
+        "; A comment between the peaks",
+        "119.0857 3.3E5",
+        "END IONS",
+    ];
+
+    assert!(MGFVec::<usize, f64>::try_from_iter(document).is_err());
+
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter_with_options(
+        document,
+        ParseOptions::default().with_allow_comments(true),
+    )
+    .unwrap();
+
+    assert_eq!(mascot_generic_formats.len(), 1);
+    assert_eq!(
+        mascot_generic_formats[0].num_peaks(FragmentationSpectraLevel::Two),
+        Some(2)
+    );
+}
+
+#[test]
+fn test_read_mgf_document_with_blank_line_between_peaks() {
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "60.5425 2.4E5",
+        "",
+        " \r",
+        "119.0857 3.3E5",
+        "END IONS",
+        "",
+    ])
+    .unwrap();
+
+    assert_eq!(mascot_generic_formats.len(), 1);
+    assert_eq!(
+        mascot_generic_formats[0].num_peaks(FragmentationSpectraLevel::Two),
+        Some(2)
+    );
+}
+
+#[test]
+fn test_read_mgf_document_with_precursor_written_with_different_decimals() {
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "SPECTYPE=CORRELATED MS",
+        "MSLEVEL=1",
+        "SCANS=-1",
+        "381.07950000001 7.8E8",
+        "END IONS",
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "SCANS=1",
+        "60.5425 2.4E5",
+        "END IONS",
+    ])
+    .unwrap();
+
+    assert_eq!(mascot_generic_formats.len(), 1);
+
+    // A precursor outside of the tolerance is still rejected.
+    assert!(MGFVec::<usize, f64>::try_from_iter([
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=1",
+        "381.0796 7.8E8",
+        "END IONS",
+    ])
+    .is_err());
+}
+
+#[test]
+fn test_read_mgf_document_with_deconvoluted_precursor() {
+    // The precursor is close to, but not equal to, the lowest peak of the first level.
+    let document = [
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0800",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "SPECTYPE=CORRELATED MS",
+        "MSLEVEL=1",
+        "SCANS=-1",
+        "381.0795 7.8E8",
+        "END IONS",
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0800",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "SCANS=1",
+        "60.5425 2.4E5",
+        "END IONS",
+    ];
+
+    assert!(MGFVec::<usize, f64>::try_from_iter(document).is_err());
+    // The tolerance on repeated fields does not apply to the precursor.
+    assert!(MGFVec::<usize, f64>::try_from_iter_with_options(
+        document,
+        ParseOptions::default().with_relative_tolerance(1e-5),
+    )
+    .is_err());
+
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter_with_options(
+        document,
+        ParseOptions::default().with_precursor_tolerance(1e-5),
+    )
+    .unwrap();
+    assert_eq!(mascot_generic_formats.len(), 1);
+
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter_with_options(
+        document,
+        ParseOptions::default().with_validate_precursor(false),
+    )
+    .unwrap();
+    assert_eq!(mascot_generic_formats.len(), 1);
+    assert_eq!(mascot_generic_formats[0].precursor_mz(), 381.08);
+}
+
+#[test]
+fn test_read_mgf_document_with_sentinel_fields() {
+    let document = |sentinel_line: &str| {
+        vec![
+            "BEGIN IONS".to_string(),
+            "FEATURE_ID=1".to_string(),
+            "PEPMASS=381.0795".to_string(),
+            "CHARGE=1".to_string(),
+            "RTINSECONDS=37.083".to_string(),
+            sentinel_line.to_string(),
+            "MSLEVEL=2".to_string(),
+            "60.5425 2.4E5".to_string(),
+            "END IONS".to_string(),
+        ]
+    };
+
+    for (sentinel_line, key) in [
+        ("SEQ=*..*", "SEQ"),
+        ("IONMODE=N/A", "IONMODE"),
+        ("SMILES=N/A", "SMILES"),
+        ("SMILES=", "SMILES"),
+    ] {
+        let mascot_generic_formats: MGFVec<usize, f64> =
+            MGFVec::try_from_iter(document(sentinel_line)).unwrap();
+
+        assert!(mascot_generic_formats[0].had_sentinel_fields());
+        assert!(mascot_generic_formats[0].sentinel_fields().contains(key));
+        assert!(mascot_generic_formats[0].extra().is_empty());
+        assert_eq!(mascot_generic_formats[0].ion_mode(), None);
+    }
+
+    let mascot_generic_formats: MGFVec<usize, f64> =
+        MGFVec::try_from_iter(document("IONMODE=Positive")).unwrap();
+
+    assert!(!mascot_generic_formats[0].had_sentinel_fields());
+
+    // Placeholders are not accepted for the required fields.
+    assert!(MGFVec::<usize, f64>::try_from_iter(document("PEPMASS=N/A")).is_err());
+
+    // Nor do they make unknown fields acceptable in strict mode.
+    for sentinel_line in ["GARBAGE=N/A", "FOO=", "MSLEVEL=N/A"] {
+        assert!(
+            MGFVec::<usize, f64>::try_from_iter(document(sentinel_line)).is_err(),
+            "{}",
+            sentinel_line
+        );
+    }
+}
+
+#[test]
+fn test_get_mgf_document_by_feature_id() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+
+    let mascot_generic_format = mascot_generic_formats.get_by_feature_id(56).unwrap();
+
+    assert_eq!(mascot_generic_format.feature_id(), 56);
+    assert_eq!(mascot_generic_format.precursor_mz(), 600.417236328125);
+
+    let position = mascot_generic_formats.position_by_feature_id(56).unwrap();
+    let index = mascot_generic_formats.build_feature_index().unwrap();
+
+    assert_eq!(index[&56], position);
+    assert_eq!(&mascot_generic_formats[position], mascot_generic_format);
+    assert!(mascot_generic_formats.get_by_feature_id(0).is_none());
+}
+
+#[test]
+fn test_read_mgf_document_with_fragment_charge_column() {
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "60.5425 2.4E5",
+        "119.0857 3.3E5 1",
+        "END IONS",
+    ])
+    .unwrap();
+
+    assert_eq!(mascot_generic_formats.len(), 1);
+    assert_eq!(
+        mascot_generic_formats[0].data()[0].fragment_intensities(),
+        &[2.4E5, 3.3E5]
+    );
+}
+
+#[test]
+fn test_read_mgf_document_with_string_feature_ids() {
+    let document = [
+        "BEGIN IONS",
+        "FEATURE_ID=f_001",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "SPECTYPE=CORRELATED MS",
+        "MSLEVEL=1",
+        "SCANS=-1",
+        "381.0795 1.2E6",
+        "END IONS",
+        "BEGIN IONS",
+        "FEATURE_ID=f_001",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "SCANS=f_001",
+        "60.5425 2.4E5",
+        "END IONS",
+        "BEGIN IONS",
+        "FEATURE_ID=f_002",
+        "PEPMASS=213.1234",
+        "CHARGE=1",
+        "RTINSECONDS=41.5",
+        "MSLEVEL=2",
+        "SCANS=f_002",
+        "60.5425 2.4E5",
+        "END IONS",
+    ];
+
+    let (mascot_generic_formats, feature_ids): (MGFVec<usize, f64>, Vec<String>) =
+        MGFVec::try_from_iter_with_string_feature_ids(document).unwrap();
+
+    assert_eq!(mascot_generic_formats.len(), 2);
+    assert_eq!(feature_ids, ["f_001", "f_002"]);
+    assert_eq!(feature_ids[mascot_generic_formats[0].feature_id()], "f_001");
+    assert_eq!(feature_ids[mascot_generic_formats[1].feature_id()], "f_002");
+    assert_eq!(mascot_generic_formats[0].data().len(), 2);
+}
+
+#[test]
+fn test_filter_mgf_documents_by_field_presence() {
+    let mut document = Vec::new();
+    for (feature_id, fields) in [
+        (1, vec!["SMILES=CCO", "PUBMED=12345"]),
+        (2, vec!["SMILES=CCO"]),
+        (
+            3,
+            vec!["INCHI=InChI=1S/C2H6O/c1-2-3/h3H,2H2,1H3", "PUBMED=67890"],
+        ),
+        (4, vec!["SMILES=N/A", "PUBMED=13579"]),
+        (5, vec![]),
+    ] {
+        document.push("BEGIN IONS".to_string());
+        document.push(format!("FEATURE_ID={}", feature_id));
+        document.push("PEPMASS=381.0795".to_string());
+        document.push("CHARGE=1".to_string());
+        document.push("RTINSECONDS=37.083".to_string());
+        document.extend(fields.into_iter().map(str::to_string));
+        document.push("MSLEVEL=2".to_string());
+        document.push("60.5425 2.4E5".to_string());
+        document.push("END IONS".to_string());
+    }
+
+    let mascot_generic_formats: MGFVec<usize, f64> =
+        MGFVec::try_from_iter_with_options(document, ParseOptions::lenient()).unwrap();
+
+    let feature_ids = |predicate: fn(FieldPresence) -> bool| {
+        mascot_generic_formats
+            .filter_by_presence(predicate)
+            .map(|mascot_generic_format| mascot_generic_format.feature_id())
+            .collect::<Vec<usize>>()
+    };
+
+    assert_eq!(
+        feature_ids(|presence| presence.has_smiles() && presence.has_reference()),
+        [1]
+    );
+    assert_eq!(
+        feature_ids(|presence| presence.has_structure() && presence.has_reference()),
+        [1, 3]
+    );
+    assert_eq!(feature_ids(|presence| presence.has_reference()), [1, 3, 4]);
+    assert_eq!(feature_ids(|presence| !presence.has_structure()), [4, 5]);
+}
+
+#[test]
+fn test_read_mgf_document_with_retention_time_in_minutes() {
+    let document = |retention_time_line: &'static str| {
+        [
+            "BEGIN IONS",
+            "FEATURE_ID=1",
+            "PEPMASS=381.0795",
+            "CHARGE=1",
+            retention_time_line,
+            "MSLEVEL=2",
+            "60.5425 2.4E5",
+            "END IONS",
+        ]
+    };
+
+    let in_seconds: MGFVec<usize, f64> =
+        MGFVec::try_from_iter(document("RTINSECONDS=37.5")).unwrap();
+    let in_minutes: MGFVec<usize, f64> =
+        MGFVec::try_from_iter(document("RTINMINUTES=0.625")).unwrap();
+
+    assert_eq!(in_seconds[0].retention_time(), 37.5);
+    assert_eq!(in_minutes[0].retention_time(), 37.5);
+    assert_eq!(in_minutes[0].retention_time_minutes(), 0.625);
+    assert_eq!(in_seconds.as_slice(), in_minutes.as_slice());
+
+    // Both keys may appear in the same entry, as long as they agree.
+    let both: MGFVec<usize, f64> = MGFVec::try_from_iter([
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.5",
+        "RTINMINUTES=0.625",
+        "MSLEVEL=2",
+        "60.5425 2.4E5",
+        "END IONS",
+    ])
+    .unwrap();
+    assert_eq!(both[0].retention_time(), 37.5);
+
+    assert!(MGFVec::<usize, f64>::try_from_iter([
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.5",
+        "RTINMINUTES=37.5",
+        "MSLEVEL=2",
+        "60.5425 2.4E5",
+        "END IONS",
+    ])
+    .is_err());
+}
+
+#[test]
+fn test_read_mgf_document_with_zero_values() {
+    let document = [
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=0.0",
+        "MSLEVEL=2",
+        "60.5425 0.0",
+        "119.0857 3.3E5",
+        "END IONS",
+    ];
+
+    assert!(MGFVec::<usize, f64>::try_from_iter(document).is_err());
+
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter_with_options(
+        document,
+        ParseOptions::default().with_allow_zero_values(true),
+    )
+    .unwrap();
+
+    assert_eq!(mascot_generic_formats.len(), 1);
+    assert_eq!(mascot_generic_formats[0].retention_time(), 0.0);
+    assert_eq!(
+        mascot_generic_formats[0].data()[0].fragment_intensities(),
+        &[0.0, 3.3E5]
+    );
+
+    // The parent ion mass must be strictly positive regardless of the options.
+    let mut zero_parent_ion_mass = document;
+    zero_parent_ion_mass[2] = "PEPMASS=0.0";
+    assert!(MGFVec::<usize, f64>::try_from_iter_with_options(
+        zero_parent_ion_mass,
+        ParseOptions::default().with_allow_zero_values(true),
+    )
+    .is_err());
+}
+
+#[test]
+fn test_read_mgf_document_with_duplicated_mz() {
+    let document = [
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "60.5425 2.4E5",
+        "60.5425 2.1E4",
+        "119.0857 3.3E5",
+        "END IONS",
+    ];
+
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter(document).unwrap();
+
+    assert!(mascot_generic_formats[0].data()[0].has_duplicate_mz(0.0));
+
+    assert!(MGFVec::<usize, f64>::try_from_iter_with_options(
+        document,
+        ParseOptions::default().with_reject_duplicate_mz(true),
+    )
+    .is_err());
+}
+
+#[test]
+fn test_read_mgf_document_with_unsorted_peaks_in_lenient_mode() {
+    let document = [
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "119.0857 3.3E5",
+        "60.5425 2.4E5",
+        "72.6217 2.1E4",
+        "END IONS",
+    ];
+
+    assert!(MGFVec::<usize, f64>::try_from_iter(document).is_err());
+
+    let (mascot_generic_formats, report): (MGFVec<usize, f64>, ParseReport) =
+        MGFVec::try_from_iter_with_report(document, ParseOptions::lenient()).unwrap();
+
+    let data = mascot_generic_formats[0]
+        .get_second_fragmentation_level()
+        .unwrap();
+    assert_eq!(
+        data.mass_divided_by_charge_ratios(),
+        &[60.5425, 72.6217, 119.0857]
+    );
+    assert_eq!(data.fragment_intensities(), &[2.4E5, 2.1E4, 3.3E5]);
+    assert_eq!(report.warnings().len(), 1);
+}
+
+#[test]
+fn test_read_mgf_document_with_european_decimals() {
+    let document = [
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381,0795",
+        "CHARGE=1",
+        "RTINSECONDS=37,083",
+        "MERGED_SCANS=1567,1540",
+        "MERGED_STATS=2 / 2 (0 removed due to low quality, 0 removed due to low cosine).",
+        "MSLEVEL=2",
+        "60,5425 2,4E5",
+        "119.0857 3.3E5",
+        "END IONS",
+    ];
+
+    assert!(MGFVec::<usize, f64>::try_from_iter(document).is_err());
+
+    // The comma-separated list of merged scans, whose count is validated
+    // against the merged statistics, is not mistaken for a decimal value.
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter_with_options(
+        document,
+        ParseOptions::default().with_european_decimals(true),
+    )
+    .unwrap();
+
+    assert_eq!(mascot_generic_formats[0].precursor_mz(), 381.0795);
+    assert_eq!(mascot_generic_formats[0].retention_time(), 37.083);
+    assert_eq!(
+        mascot_generic_formats[0].data()[0].mass_divided_by_charge_ratios(),
+        &[60.5425, 119.0857]
+    );
+}
+
+#[test]
+fn test_clear_retains_capacity() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+
+    let mut buffer: MGFVec<usize, f64> = MGFVec::with_capacity(mascot_generic_formats.len());
+    let capacity = buffer.capacity();
+    assert!(capacity >= mascot_generic_formats.len());
+
+    for _ in 0..2 {
+        for mascot_generic_format in mascot_generic_formats.iter() {
+            buffer.push(mascot_generic_format.clone());
+        }
+        assert_eq!(buffer.len(), mascot_generic_formats.len());
+        assert_eq!(buffer.capacity(), capacity);
+
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.capacity(), capacity);
+    }
+}
+
+#[test]
+fn test_concatenate_mgf_documents() {
+    let first_path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    let second_path = "tests/data/20220513_PMA_DBGI_01_04_002.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    let first: MGFVec<usize, f64> = MGFVec::from_path(first_path).unwrap();
+    let second: MGFVec<usize, f64> = MGFVec::from_path(second_path).unwrap();
+    let total = first.len() + second.len();
+
+    let mut extended = first.clone();
+    extended.extend(second.clone());
+    assert_eq!(extended.len(), total);
+
+    let concatenated = MGFVec::concat(vec![first.clone(), second.clone()]);
+    assert_eq!(concatenated.len(), total);
+    assert_eq!(concatenated.as_slice(), extended.as_slice());
+    assert_eq!(&concatenated.as_slice()[..first.len()], first.as_slice());
+    assert_eq!(&concatenated.as_slice()[first.len()..], second.as_slice());
+}
+
+#[test]
+fn test_wrap_vec_of_mgf_documents() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+
+    let vec = mascot_generic_formats.clone().into_vec();
+    let pointer = vec.as_ptr();
+
+    let wrapped = MGFVec::from_vec(vec);
+    assert_eq!(wrapped.as_slice(), mascot_generic_formats.as_slice());
+    // The MGF objects are moved into the vector rather than copied.
+    assert_eq!(wrapped.as_slice().as_ptr(), pointer);
+
+    let converted: MGFVec<usize, f64> = wrapped.into_vec().into();
+    assert_eq!(converted.as_slice(), mascot_generic_formats.as_slice());
+}
+
+#[test]
+fn test_filtering_reports_removed_peaks() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+
+    for mascot_generic_format in mascot_generic_formats.iter() {
+        let mut data = mascot_generic_format
+            .get_second_fragmentation_level()
+            .unwrap()
+            .clone();
+
+        let before = data.len();
+        let removed = data.filter_by_relative_intensity(0.05);
+        assert_eq!(removed, before - data.len());
+        assert!(!data.is_empty());
+        assert!(data.is_sorted_by_mz());
+
+        let before = data.len();
+        let removed = data.deisotope(0.01, 2);
+        assert_eq!(removed, before - data.len());
+        assert_eq!(
+            data.mass_divided_by_charge_ratios().len(),
+            data.fragment_intensities().len()
+        );
+    }
+}
+
+#[test]
+fn test_read_mgf_document_with_ambiguous_charge() {
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=2+ and 1+",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "60.5425 2.4E5",
+        "END IONS",
+    ])
+    .unwrap();
+
+    assert_eq!(mascot_generic_formats[0].charge(), Charge::OnePlus);
+    assert_eq!(
+        mascot_generic_formats[0].alternative_charges(),
+        &[Charge::TwoPlus]
+    );
+
+    let mgf = mascot_generic_formats[0].to_mgf_string(DEFAULT_MGF_PRECISION);
+    assert!(mgf.lines().any(|line| line == "CHARGE=1+ and 2+"));
+    let reloaded: MGFVec<usize, f64> = MGFVec::try_from_iter(mgf.lines()).unwrap();
+    assert_eq!(reloaded.as_slice(), mascot_generic_formats.as_slice());
+
+    assert!(MGFVec::<usize, f64>::try_from_iter([
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1+ or 2+",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "60.5425 2.4E5",
+        "END IONS",
+    ])
+    .is_err());
+}
+
+#[test]
+fn test_read_mgf_document_validating_smiles() {
+    let document = |smiles: &str| {
+        vec![
+            "BEGIN IONS".to_string(),
+            "FEATURE_ID=1".to_string(),
+            "PEPMASS=381.0795".to_string(),
+            "CHARGE=1".to_string(),
+            "RTINSECONDS=37.083".to_string(),
+            format!("SMILES={}", smiles),
+            "MSLEVEL=2".to_string(),
+            "60.5425 2.4E5".to_string(),
+            "END IONS".to_string(),
+        ]
+    };
+    let options = ParseOptions::lenient();
+
+    for smiles in [
+        "CCO",
+        "c1ccccc1Cl",
+        "C[C@@H](C(=O)O)[NH3+]",
+        "OC[C@H]1OC(O)[C@H](O)[C@@H]1O",
+    ] {
+        for options in [options, options.with_validate_smiles(true)] {
+            let mascot_generic_formats: MGFVec<usize, f64> =
+                MGFVec::try_from_iter_with_options(document(smiles), options).unwrap();
+            assert!(mascot_generic_formats[0].field_presence().has_smiles());
+        }
+    }
+
+    for smiles in ["not a smiles", "CC(O", "CC)O", "C[NH4+", "C1CC{O}"] {
+        assert!(
+            MGFVec::<usize, f64>::try_from_iter_with_options(document(smiles), options).is_ok()
+        );
+        assert!(MGFVec::<usize, f64>::try_from_iter_with_options(
+            document(smiles),
+            options.with_validate_smiles(true)
+        )
+        .is_err());
+    }
+}
+
+#[test]
+fn test_mgf_statistics() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+
+    let statistics = mascot_generic_formats.statistics();
+
+    let number_of_two_levels = mascot_generic_formats
+        .iter()
+        .filter(|mascot_generic_format| {
+            mascot_generic_format
+                .get_first_fragmentation_level()
+                .is_ok()
+                && mascot_generic_format.has_second_level()
+        })
+        .count();
+    assert!(number_of_two_levels > 0);
+    assert_eq!(statistics.number_of_two_levels(), number_of_two_levels);
+    assert_eq!(
+        statistics.number_of_first_level_only()
+            + statistics.number_of_second_level_only()
+            + statistics.number_of_two_levels(),
+        mascot_generic_formats.len()
+    );
+    assert!(statistics.min_precursor_mz() <= statistics.mean_precursor_mz());
+    assert!(statistics.mean_precursor_mz() <= statistics.max_precursor_mz());
+    assert!(statistics
+        .to_string()
+        .contains(&format!("both levels: {}", number_of_two_levels)));
+
+    let statistics = MGFVec::<usize, f64>::new().statistics();
+    assert_eq!(statistics.number_of_entries(), 0);
+    assert_eq!(statistics.mean_precursor_mz(), None);
+}
+
+#[test]
+fn test_read_mgf_documents_from_bytes() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_003.mgf";
+    let bytes = std::fs::read(path).unwrap();
+
+    let from_bytes: MGFVec<usize, f64> = MGFVec::try_from_bytes(&bytes).unwrap();
+    let from_path: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+    assert_eq!(from_bytes.as_slice(), from_path.as_slice());
+
+    let document: &[u8] = concat!(
+        "BEGIN IONS\r\n",
+        "FEATURE_ID=1\r\n",
+        "PEPMASS=381.0795\r\n",
+        "CHARGE=1\r\n",
+        "RTINSECONDS=37.083\r\n",
+        "MSLEVEL=2\r\n",
+        "60.5425 2.4E5\r\n",
+        "END IONS\r\n",
+    )
+    .as_bytes();
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_bytes(document).unwrap();
+    assert_eq!(mascot_generic_formats.len(), 1);
+    assert_eq!(mascot_generic_formats[0].feature_id(), 1);
+
+    let mut invalid = document.to_vec();
+    invalid[20] = 0xFF;
+    assert!(MGFVec::<usize, f64>::try_from_bytes(&invalid).is_err());
+}
+
+#[test]
+fn test_read_mgf_documents_from_dir() {
+    let (mascot_generic_formats, errors): (MGFVec<usize, f64>, Vec<(String, String)>) =
+        MGFVec::try_from_dir("tests/batch_data").unwrap();
+
+    let valid: MGFVec<usize, f64> = MGFVec::from_path("tests/batch_data/valid.mgf").unwrap();
+    assert_eq!(mascot_generic_formats.as_slice(), valid.as_slice());
+
+    assert_eq!(errors.len(), 1);
+    let (path, error) = &errors[0];
+    assert_eq!(path, "tests/batch_data/broken.mgf");
+    assert!(error.contains("line 8"), "{}", error);
+
+    // The error of the file parsed alone is prefixed with its path.
+    let error =
+        MGFVec::<usize, f64>::try_from_path_with_report(path, ParseOptions::default()).unwrap_err();
+    assert!(error.starts_with(&format!("{}: ", path)), "{}", error);
+
+    assert!(MGFVec::<usize, f64>::try_from_dir("tests/missing_directory").is_err());
+}
+
+#[test]
+fn test_read_mgf_documents_from_dir_with_report() {
+    let dir = std::env::temp_dir().join("mascot_rs_test_read_mgf_documents_from_dir_with_report");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("unsorted.mgf");
+    std::fs::write(
+        &path,
+        concat!(
+            "BEGIN IONS\n",
+            "FEATURE_ID=1\n",
+            "PEPMASS=381.0795\n",
+            "CHARGE=1\n",
+            "RTINSECONDS=37.083\n",
+            "MSLEVEL=2\n",
+            "119.0857 3.3E5\n",
+            "60.5425 2.4E5\n",
+            "END IONS\n",
+        ),
+    )
+    .unwrap();
+
+    let (mascot_generic_formats, errors, report): (
+        MGFVec<usize, f64>,
+        Vec<(String, String)>,
+        ParseReport,
+    ) = MGFVec::try_from_dir_with_report(dir.to_str().unwrap(), ParseOptions::lenient()).unwrap();
+
+    assert_eq!(mascot_generic_formats.len(), 1);
+    assert!(errors.is_empty());
+    // The warnings are prefixed with the path of the file they were raised in.
+    assert_eq!(report.warnings().len(), 1);
+    assert!(
+        report.warnings()[0].starts_with(&format!("{}: Warning at line", path.to_str().unwrap())),
+        "{}",
+        report.warnings()[0]
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_read_msp_document_from_path() {
+    let path = std::env::temp_dir().join("mascot_rs_test_read_msp_document_from_path.msp");
+    let path = path.to_str().unwrap();
+    let document = concat!(
+        "\u{FEFF}NAME: 1\n",
+        "PRECURSORMZ: 381.0795\n",
+        "CHARGE: 1\n",
+        "RETENTIONTIME: 37.083\n",
+        "Num Peaks: 1\n",
+        "60.5425 240000\n",
+    );
+
+    // A leading byte order mark is ignored.
+    std::fs::write(path, document).unwrap();
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_msp_path(path).unwrap();
+    assert_eq!(mascot_generic_formats.len(), 1);
+
+    // The errors are prefixed with the path of the file.
+    std::fs::write(path, document.replace("NAME", "GARBAGE")).unwrap();
+    assert!(MGFVec::<usize, f64>::try_from_msp_path(path)
+        .unwrap_err()
+        .starts_with(&format!("{}: Error at line 1", path)));
+
+    let mut bytes = document.as_bytes().to_vec();
+    bytes.push(0xFF);
+    std::fs::write(path, bytes).unwrap();
+    assert!(MGFVec::<usize, f64>::try_from_msp_path(path)
+        .unwrap_err()
+        .starts_with(&format!("{}: The document is not valid UTF-8", path)));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_read_mgf_document_with_independent_scan_numbers() {
+    let document = [
+        "BEGIN IONS",
+        "FEATURE_ID=10",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "SCANS=42",
+        "60.5425 2.4E5",
+        "119.0857 3.3E5",
+        "END IONS",
+    ];
+
+    assert!(MGFVec::<usize, f64>::try_from_iter(document).is_err());
+
+    let mascot_generic_formats: MGFVec<usize, f64> =
+        MGFVec::try_from_iter_with_options(document, ParseOptions::lenient()).unwrap();
+    assert_eq!(mascot_generic_formats.len(), 1);
+    assert_eq!(mascot_generic_formats[0].feature_id(), 10);
+    assert_eq!(mascot_generic_formats[0].scan_number(), Some(42));
+
+    // The scan number is written back in place of the feature ID.
+    let mgf = mascot_generic_formats[0].to_mgf_string(DEFAULT_MGF_PRECISION);
+    assert!(mgf.lines().any(|line| line == "FEATURE_ID=10"));
+    assert!(mgf.lines().any(|line| line == "SCANS=42"));
+
+    // A scan number equal to the feature ID is not stored separately.
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter_with_options(
+        document.map(|line| if line == "SCANS=42" { "SCANS=10" } else { line }),
+        ParseOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(mascot_generic_formats[0].scan_number(), None);
+
+    // The option is independent from the strictness of the parsing.
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter_with_options(
+        document,
+        ParseOptions::default().with_allow_independent_scan_numbers(true),
+    )
+    .unwrap();
+    assert_eq!(mascot_generic_formats[0].scan_number(), Some(42));
+    assert!(MGFVec::<usize, f64>::try_from_iter_with_options(
+        document,
+        ParseOptions::lenient().with_allow_independent_scan_numbers(false),
+    )
+    .is_err());
+
+    // With string feature IDs, the scan numbers are mapped along the feature
+    // IDs, and an entry without a feature ID takes its scan number.
+    let (mascot_generic_formats, feature_ids): (MGFVec<usize, f64>, Vec<String>) =
+        MGFVec::try_from_iter_with_string_feature_ids_and_options(
+            document
+                .into_iter()
+                .map(|line| match line {
+                    "FEATURE_ID=10" => "FEATURE_ID=f_010",
+                    "SCANS=42" => "SCANS=s_042",
+                    line => line,
+                })
+                .chain(
+                    document
+                        .into_iter()
+                        .filter(|line| !line.starts_with("FEATURE_ID="))
+                        .map(|line| {
+                            if line == "SCANS=42" {
+                                "SCANS=s_043"
+                            } else {
+                                line
+                            }
+                        }),
+                ),
+            ParseOptions::lenient(),
+        )
+        .unwrap();
+    assert_eq!(mascot_generic_formats.len(), 2);
+    assert_eq!(feature_ids[mascot_generic_formats[0].feature_id()], "f_010");
+    assert_eq!(
+        feature_ids[mascot_generic_formats[0].scan_number().unwrap()],
+        "s_042"
+    );
+    assert_eq!(feature_ids[mascot_generic_formats[1].feature_id()], "s_043");
+    assert_eq!(mascot_generic_formats[1].scan_number(), None);
+}
+
+#[test]
+fn test_report_level_gaps() {
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+        // An entry with both the first and the second fragmentation levels.
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "SPECTYPE=CORRELATED MS",
+        "MSLEVEL=1",
+        "CHARGE=1+",
+        "RTINSECONDS=37.083",
+        "SCANS=-1",
+        "381.0795 7.8E8",
+        "END IONS",
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "MSLEVEL=2",
+        "CHARGE=1+",
+        "RTINSECONDS=37.083",
+        "SCANS=1",
+        "60.5425 2.4E5",
+        "END IONS",
+        // An entry with only the second fragmentation level.
+        "BEGIN IONS",
+        "FEATURE_ID=2",
+        "PEPMASS=245.1282",
+        "MSLEVEL=2",
+        "CHARGE=1+",
+        "RTINSECONDS=52.117",
+        "SCANS=2",
+        "70.0651 1.2E5",
+        "END IONS",
+        // An entry with only the first fragmentation level.
+        "BEGIN IONS",
+        "FEATURE_ID=3",
+        "PEPMASS=512.2034",
+        "MSLEVEL=1",
+        "CHARGE=1+",
+        "RTINSECONDS=61.504",
+        "SCANS=3",
+        "512.2034 3.1E8",
+        "END IONS",
+    ])
+    .unwrap();
+
+    let level_profiles = mascot_generic_formats
+        .iter()
+        .map(|mascot_generic_format| mascot_generic_format.level_profile())
+        .collect::<Vec<LevelProfile>>();
+    assert_eq!(
+        level_profiles,
+        vec![
+            LevelProfile::new(true, true),
+            LevelProfile::new(false, true),
+            LevelProfile::new(true, false),
+        ]
+    );
+    assert!(level_profiles[0].has_both_levels());
+    assert!(level_profiles[1].is_second_level_only());
+    assert!(level_profiles[2].is_first_level_only());
+
+    assert_eq!(
+        mascot_generic_formats
+            .entries_missing_ms1()
+            .map(|mascot_generic_format| mascot_generic_format.feature_id())
+            .collect::<Vec<usize>>(),
+        vec![2]
+    );
+    assert_eq!(
+        mascot_generic_formats
+            .entries_missing_ms2()
+            .map(|mascot_generic_format| mascot_generic_format.feature_id())
+            .collect::<Vec<usize>>(),
+        vec![3]
+    );
+}
+
+#[test]
+fn test_read_mgf_document_with_conflicting_ion_modes() {
+    let document = [
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "SPECTYPE=CORRELATED MS",
+        "MSLEVEL=1",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "IONMODE=Negative",
+        "SOURCE_INSTRUMENT=LC-ESI-qTof",
+        "ORGANISM=GNPS-LIBRARY",
+        "SCANS=-1",
+        "381.0795 7.8E8",
+        "END IONS",
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "MSLEVEL=2",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "IONMODE=Positive",
+        "SOURCE_INSTRUMENT=Orbitrap",
+        "ORGANISM=BMDMS-NP",
+        "SCANS=1",
+        "60.5425 2.4E5",
+        "END IONS",
+    ];
+
+    assert!(MGFVec::<usize, f64>::try_from_iter_with_options(
+        document,
+        ParseOptions::lenient().with_ion_mode_conflict(ConflictPolicy::Error)
+    )
+    .is_err());
+
+    for (policy, ion_mode, source_instrument, organism) in [
+        (
+            ConflictPolicy::KeepFirst,
+            IonMode::Negative,
+            "LC-ESI-qTof",
+            "GNPS-LIBRARY",
+        ),
+        (
+            ConflictPolicy::KeepLast,
+            IonMode::Positive,
+            "Orbitrap",
+            "BMDMS-NP",
+        ),
+    ] {
+        let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter_with_options(
+            document,
+            ParseOptions::lenient().with_ion_mode_conflict(policy),
+        )
+        .unwrap();
+
+        let mascot_generic_format = &mascot_generic_formats[0];
+        assert_eq!(mascot_generic_format.ion_mode(), Some(ion_mode));
+        assert_eq!(
+            mascot_generic_format
+                .extra()
+                .get("SOURCE_INSTRUMENT")
+                .map(String::as_str),
+            Some(source_instrument)
+        );
+        assert_eq!(
+            mascot_generic_format
+                .extra()
+                .get("ORGANISM")
+                .map(String::as_str),
+            Some(organism)
+        );
+    }
+
+    // The policy does not apply to the other fields.
+    let conflicting_charge = document.map(|line| {
+        if line == "IONMODE=Positive" {
+            "CHARGE=2"
+        } else {
+            line
+        }
+    });
+    assert!(MGFVec::<usize, f64>::try_from_iter_with_options(
+        conflicting_charge,
+        ParseOptions::lenient().with_ion_mode_conflict(ConflictPolicy::KeepLast)
+    )
+    .is_err());
+}
+
+#[test]
+fn test_read_mgf_document_with_filename() {
+    let document = [
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "FILENAME=20220513_PMA_DBGI_01_04_003.mzML",
+        "60.5425 2.4E5",
+        "END IONS",
+    ];
+
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter(document).unwrap();
+    assert_eq!(
+        mascot_generic_formats[0].filename(),
+        Some("20220513_PMA_DBGI_01_04_003.mzML")
+    );
+    assert!(mascot_generic_formats[0].field_presence().has_filename());
+
+    // The filename is written back, so that it survives a round trip.
+    let mgf = mascot_generic_formats[0].to_mgf_string(DEFAULT_MGF_PRECISION);
+    let reloaded: MGFVec<usize, f64> = MGFVec::try_from_iter(mgf.lines()).unwrap();
+    assert_eq!(reloaded[0].filename(), mascot_generic_formats[0].filename());
+
+    // Without the field, or with an empty value, there is no filename.
+    let without_filename: MGFVec<usize, f64> = MGFVec::try_from_iter(
+        document
+            .iter()
+            .filter(|line| !line.starts_with("FILENAME=")),
+    )
+    .unwrap();
+    assert_eq!(without_filename[0].filename(), None);
+
+    let empty_filename: MGFVec<usize, f64> = MGFVec::try_from_iter(document.map(|line| {
+        if line.starts_with("FILENAME=") {
+            "FILENAME="
+        } else {
+            line
+        }
+    }))
+    .unwrap();
+    assert_eq!(empty_filename[0].filename(), None);
+}
+
+#[test]
+fn test_normalize_mgf_documents_in_place() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    let mut mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+
+    mascot_generic_formats
+        .try_map(|mascot_generic_format| {
+            let base_peak_intensity = mascot_generic_format
+                .levels()
+                .flat_map(|data| data.fragment_intensities_iter().copied())
+                .fold(0.0, f64::max);
+            mascot_generic_format.map_intensities(|intensity| intensity / base_peak_intensity)
+        })
+        .unwrap();
+
+    for mascot_generic_format in mascot_generic_formats.iter() {
+        let intensities = mascot_generic_format
+            .levels()
+            .flat_map(|data| data.fragment_intensities_iter().copied())
+            .collect::<Vec<f64>>();
+        assert!(intensities.iter().all(|&intensity| intensity <= 1.0));
+        assert!(intensities.contains(&1.0));
+    }
+
+    // The first error stops the transformation.
+    let mut visited = 0;
+    let error = mascot_generic_formats
+        .try_map(|mascot_generic_format| {
+            visited += 1;
+            mascot_generic_format.map_intensities(|_| f64::NAN)
+        })
+        .unwrap_err();
+    assert!(!error.is_empty());
+    assert_eq!(visited, 1);
+}
+
+#[test]
+fn test_read_mgf_documents_collecting_errors() {
+    let (mascot_generic_formats, errors): (MGFVec<usize, f64>, DroppedEntries<usize>) =
+        MGFVec::try_from_path_collecting_errors("tests/dropped_entries_data/partially_broken.mgf")
+            .unwrap();
+
+    assert_eq!(mascot_generic_formats.len(), 1);
+    assert_eq!(mascot_generic_formats[0].feature_id(), 1);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0, Some(2));
+    assert!(
+        errors[0].1.starts_with("Error at line 17"),
+        "{}",
+        errors[0].1
+    );
+
+    // The later sections of a dropped entry are dropped and reported with
+    // it, and an incomplete entry at the end of the document is reported.
+    let (mascot_generic_formats, errors): (MGFVec<usize, f64>, DroppedEntries<usize>) =
+        MGFVec::try_from_iter_collecting_errors(
+            [
+                "BEGIN IONS",
+                "FEATURE_ID=1",
+                "PEPMASS=381.0795",
+                "SPECTYPE=CORRELATED MS",
+                "MSLEVEL=1",
+                "CHARGE=1",
+                "RTINSECONDS=37.083",
+                "SCANS=-1",
+                "381.0795 -7.8E8",
+                "END IONS",
+                "BEGIN IONS",
+                "FEATURE_ID=1",
+                "PEPMASS=381.0795",
+                "MSLEVEL=2",
+                "CHARGE=1",
+                "RTINSECONDS=37.083",
+                "SCANS=1",
+                "60.5425 2.4E5",
+                "END IONS",
+                "BEGIN IONS",
+                "FEATURE_ID=2",
+                "PEPMASS=245.1282",
+                "CHARGE=1",
+                "RTINSECONDS=52.117",
+                "MSLEVEL=2",
+                "70.0651 1.2E5",
+                "END IONS",
+                "BEGIN IONS",
+                "FEATURE_ID=3",
+                "PEPMASS=512.2034",
+            ],
+            ParseOptions::default(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        mascot_generic_formats
+            .iter()
+            .map(|mascot_generic_format| mascot_generic_format.feature_id())
+            .collect::<Vec<usize>>(),
+        vec![2]
+    );
+    assert_eq!(
+        errors
+            .iter()
+            .map(|(feature_id, _)| *feature_id)
+            .collect::<Vec<Option<usize>>>(),
+        vec![Some(1), Some(1), Some(3)]
+    );
+    assert!(
+        errors[0].1.starts_with("Error at line 9"),
+        "{}",
+        errors[0].1
+    );
+    assert!(
+        errors[1].1.starts_with("Error at line 19") && errors[1].1.contains("dropped earlier"),
+        "{}",
+        errors[1].1
+    );
+    assert!(errors[2].1.contains("incomplete"), "{}", errors[2].1);
+}
+
+#[test]
+fn test_read_mgf_document_with_spec_type() {
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "SPECTYPE=SINGLE SCAN",
+        "MSLEVEL=1",
+        "CHARGE=1+",
+        "RTINSECONDS=37.083",
+        "SCANS=-1",
+        "381.0795 7.8E8",
+        "END IONS",
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "MSLEVEL=2",
+        "CHARGE=1+",
+        "RTINSECONDS=37.083",
+        "SCANS=1",
+        "60.5425 2.4E5",
+        "END IONS",
+    ])
+    .unwrap();
+
+    let mascot_generic_format = &mascot_generic_formats[0];
+    assert_eq!(
+        mascot_generic_format
+            .get_level(FragmentationSpectraLevel::One)
+            .unwrap()
+            .spec_type(),
+        Some("SINGLE SCAN")
+    );
+    assert_eq!(
+        mascot_generic_format
+            .get_level(FragmentationSpectraLevel::Two)
+            .unwrap()
+            .spec_type(),
+        None
+    );
+
+    // The spectrum type is written back as it was read.
+    let mgf = mascot_generic_format.to_mgf_string(DEFAULT_MGF_PRECISION);
+    assert!(mgf.lines().any(|line| line == "SPECTYPE=SINGLE SCAN"));
+    assert!(!mgf.lines().any(|line| line == "SPECTYPE=CORRELATED MS"));
+
+    let reloaded: MGFVec<usize, f64> = MGFVec::try_from_iter(mgf.lines()).unwrap();
+    assert_eq!(&reloaded[0], mascot_generic_format);
+}
+
+#[test]
+fn test_read_mgf_document_with_derived_precursor() {
+    // The entry has no PEPMASS field, so that the parent ion mass is derived
+    // from the minimum mass divided by charge ratio of the first level.
+    let document = [
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "SPECTYPE=CORRELATED MS",
+        "MSLEVEL=1",
+        "CHARGE=1+",
+        "RTINSECONDS=37.083",
+        "SCANS=-1",
+        "420.5 3.3E5",
+        "381.0795 7.8E8",
+        "END IONS",
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "MSLEVEL=2",
+        "CHARGE=1+",
+        "RTINSECONDS=37.083",
+        "SCANS=1",
+        "60.5425 2.4E5",
+        "END IONS",
+    ];
+    let options = ParseOptions::default().with_derive_missing_parent_ion_mass(true);
+    let mascot_generic_formats: MGFVec<usize, f64> =
+        MGFVec::try_from_iter_with_options(document, options).unwrap();
+
+    assert_eq!(mascot_generic_formats.len(), 1);
+    assert_eq!(mascot_generic_formats[0].precursor_mz(), 381.0795);
+
+    // By default, the PEPMASS field is required.
+    let mut mascot_generic_format_builder = MascotGenericFormatBuilder::<usize, f64>::default();
+    mascot_generic_format_builder
+        .digest_lines(document)
+        .unwrap();
+    assert!(!mascot_generic_format_builder.can_build());
+    assert!(mascot_generic_format_builder.build().is_err());
+
+    // Without a first level, the parent ion mass cannot be derived.
+    let mut mascot_generic_format_builder =
+        MascotGenericFormatBuilder::<usize, f64>::with_options(options);
+    mascot_generic_format_builder
+        .digest_lines([
+            "BEGIN IONS",
+            "FEATURE_ID=1",
+            "MSLEVEL=2",
+            "CHARGE=1+",
+            "RTINSECONDS=37.083",
+            "60.5425 2.4E5",
+            "END IONS",
+        ])
+        .unwrap();
+    assert!(!mascot_generic_format_builder.can_build());
+    assert!(mascot_generic_format_builder.build().is_err());
+}
+
+#[test]
+fn test_check_ion_mode_consistency() {
+    let entry = |ion_mode: &str, charge: &str| {
+        MGFVec::<usize, f64>::try_from_iter([
+            "BEGIN IONS",
+            "FEATURE_ID=1",
+            "PEPMASS=381.0795",
+            ion_mode,
+            charge,
+            "RTINSECONDS=37.083",
+            "MSLEVEL=2",
+            "60.5425 2.4E5",
+            "END IONS",
+        ])
+        .unwrap()[0]
+            .clone()
+    };
+
+    let consistent = entry("IONMODE=Negative", "CHARGE=1-");
+    assert_eq!(consistent.ion_mode_consistent(), Some(true));
+
+    let inconsistent = entry("IONMODE=Positive", "CHARGE=1-");
+    assert_eq!(inconsistent.ion_mode_consistent(), Some(false));
+    assert_eq!(
+        inconsistent.ion_mode().unwrap().opposite(),
+        IonMode::Negative
+    );
+
+    // A charge without an explicit sign does not imply a polarity.
+    let unsigned = entry("IONMODE=Positive", "CHARGE=1");
+    assert_eq!(unsigned.ion_mode_consistent(), None);
+}
+
+#[test]
+fn test_read_mgf_document_with_charge_from_adduct() {
+    let document = |charge: Option<&'static str>| {
+        let mut lines = vec![
+            "BEGIN IONS",
+            "FEATURE_ID=1",
+            "PEPMASS=381.0795",
+            "ADDUCT=[M-H]-",
+            "RTINSECONDS=37.083",
+            "MSLEVEL=2",
+            "60.5425 2.4E5",
+            "END IONS",
+        ];
+        if let Some(charge) = charge {
+            lines.insert(3, charge);
+        }
+        lines
+    };
+    let options = ParseOptions::default().with_infer_charge_from_adduct(true);
+
+    let mascot_generic_formats: MGFVec<usize, f64> =
+        MGFVec::try_from_iter_with_options(document(None), options).unwrap();
+    assert_eq!(mascot_generic_formats[0].charge(), Charge::OneMinus);
+    assert_eq!(
+        mascot_generic_formats[0].extra().get("ADDUCT"),
+        Some(&"[M-H]-".to_string())
+    );
+
+    // An explicit charge is authoritative, as long as it agrees with the adduct.
+    let mascot_generic_formats: MGFVec<usize, f64> =
+        MGFVec::try_from_iter_with_options(document(Some("CHARGE=1")), options).unwrap();
+    assert_eq!(mascot_generic_formats[0].charge(), Charge::One);
+    assert!(
+        MGFVec::<usize, f64>::try_from_iter_with_options(document(Some("CHARGE=1+")), options)
+            .is_err()
+    );
+
+    // Without the option, the adduct is not recognized by the strict parse options.
+    assert!(MGFVec::<usize, f64>::try_from_iter(document(None)).is_err());
+}
+
+#[test]
+fn test_rescale_retention_times() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    let original: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+
+    let mut rescaled = original.clone();
+    rescaled
+        .try_map(|mascot_generic_format| {
+            mascot_generic_format.map_retention_time(|retention_time| retention_time * 1.05)
+        })
+        .unwrap();
+
+    for (rescaled, original) in rescaled.iter().zip(original.iter()) {
+        assert_eq!(rescaled.retention_time(), original.retention_time() * 1.05);
+        assert_eq!(rescaled.levels().count(), original.levels().count());
+    }
+
+    // A correction yielding a non-positive retention time is rejected.
+    let mut mascot_generic_format = original[0].clone();
+    assert!(mascot_generic_format
+        .map_retention_time(|retention_time| retention_time - 1000.0)
+        .is_err());
+    assert_eq!(mascot_generic_format, original[0]);
+
+    mascot_generic_format.set_retention_time(12.5).unwrap();
+    assert_eq!(mascot_generic_format.retention_time(), 12.5);
+}
+
+#[test]
+fn test_dedup_merge_by_feature_id_failure_leaves_the_vector_unchanged() {
+    let mascot_generic_format = |parent_ion_mass: f64| {
+        MascotGenericFormat::new(
+            MascotGenericFormatMetadata::<usize, f64>::new(
+                1,
+                parent_ion_mass,
+                37.083,
+                Charge::One,
+                None,
+                None,
+            )
+            .unwrap(),
+            vec![MascotGenericFormatData::new(
+                FragmentationSpectraLevel::One,
+                vec![parent_ion_mass, 420.5],
+                vec![2.4E5, 3.3E5],
+            )
+            .unwrap()],
+        )
+        .unwrap()
+    };
+
+    let mut mascot_generic_formats = MGFVec::new();
+    mascot_generic_formats.push(mascot_generic_format(381.0795));
+    mascot_generic_formats.push(mascot_generic_format(300.0));
+    let original = mascot_generic_formats.clone();
+
+    // The merged first level starts below the parent ion mass of the first occurrence.
+    assert!(mascot_generic_formats
+        .dedup_merge_by_feature_id(Some(DEFAULT_RELATIVE_TOLERANCE))
+        .is_err());
+    assert_eq!(mascot_generic_formats.as_slice(), original.as_slice());
+
+    mascot_generic_formats
+        .dedup_merge_by_feature_id(None)
+        .unwrap();
+    assert_eq!(mascot_generic_formats.len(), 1);
+    assert_eq!(mascot_generic_formats[0].precursor_mz(), 381.0795);
+    assert_eq!(
+        mascot_generic_formats[0].num_peaks(FragmentationSpectraLevel::One),
+        Some(4)
+    );
+}
+
+#[test]
+fn test_new_rejects_missing_levels() {
+    let metadata = MascotGenericFormatMetadata::<usize, f64>::new(
+        1,
+        381.0795,
+        37.083,
+        Charge::One,
+        None,
+        None,
+    )
+    .unwrap();
+
+    assert!(MascotGenericFormat::new(metadata.clone(), vec![]).is_err());
+    assert!(MascotGenericFormat::new_with_precursor_tolerance(metadata, vec![], None).is_err());
+}
+
+#[test]
+fn test_new_rejects_repeated_levels() {
+    let metadata = MascotGenericFormatMetadata::<usize, f64>::new(
+        1,
+        381.0795,
+        37.083,
+        Charge::One,
+        None,
+        None,
+    )
+    .unwrap();
+    let second_level = MascotGenericFormatData::new(
+        FragmentationSpectraLevel::Two,
+        vec![60.5425, 119.0857],
+        vec![2.4E5, 3.3E5],
+    )
+    .unwrap();
+
+    assert!(MascotGenericFormat::new(
+        metadata.clone(),
+        vec![second_level.clone(), second_level.clone()]
+    )
+    .is_err());
+    assert!(MascotGenericFormat::new_with_precursor_tolerance(
+        metadata.clone(),
+        vec![second_level.clone(), second_level.clone()],
+        None
+    )
+    .is_err());
+
+    let mascot_generic_format = MascotGenericFormat::new(metadata, vec![second_level]).unwrap();
+    assert_eq!(
+        mascot_generic_format.max_fragmentation_level(),
+        FragmentationSpectraLevel::Two
+    );
+}
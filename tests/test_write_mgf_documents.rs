@@ -0,0 +1,220 @@
+/// Test to write MGF documents and read them back.
+use mascot_rs::prelude::*;
+
+#[test]
+fn test_write_mgf_document_with_default_precision() {
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter([
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "60.5425 2.4E5",
+        "119.0857 3.3E5",
+        "END IONS",
+    ])
+    .unwrap();
+
+    let mgf = mascot_generic_formats[0].to_mgf_string(DEFAULT_MGF_PRECISION);
+
+    assert!(mgf.lines().any(|line| line == "PEPMASS=381.0795"));
+    assert!(mgf.lines().any(|line| line == "RTINSECONDS=37.0830"));
+    assert!(mgf.lines().any(|line| line == "60.5425 240000.0000"));
+    assert!(mgf.lines().any(|line| line == "119.0857 330000.0000"));
+}
+
+#[test]
+fn test_write_mgf_document_round_trip() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+
+    let mut mgf = Vec::new();
+    // A precision large enough to write the exact decimal expansion of the values.
+    mascot_generic_formats.write_to(&mut mgf, 40).unwrap();
+    let mgf = String::from_utf8(mgf).unwrap();
+
+    let reloaded: MGFVec<usize, f64> = MGFVec::try_from_iter(mgf.lines()).unwrap();
+
+    assert_eq!(reloaded.as_slice(), mascot_generic_formats.as_slice());
+}
+
+#[test]
+fn test_write_mgf_document_round_trip_with_default_precision() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+
+    let mut mgf = Vec::new();
+    mascot_generic_formats
+        .write_to(&mut mgf, DEFAULT_MGF_PRECISION)
+        .unwrap();
+    let mgf = String::from_utf8(mgf).unwrap();
+
+    let reloaded: MGFVec<usize, f64> = MGFVec::try_from_iter(mgf.lines()).unwrap();
+
+    assert_eq!(reloaded.len(), mascot_generic_formats.len());
+    for (left, right) in reloaded.iter().zip(mascot_generic_formats.iter()) {
+        // Values written with four decimals differ by at most half a unit of the last decimal.
+        assert!(
+            left.approx_eq(right, 1e-4, 1e-4),
+            "{:?} != {:?}",
+            left,
+            right
+        );
+    }
+}
+
+#[test]
+fn test_write_summary_csv() {
+    let path = "tests/data/20220513_PMA_DBGI_01_04_001.mzML_chromatograms_deconvoluted_deisotoped_filtered_enpkg_sirius.mgf";
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::from_path(path).unwrap();
+
+    assert_eq!(
+        mascot_generic_formats.summary_iter().count(),
+        mascot_generic_formats.len()
+    );
+
+    let mut csv = Vec::new();
+    mascot_generic_formats.to_summary_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+
+    assert_eq!(csv.lines().count(), mascot_generic_formats.len() + 1);
+    for (line, (feature_id, precursor_mz, _)) in csv
+        .lines()
+        .skip(1)
+        .zip(mascot_generic_formats.summary_iter())
+    {
+        assert!(line.starts_with(&format!("{},{},", feature_id, precursor_mz)));
+    }
+}
+
+#[test]
+fn test_write_mgf_document_preserving_field_order() {
+    let document = concat!(
+        "BEGIN IONS\n",
+        "FEATURE_ID=1\n",
+        "PEPMASS=381.0795\n",
+        "SPECTYPE=CORRELATED MS\n",
+        "MSLEVEL=1\n",
+        "CHARGE=1+\n",
+        "RTINSECONDS=37.0830\n",
+        "SCANS=-1\n",
+        "381.0795 780000000.0000\n",
+        "END IONS\n",
+        "\n",
+        "BEGIN IONS\n",
+        "FEATURE_ID=1\n",
+        "CHARGE=1+\n",
+        "PEPMASS=381.0795\n",
+        "MSLEVEL=2\n",
+        "RTINSECONDS=37.0830\n",
+        "SCANS=1\n",
+        "MERGED_SCANS=1567,1540\n",
+        "MERGED_STATS=2 / 2 (0 removed due to low quality, 0 removed due to low cosine).\n",
+        "60.5425 240000.0000\n",
+        "119.0857 330000.0000\n",
+        "END IONS\n",
+        "\n",
+    );
+
+    let mascot_generic_formats: MGFVec<usize, f64> = MGFVec::try_from_iter_with_options(
+        document.lines(),
+        ParseOptions::default().with_preserve_field_order(true),
+    )
+    .unwrap();
+
+    let mut mgf = Vec::new();
+    mascot_generic_formats
+        .write_to(&mut mgf, DEFAULT_MGF_PRECISION)
+        .unwrap();
+    assert_eq!(String::from_utf8(mgf).unwrap(), document);
+
+    // Edited values are written in the position of their field.
+    let mut edited = mascot_generic_formats.clone();
+    edited[0].set_retention_time(99.0).unwrap();
+    let mut mgf = Vec::new();
+    edited.write_to(&mut mgf, DEFAULT_MGF_PRECISION).unwrap();
+    assert_eq!(
+        String::from_utf8(mgf).unwrap(),
+        document.replace("RTINSECONDS=37.0830", "RTINSECONDS=99.0000")
+    );
+    assert_ne!(edited[0], mascot_generic_formats[0]);
+
+    // The values are written with the requested precision.
+    let mgf = mascot_generic_formats[0].to_mgf_string(2);
+    assert!(mgf.contains("FEATURE_ID=1\nPEPMASS=381.08\nSPECTYPE=CORRELATED MS\n"));
+    assert!(mgf.contains("RTINSECONDS=37.08\n"));
+
+    // Without the option, the fields are written in the fixed order.
+    let mascot_generic_formats_in_fixed_order: MGFVec<usize, f64> =
+        MGFVec::try_from_iter(document.lines()).unwrap();
+    assert_ne!(
+        mascot_generic_formats_in_fixed_order[0].to_mgf_string(DEFAULT_MGF_PRECISION),
+        document
+    );
+    // The order of the fields does not take part in the equality.
+    assert_eq!(
+        mascot_generic_formats_in_fixed_order[0],
+        mascot_generic_formats[0]
+    );
+}
+
+#[test]
+fn test_write_mgf_document_round_trip_with_extra_fields() {
+    let document = [
+        "BEGIN IONS",
+        "FEATURE_ID=1",
+        "PEPMASS=381.0795",
+        "CHARGE=1-",
+        "ADDUCT=[M-H]-",
+        "RTINSECONDS=37.083",
+        "MSLEVEL=2",
+        "60.5425 2.4E5",
+        "END IONS",
+    ];
+
+    // The adduct is retained, and read back, when inferring the charge from it.
+    let options = ParseOptions::default().with_infer_charge_from_adduct(true);
+    let mascot_generic_formats: MGFVec<usize, f64> =
+        MGFVec::try_from_iter_with_options(document, options).unwrap();
+    let mgf = mascot_generic_formats[0].to_mgf_string(DEFAULT_MGF_PRECISION);
+    let reloaded: MGFVec<usize, f64> =
+        MGFVec::try_from_iter_with_options(mgf.lines(), options).unwrap();
+    assert_eq!(reloaded.as_slice(), mascot_generic_formats.as_slice());
+
+    // Other extra fields are only retained, and read back, by the lenient parse options.
+    let mut lines = document.to_vec();
+    lines[4] = "SOURCE_INSTRUMENT=LC-ESI-qTof";
+    assert!(MGFVec::<usize, f64>::try_from_iter(&lines).is_err());
+    let mascot_generic_formats: MGFVec<usize, f64> =
+        MGFVec::try_from_iter_with_options(&lines, ParseOptions::lenient()).unwrap();
+    let mgf = mascot_generic_formats[0].to_mgf_string(DEFAULT_MGF_PRECISION);
+    assert!(MGFVec::<usize, f64>::try_from_iter(mgf.lines()).is_err());
+    let reloaded: MGFVec<usize, f64> =
+        MGFVec::try_from_iter_with_options(mgf.lines(), ParseOptions::lenient()).unwrap();
+    assert_eq!(reloaded.as_slice(), mascot_generic_formats.as_slice());
+}
+
+#[test]
+fn test_write_msp_entry_round_trip() {
+    let msp = [
+        "Name: 1",
+        "PrecursorMZ: 381.0795",
+        "Num Peaks: 2",
+        "60.5425 240000",
+        "119.0857 330000",
+        "",
+    ];
+
+    // Without a retention time and a charge, the entry is only read when
+    // zero values are allowed, and it is then read back with the same options.
+    assert!(MGFVec::<usize, f64>::try_from_msp_iter(msp).is_err());
+    let options = ParseOptions::default().with_allow_zero_values(true);
+    let mascot_generic_formats: MGFVec<usize, f64> =
+        MGFVec::try_from_msp_iter_with_options(msp, options).unwrap();
+    let mgf = mascot_generic_formats[0].to_mgf_string(DEFAULT_MGF_PRECISION);
+    assert!(MGFVec::<usize, f64>::try_from_iter(mgf.lines()).is_err());
+    let reloaded: MGFVec<usize, f64> =
+        MGFVec::try_from_iter_with_options(mgf.lines(), options).unwrap();
+    assert_eq!(reloaded.as_slice(), mascot_generic_formats.as_slice());
+}